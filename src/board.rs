@@ -0,0 +1,360 @@
+//! Compact board encoding, for when many positions must be kept in memory (e.g. by the solver).
+
+use crate::{Rules, Slot, State};
+
+/// Bits used to store the ball count of a column (capacity is at most 20).
+const HEIGHT_BITS: usize = 5;
+
+/// Room between the rows of a hexagonal board, in slots, for the labels of the first row.
+const HEX_ROW_GAP: f32 = 1.0;
+
+/// How the columns of a board are arranged, which decides where a ball can go.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// Columns side by side, a ball going from any column to any other.
+    #[default]
+    Grid,
+
+    /// Columns in two rows of hexagonal cells, the second one offset by half a column, numbered
+    /// in a zigzag from one row to the other. A ball only goes to the adjacent cells: the column
+    /// on either side in the same row, and the two columns it touches in the other row, which
+    /// are the columns within two places of its own.
+    Hex,
+}
+
+impl Topology {
+    /// Whether a ball can go from `from_column` to `to_column`, room aside.
+    pub fn connects(self, from_column: usize, to_column: usize) -> bool {
+        match self {
+            Topology::Grid => from_column != to_column,
+            Topology::Hex => (1..=2).contains(&from_column.abs_diff(to_column)),
+        }
+    }
+
+    /// Whether the columns can be reordered without changing which moves are possible.
+    fn is_symmetric(self) -> bool {
+        self == Topology::Grid
+    }
+
+    /// Top left corner of `column`, in slots from the top left corner of the board.
+    pub fn column_origin(self, column: usize, column_capacity: usize) -> egui::Vec2 {
+        match self {
+            Topology::Grid => egui::vec2(column as f32, 0.0),
+            Topology::Hex => {
+                let row = (column % 2) as f32;
+                egui::vec2(
+                    (column / 2) as f32 + row / 2.0,
+                    row * (column_capacity as f32 + HEX_ROW_GAP),
+                )
+            }
+        }
+    }
+
+    /// Size of the board, in slots.
+    pub fn board_size(self, column_count: usize, column_capacity: usize) -> egui::Vec2 {
+        (0..column_count)
+            .map(|column| self.column_origin(column, column_capacity))
+            .fold(egui::Vec2::ZERO, egui::Vec2::max)
+            + egui::vec2(1.0, column_capacity as f32)
+    }
+}
+
+/// What a finished column looks like.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Goal {
+    /// Balls of a single color.
+    #[default]
+    Colors,
+
+    /// Balls numbered from 1 at the bottom to the capacity at the top, the ball at each level
+    /// being stored as its number minus one.
+    Numbers,
+}
+
+/// A board position packed into a few machine words.
+///
+/// Each column is stored as its ball count followed by its balls from the bottom up, at 4 bits per
+/// ball (5 bits past 16 colors). Slots above the top ball are always zero, so two boards are equal
+/// exactly when they hold the same balls in the same columns and the encoding can be hashed as is.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PackedBoard {
+    topology: Topology,
+    goal: Goal,
+    rules: Rules,
+    column_count: u8,
+    column_capacity: u8,
+    ball_bits: u8,
+    words: Box<[u64]>,
+}
+
+impl PackedBoard {
+    pub fn column_count(&self) -> usize {
+        self.column_count as usize
+    }
+
+    pub fn column_capacity(&self) -> usize {
+        self.column_capacity as usize
+    }
+
+    fn column_bits(&self) -> usize {
+        HEIGHT_BITS + self.column_capacity() * self.ball_bits as usize
+    }
+
+    fn column_offset(&self, column: usize) -> usize {
+        column * self.column_bits()
+    }
+
+    fn ball_offset(&self, column: usize, level: usize) -> usize {
+        self.column_offset(column) + HEIGHT_BITS + level * self.ball_bits as usize
+    }
+
+    fn get(&self, offset: usize, width: usize) -> usize {
+        let (word, shift) = (offset / 64, offset % 64);
+        let mut value = self.words[word] >> shift;
+        if shift + width > 64 {
+            value |= self.words[word + 1] << (64 - shift);
+        }
+        (value & ((1 << width) - 1)) as usize
+    }
+
+    fn set(&mut self, offset: usize, width: usize, value: usize) {
+        let mask = (1u64 << width) - 1;
+        let value = value as u64 & mask;
+        let (word, shift) = (offset / 64, offset % 64);
+        self.words[word] = (self.words[word] & !(mask << shift)) | (value << shift);
+        if shift + width > 64 {
+            let spill = 64 - shift;
+            self.words[word + 1] = (self.words[word + 1] & !(mask >> spill)) | (value >> spill);
+        }
+    }
+
+    /// The whole encoding of `column`, which fits in 128 bits since it's at most 105 bits long.
+    fn column(&self, column: usize) -> u128 {
+        let offset = self.column_offset(column);
+        (0..self.column_bits())
+            .step_by(32)
+            .map(|chunk| {
+                let width = 32.min(self.column_bits() - chunk);
+                (self.get(offset + chunk, width) as u128) << chunk
+            })
+            .sum()
+    }
+
+    fn set_column(&mut self, column: usize, value: u128) {
+        let offset = self.column_offset(column);
+        for chunk in (0..self.column_bits()).step_by(32) {
+            let width = 32.min(self.column_bits() - chunk);
+            self.set(offset + chunk, width, (value >> chunk) as usize);
+        }
+    }
+
+    /// Same position with the columns sorted. Column order doesn't matter to which positions can
+    /// be reached and how quickly, so boards differing only by it share the same canonical form.
+    /// That's only true of a grid: other boards are their own canonical form.
+    pub fn canonical(&self) -> Self {
+        if !self.topology.is_symmetric() {
+            return self.clone();
+        }
+
+        let mut columns: Vec<_> = (0..self.column_count())
+            .map(|column| self.column(column))
+            .collect();
+        columns.sort_unstable();
+
+        let mut board = self.clone();
+        for (column, value) in columns.into_iter().enumerate() {
+            board.set_column(column, value);
+        }
+        board
+    }
+
+    /// Number of balls in `column`.
+    pub fn height(&self, column: usize) -> usize {
+        self.get(self.column_offset(column), HEIGHT_BITS)
+    }
+
+    /// Color of the ball at `level` in `column`, counting from the bottom.
+    pub fn ball(&self, column: usize, level: usize) -> usize {
+        self.get(self.ball_offset(column, level), self.ball_bits as usize)
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn goal(&self) -> Goal {
+        self.goal
+    }
+
+    /// Number of balls at the bottom of `column` already where they'll be once it's finished:
+    /// sharing the color of the bottom one, or numbered in order from 1.
+    pub fn base_run(&self, column: usize) -> usize {
+        let height = self.height(column);
+        if height == 0 {
+            return 0;
+        }
+        let in_place = |level: usize| match self.goal {
+            Goal::Colors => self.ball(column, level) == self.ball(column, 0),
+            Goal::Numbers => self.ball(column, level) == level,
+        };
+        (0..height)
+            .find(|&level| !in_place(level))
+            .unwrap_or(height)
+    }
+
+    pub fn can_move(&self, from_column: usize, to_column: usize) -> bool {
+        self.height(from_column) > 0
+            && self.height(to_column) < self.column_capacity()
+            && self
+                .rules
+                .rule_set()
+                .allows_move(self, from_column, to_column)
+    }
+
+    /// Move the top ball of `from_column` to `to_column`, which must be possible.
+    pub fn apply_move(&mut self, from_column: usize, to_column: usize) {
+        debug_assert!(self.can_move(from_column, to_column));
+
+        let ball_bits = self.ball_bits as usize;
+        let from_height = self.height(from_column);
+        let to_height = self.height(to_column);
+        let ball = self.ball(from_column, from_height - 1);
+
+        self.set(self.ball_offset(from_column, from_height - 1), ball_bits, 0);
+        self.set(
+            self.column_offset(from_column),
+            HEIGHT_BITS,
+            from_height - 1,
+        );
+        self.set(self.ball_offset(to_column, to_height), ball_bits, ball);
+        self.set(self.column_offset(to_column), HEIGHT_BITS, to_height + 1);
+    }
+
+    /// Same rule as [`State::is_winning`]: every column is either empty or full of one color.
+    pub fn is_solved(&self) -> bool {
+        let rule_set = self.rules.rule_set();
+        (0..self.column_count()).all(|column| rule_set.is_column_finished(self, column))
+    }
+}
+
+impl From<&State> for PackedBoard {
+    fn from(state: &State) -> Self {
+        let color_count = state
+            .slots
+            .iter()
+            .filter_map(|slot| match slot {
+                Slot::Ball(color) => Some(color + 1),
+                Slot::Empty => None,
+            })
+            .max()
+            .unwrap_or(0);
+        let ball_bits = if color_count <= 16 { 4 } else { 5 };
+        let bit_count = state.column_count * (HEIGHT_BITS + state.column_capacity * ball_bits);
+
+        let mut board = Self {
+            topology: state.mode.topology(),
+            goal: state.mode.goal(),
+            rules: state.rules,
+            column_count: state.column_count as u8,
+            column_capacity: state.column_capacity as u8,
+            ball_bits: ball_bits as u8,
+            words: vec![0; bit_count.div_ceil(64)].into_boxed_slice(),
+        };
+
+        for column in 0..state.column_count {
+            // row 0 is the top of the column
+            let balls: Vec<_> = (0..state.column_capacity)
+                .rev()
+                .map_while(|row| match state.slot(row, column) {
+                    Slot::Ball(color) => Some(color),
+                    Slot::Empty => None,
+                })
+                .collect();
+            for (level, &ball) in balls.iter().enumerate() {
+                board.set(board.ball_offset(column, level), ball_bits, ball);
+            }
+            board.set(board.column_offset(column), HEIGHT_BITS, balls.len());
+        }
+
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mode;
+
+    /// Three colors in columns of two balls, each column listed from the top, the last one empty.
+    const CODES: [u8; 8] = [2, 1, 3, 2, 1, 3, 0, 0];
+
+    fn board(codes: &[u8]) -> PackedBoard {
+        let state = State::from_codes(4, 2, Goal::Colors, codes).unwrap();
+        PackedBoard::from(&state)
+    }
+
+    #[test]
+    fn get_and_set_round_trip_across_words() {
+        let mut board = PackedBoard::from(&State::from_seed(6, 4, 1));
+        assert_eq!(board.words.len(), 2);
+
+        let fields = [
+            (0, 5, 17),
+            (5, 4, 9),
+            (58, 6, 45),
+            (64, 3, 5),
+            (100, 20, 0xabcde),
+        ];
+        for &(offset, width, value) in &fields {
+            board.set(offset, width, value);
+        }
+        for &(offset, width, value) in &fields {
+            assert_eq!(board.get(offset, width), value, "at {offset}");
+        }
+
+        board.set(58, 6, 0);
+        assert_eq!(board.get(58, 6), 0);
+        assert_eq!(board.get(5, 4), 9);
+        assert_eq!(board.get(64, 3), 5);
+    }
+
+    #[test]
+    fn packs_the_balls_of_a_game() {
+        let state = State::from_seed(8, 5, 3);
+        let board = PackedBoard::from(&state);
+        for column in 0..state.column_count {
+            let balls: Vec<_> = (0..state.column_capacity)
+                .rev()
+                .map_while(|row| match state.slot(row, column) {
+                    Slot::Ball(color) => Some(color),
+                    Slot::Empty => None,
+                })
+                .collect();
+            assert_eq!(board.height(column), balls.len());
+            for (level, &ball) in balls.iter().enumerate() {
+                assert_eq!(board.ball(column, level), ball);
+            }
+        }
+    }
+
+    #[test]
+    fn column_order_shares_a_canonical_form() {
+        let swapped = [3, 2, 2, 1, 0, 0, 1, 3];
+        let (board, swapped) = (board(&CODES), board(&swapped));
+        assert_ne!(board, swapped);
+        assert_eq!(board.canonical(), swapped.canonical());
+        assert_eq!(board.canonical().canonical(), board.canonical());
+
+        let mut moved = board.clone();
+        moved.apply_move(0, 3);
+        assert_ne!(moved.canonical(), board.canonical());
+    }
+
+    #[test]
+    fn hex_boards_keep_their_column_order() {
+        let mut state = State::from_codes(4, 2, Goal::Colors, &CODES).unwrap();
+        state.mode = Mode::Hex;
+        let board = PackedBoard::from(&state);
+        assert_eq!(board.canonical(), board);
+    }
+}
@@ -0,0 +1,169 @@
+//! Opt-in spectator broadcast: the game being played is streamed over TCP, so that another
+//! machine, an overlay or a stream dashboard can draw it live. Each message is a line of JSON,
+//! a snapshot of the board sent whenever it changes, and spectators connecting get the latest one
+//! right away.
+
+use std::io::Write as _;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::{Move, Slot, State};
+
+/// Port listened on unless set otherwise.
+pub const DEFAULT_PORT: u16 = 4717;
+
+/// How often new spectators are let in.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Spectators that can't keep up for this long are dropped, rather than holding up the others.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct BroadcastSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for BroadcastSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+/// What spectators, and remote control clients, are told about the game.
+#[derive(serde::Serialize)]
+pub struct Snapshot {
+    /// Tab index of the game.
+    game: usize,
+    column_count: usize,
+    column_capacity: usize,
+
+    /// Slot codes, column by column from the top (see [`Slot::code`]).
+    slots: Vec<u8>,
+    play_count: usize,
+    elapsed_seconds: f32,
+    paused: bool,
+    won: bool,
+
+    /// Source and destination columns of the move that led to the board, if it was played.
+    #[serde(rename = "move")]
+    last_move: Option<[usize; 2]>,
+}
+
+impl Snapshot {
+    pub fn new(game: usize, state: &State, last_move: Option<Move>) -> Self {
+        Self {
+            game,
+            column_count: state.column_count,
+            column_capacity: state.column_capacity,
+            slots: state
+                .slots
+                .iter()
+                .map(|slot| slot.code().unwrap_or_default())
+                .collect(),
+            play_count: state.play_count,
+            elapsed_seconds: state.elapsed.as_secs_f32(),
+            paused: state.paused,
+            won: state.is_winning().is_some(),
+            last_move: last_move.map(|mv| [mv.from, mv.to]),
+        }
+    }
+}
+
+/// Listening for spectators, with the thread sending them snapshots.
+pub struct Broadcaster {
+    port: u16,
+
+    /// Snapshot lines for the sending thread, which stops once this is dropped.
+    sender: mpsc::Sender<String>,
+
+    /// Tab index, move count, pause and board last sent.
+    sent: Option<(usize, usize, bool, Vec<Slot>)>,
+}
+
+impl Broadcaster {
+    /// Listen for spectators on `port`, on every network interface.
+    pub fn start(port: u16) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        listener.set_nonblocking(true)?;
+        let (sender, receiver) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("broadcast".to_owned())
+            .spawn(move || serve(&listener, &receiver))?;
+        log::info!("broadcasting to spectators on port {port}");
+        Ok(Self {
+            port,
+            sender,
+            sent: None,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Send the board of game `index` if it changed since last time, along with the move that led
+    /// to it.
+    pub fn send_game(&mut self, index: usize, state: &State) {
+        let previous = match &self.sent {
+            Some((sent_index, play_count, paused, slots))
+                if *sent_index == index
+                    && *play_count == state.play_count
+                    && *paused == state.paused
+                    && *slots == state.slots =>
+            {
+                return;
+            }
+            Some((sent_index, play_count, ..)) if *sent_index == index => Some(*play_count),
+            _ => None,
+        };
+        self.sent = Some((index, state.play_count, state.paused, state.slots.clone()));
+
+        let played = previous.is_some_and(|count| state.play_count > count);
+        let last_move = state.moves.last().copied().filter(|_| played);
+        let snapshot = Snapshot::new(index, state, last_move);
+        match serde_json::to_string(&snapshot) {
+            Ok(line) => {
+                self.sender.send(line + "\n").ok();
+            }
+            Err(err) => log::warn!("failed to serialize the board for spectators: {err}"),
+        }
+    }
+}
+
+/// Let spectators in and send them each line received, until the sender is dropped.
+fn serve(listener: &TcpListener, receiver: &mpsc::Receiver<String>) {
+    let mut spectators: Vec<TcpStream> = Vec::new();
+    let mut latest = None;
+    loop {
+        match receiver.recv_timeout(ACCEPT_INTERVAL) {
+            Ok(line) => {
+                spectators.retain_mut(|spectator| spectator.write_all(line.as_bytes()).is_ok());
+                latest = Some(line);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        }
+
+        while let Ok((mut spectator, address)) = listener.accept() {
+            log::info!("spectator connected from {address}");
+            // accepted sockets may inherit the listener's non-blocking mode
+            if spectator.set_nonblocking(false).is_err()
+                || spectator.set_write_timeout(Some(WRITE_TIMEOUT)).is_err()
+            {
+                continue;
+            }
+            let caught_up = latest
+                .as_ref()
+                .is_none_or(|line: &String| spectator.write_all(line.as_bytes()).is_ok());
+            if caught_up {
+                spectators.push(spectator);
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! IndexedDB storage for bulky data on the web, where eframe's storage is backed by localStorage
+//! and limited to a few megabytes.
+
+use std::{cell::RefCell, rc::Rc};
+
+use eframe::wasm_bindgen::JsValue;
+use rexie::{ObjectStore, Rexie, TransactionMode};
+
+const DATABASE: &str = "boule";
+const STORE: &str = "data";
+
+async fn open() -> rexie::Result<Rexie> {
+    Rexie::builder(DATABASE)
+        .version(1)
+        .add_object_store(ObjectStore::new(STORE))
+        .build()
+        .await
+}
+
+async fn get(key: &str) -> rexie::Result<Option<String>> {
+    let database = open().await?;
+    let transaction = database.transaction(&[STORE], TransactionMode::ReadOnly)?;
+    let value = transaction
+        .store(STORE)?
+        .get(JsValue::from_str(key))
+        .await?;
+    transaction.done().await?;
+    Ok(value.and_then(|value| value.as_string()))
+}
+
+async fn put(key: &str, value: &str) -> rexie::Result<()> {
+    let database = open().await?;
+    let transaction = database.transaction(&[STORE], TransactionMode::ReadWrite)?;
+    transaction
+        .store(STORE)?
+        .put(&JsValue::from_str(value), Some(&JsValue::from_str(key)))
+        .await?;
+    transaction.done().await?;
+    Ok(())
+}
+
+/// Outcome of reading a key: its value, `None` if it's missing, or what went wrong.
+pub type Value = Result<Option<String>, String>;
+
+/// Values being read from storage.
+pub struct PendingLoad(Rc<RefCell<Option<Vec<Value>>>>);
+
+impl PendingLoad {
+    /// The values once all read, in the order of their keys.
+    pub fn poll(&self) -> Option<Vec<Value>> {
+        self.0.borrow_mut().take()
+    }
+}
+
+/// Start reading `keys`, repainting `ctx` once done.
+pub fn load(keys: Vec<String>, ctx: egui::Context) -> PendingLoad {
+    let result = Rc::new(RefCell::new(None));
+    let pending = PendingLoad(result.clone());
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            let value = get(&key).await.map_err(|err| {
+                log::warn!("failed to read {key} from IndexedDB: {err}");
+                err.to_string()
+            });
+            values.push(value);
+        }
+        *result.borrow_mut() = Some(values);
+        ctx.request_repaint();
+    });
+    pending
+}
+
+/// Write each value to its key in the background, then clear `replaced` if they were all written.
+pub fn save_all(values: Vec<(String, String)>, replaced: Option<String>) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut written = true;
+        for (key, value) in values {
+            if let Err(err) = put(&key, &value).await {
+                log::warn!("failed to write {key} to IndexedDB: {err}");
+                written = false;
+            }
+        }
+        if let Some(key) = replaced.filter(|_| written) {
+            match put(&key, "").await {
+                Ok(()) => log::info!("cleared {key} from IndexedDB"),
+                Err(err) => log::warn!("failed to clear {key} from IndexedDB: {err}"),
+            }
+        }
+    });
+}
+
+/// Write `value` to `key` in the background.
+pub fn save(key: String, value: String) {
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(err) = put(&key, &value).await {
+            log::warn!("failed to write {key} to IndexedDB: {err}");
+        }
+    });
+}
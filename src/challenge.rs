@@ -0,0 +1,123 @@
+//! Challenges: a puzzle along with the result of whoever sent it, packed into a string to paste
+//! to a friend, who then plays the very same puzzle and gets compared once done.
+
+use base64::Engine as _;
+
+use crate::State;
+
+/// Start of every challenge string, so that it's recognizable once pasted.
+const PREFIX: &str = "boule-challenge:";
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Challenge {
+    pub column_count: usize,
+    pub column_capacity: usize,
+
+    /// Starting arrangement as slot codes, column by column.
+    slots: Vec<u8>,
+
+    /// Who sent the challenge, and their result.
+    pub nickname: String,
+    pub moves: usize,
+    pub seconds: f32,
+}
+
+impl Challenge {
+    /// Challenge a friend to beat `state`, which must be won.
+    pub fn from_game(state: &State, nickname: &str) -> Option<Self> {
+        let moves = state.is_winning()?;
+        Some(Self {
+            column_count: state.column_count,
+            column_capacity: state.column_capacity,
+            slots: state.initial_codes()?,
+            nickname: nickname.trim().to_owned(),
+            moves,
+            seconds: state.elapsed.as_secs_f32(),
+        })
+    }
+
+    /// The puzzle of the challenge, ready to be played, if it holds together.
+    pub fn game(&self) -> Option<State> {
+        let mut state = State::from_codes(
+            self.column_count,
+            self.column_capacity,
+            crate::board::Goal::Colors,
+            &self.slots,
+        )?;
+        state.challenge = Some(self.clone());
+        Some(state)
+    }
+
+    pub fn encode(&self) -> String {
+        let text = ron::to_string(self).unwrap_or_default();
+        let code = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(text);
+        format!("{PREFIX}{code}")
+    }
+
+    /// Challenge from a pasted string, tolerating surrounding text and whitespace, if its time
+    /// makes sense.
+    pub fn decode(text: &str) -> Option<Self> {
+        let code = text.split(PREFIX).nth(1)?.split_whitespace().next()?;
+        let text = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(code)
+            .ok()?;
+        ron::de::from_bytes(&text)
+            .ok()
+            .filter(|challenge: &Self| challenge.duration().is_some())
+    }
+
+    /// Time of whoever sent the challenge, unless it's negative or too large to be one.
+    pub fn duration(&self) -> Option<std::time::Duration> {
+        std::time::Duration::try_from_secs_f32(self.seconds).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenge() -> Challenge {
+        Challenge {
+            column_count: 4,
+            column_capacity: 3,
+            slots: vec![2, 1, 3, 3, 2, 1, 1, 3, 2, 0, 0, 0],
+            nickname: "Ada".to_owned(),
+            moves: 12,
+            seconds: 42.5,
+        }
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let challenge = challenge();
+        assert_eq!(Challenge::decode(&challenge.encode()), Some(challenge));
+    }
+
+    #[test]
+    fn decode_ignores_surrounding_text() {
+        let challenge = challenge();
+        let text = format!("Beat this:\n  {}  \nGood luck!", challenge.encode());
+        assert_eq!(Challenge::decode(&text), Some(challenge));
+    }
+
+    #[test]
+    fn decode_rejects_invalid_times_and_garbage() {
+        let challenge = Challenge {
+            seconds: -1.0,
+            ..challenge()
+        };
+        assert_eq!(Challenge::decode(&challenge.encode()), None);
+        assert_eq!(Challenge::decode("boule-challenge:not base64!"), None);
+        assert_eq!(Challenge::decode("no challenge here"), None);
+    }
+
+    #[test]
+    fn decoded_challenge_plays_its_puzzle() {
+        let challenge = challenge();
+        let state = Challenge::decode(&challenge.encode())
+            .and_then(|challenge| challenge.game())
+            .unwrap();
+        assert_eq!(state.initial_codes(), Some(challenge.slots.clone()));
+        assert_eq!(state.challenge, Some(challenge));
+    }
+}
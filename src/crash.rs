@@ -0,0 +1,104 @@
+//! Crash recovery: a panic hook stores the panic report along with the game being played, so the
+//! next launch can bring the game back.
+
+use std::sync::Mutex;
+
+use crate::State;
+
+/// Game being played and its tab index, as of the last save.
+static SNAPSHOT: Mutex<Option<(usize, State)>> = Mutex::new(None);
+
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct CrashReport {
+    /// Panic message, location and backtrace, if available.
+    pub report: String,
+
+    /// Game being played when the crash happened, and its tab index.
+    pub game: Option<(usize, State)>,
+}
+
+/// Remember the game being played, should the app crash.
+pub fn set_snapshot(game: Option<(usize, State)>) {
+    if let Ok(mut snapshot) = SNAPSHOT.lock() {
+        *snapshot = game;
+    }
+}
+
+/// Write a crash report on panic, then run the previous hook.
+pub fn install_panic_hook() {
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        // the panic may have happened while the snapshot was being updated
+        let game = SNAPSHOT
+            .try_lock()
+            .ok()
+            .and_then(|snapshot| snapshot.clone());
+
+        let mut report = panic_info.to_string();
+        if let Some((_, state)) = &game {
+            report.push_str(&format!("\n\n{}", state.to_ascii()));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        report.push_str(&format!(
+            "\n\n{}",
+            std::backtrace::Backtrace::force_capture()
+        ));
+        if let Ok(text) = ron::to_string(&CrashReport { report, game }) {
+            write(&text);
+        }
+
+        previous_hook(panic_info);
+    }));
+}
+
+/// Crash report left by the previous run, if any. It's removed from storage.
+pub fn take_report() -> Option<CrashReport> {
+    let text = take()?;
+    ron::from_str(&text)
+        .map_err(|err| log::warn!("failed to read crash report: {err}"))
+        .ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn report_path() -> Option<std::path::PathBuf> {
+    eframe::storage_dir(crate::APP_ID).map(|dir| dir.join("crash.ron"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(text: &str) {
+    if let Some(path) = report_path() {
+        std::fs::create_dir_all(path.parent().unwrap_or(&path)).ok();
+        std::fs::write(path, text).ok();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn take() -> Option<String> {
+    let path = report_path()?;
+    let text = std::fs::read_to_string(&path).ok()?;
+    std::fs::remove_file(&path).ok();
+    Some(text)
+}
+
+#[cfg(target_arch = "wasm32")]
+const STORAGE_KEY: &str = "boule_crash";
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<eframe::web_sys::Storage> {
+    eframe::web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(text: &str) {
+    if let Some(storage) = local_storage() {
+        storage.set_item(STORAGE_KEY, text).ok();
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn take() -> Option<String> {
+    let storage = local_storage()?;
+    let text = storage.get_item(STORAGE_KEY).ok()??;
+    storage.remove_item(STORAGE_KEY).ok();
+    Some(text)
+}
@@ -0,0 +1,77 @@
+//! Daily puzzle: one board a day, the same for everyone, with past days still playable from a
+//! calendar.
+
+use crate::State;
+
+/// Colors and height of the daily puzzles.
+pub const PUZZLE: (usize, usize) = (6, 6);
+
+/// Days since 1970-01-01, UTC.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+pub struct Day(u64);
+
+impl Day {
+    /// Day of a Unix timestamp.
+    pub fn of(timestamp: u64) -> Self {
+        Self(timestamp / 86_400)
+    }
+
+    /// ISO 8601 date, e.g. `2024-05-17`.
+    pub fn label(self) -> String {
+        crate::export::format_date(self.0 * 86_400)
+    }
+
+    pub fn day_of_month(self) -> u64 {
+        crate::export::civil_date(self.0 * 86_400).2
+    }
+
+    /// 0 for Monday to 6 for Sunday, 1970-01-01 being a Thursday.
+    pub fn weekday(self) -> u64 {
+        (self.0 + 3) % 7
+    }
+
+    pub fn month(self) -> Month {
+        Month(Day(self.0 + 1 - self.day_of_month()))
+    }
+
+    /// The day's puzzle, ready to be played.
+    pub fn game(self) -> State {
+        let (colors, height) = PUZZLE;
+        // offset so that the boards differ from the weekly challenge's
+        let mut state = State::from_seed(colors + 1, height, crate::mix_seed(!self.0));
+        state.daily = Some(self);
+        state
+    }
+}
+
+/// Month of the calendar, as its first day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Month(Day);
+
+impl Month {
+    pub fn first(self) -> Day {
+        self.0
+    }
+
+    /// Year and month, e.g. `2024-05`.
+    pub fn label(self) -> String {
+        let (year, month, _) = crate::export::civil_date(self.0 .0 * 86_400);
+        format!("{year}-{month:02}")
+    }
+
+    pub fn previous(self) -> Self {
+        Day(self.0 .0.saturating_sub(1)).month()
+    }
+
+    pub fn next(self) -> Self {
+        // months are 28 to 31 days long
+        Day(self.0 .0 + 31).month()
+    }
+
+    pub fn days(self) -> impl Iterator<Item = Day> {
+        let next = self.next().first().0;
+        (self.0 .0..next).map(Day)
+    }
+}
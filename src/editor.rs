@@ -0,0 +1,246 @@
+//! Puzzle editor, where balls are stacked into columns by hand before the puzzle is played or
+//! shared as a code.
+
+use egui::{vec2, Sense};
+
+use crate::{i18n::Msg, puzzle::Puzzle, BallStyle, Slot};
+
+/// Size of a slot in the editor, as on the board.
+const SLOT_SIZE: f32 = 30.0;
+
+pub enum Outcome {
+    Play(Box<crate::State>),
+    Export(Puzzle),
+    Close,
+}
+
+pub struct Editor {
+    /// One more than the colors, as on the setup screen.
+    column_count: usize,
+    column_capacity: usize,
+
+    /// Colors of the balls of each column, bottom first.
+    columns: Vec<Vec<usize>>,
+
+    /// Color dropped by clicking a column.
+    color: usize,
+
+    /// Whether the puzzle code was just copied.
+    copied: bool,
+
+    name: String,
+    author: String,
+    difficulty: Option<u8>,
+}
+
+impl Editor {
+    pub fn new(column_count: usize, column_capacity: usize) -> Self {
+        Self {
+            column_count,
+            column_capacity,
+            columns: vec![Vec::new(); column_count],
+            color: 0,
+            copied: false,
+            name: String::new(),
+            author: String::new(),
+            difficulty: None,
+        }
+    }
+
+    /// Take all the balls out, keeping the details of the puzzle.
+    fn clear(&mut self) {
+        self.columns = vec![Vec::new(); self.column_count];
+        self.color = 0;
+        self.copied = false;
+    }
+
+    fn color_count(&self) -> usize {
+        self.column_count - 1
+    }
+
+    /// Balls of `color` placed so far.
+    fn count(&self, color: usize) -> usize {
+        self.columns
+            .iter()
+            .flatten()
+            .filter(|&&c| c == color)
+            .count()
+    }
+
+    fn balls_left(&self) -> usize {
+        self.color_count() * self.column_capacity - self.columns.iter().map(Vec::len).sum::<usize>()
+    }
+
+    /// Slot at `row` of `column`, row 0 being the top as on the board.
+    fn slot(&self, row: usize, column: usize) -> Slot {
+        let level = self.column_capacity - 1 - row;
+        self.columns[column]
+            .get(level)
+            .map_or(Slot::Empty, |&color| Slot::Ball(color))
+    }
+
+    /// The puzzle, once every ball is placed.
+    pub fn puzzle(&self) -> Option<Puzzle> {
+        if self.balls_left() > 0 {
+            return None;
+        }
+        let codes = (0..self.column_count)
+            .flat_map(|column| (0..self.column_capacity).map(move |row| (row, column)))
+            .map(|(row, column)| self.slot(row, column).code())
+            .collect::<Option<Vec<_>>>()?;
+        let mut puzzle = Puzzle::new(self.column_count, self.column_capacity, codes);
+        puzzle.name = self.name.trim().to_owned();
+        puzzle.author = self.author.trim().to_owned();
+        puzzle.difficulty = self.difficulty;
+        puzzle.game().is_some().then_some(puzzle)
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<Outcome> {
+        let mut outcome = None;
+
+        ui.strong(Msg::Colors);
+        let mut color_count = self.color_count();
+        let mut changed =
+            crate::selectable_label_range(ui, 3..=BallStyle::MAX_STYLES, &mut color_count);
+        ui.strong(Msg::Height);
+        let mut column_capacity = self.column_capacity;
+        changed |=
+            crate::selectable_label_range(ui, 2..=crate::MAX_COLUMN_CAPACITY, &mut column_capacity);
+        if changed {
+            self.column_count = color_count + 1;
+            self.column_capacity = column_capacity;
+            self.clear();
+        }
+        ui.add_space(6.0);
+
+        // palette, with the balls left of each color underneath
+        ui.with_layout(
+            crate::i18n::horizontal_layout().with_main_wrap(true),
+            |ui| {
+                for color in 0..self.color_count() {
+                    ui.vertical(|ui| {
+                        let (rect, response) =
+                            ui.allocate_exact_size(vec2(SLOT_SIZE, SLOT_SIZE), Sense::click());
+                        Slot::Ball(color)
+                            .color(ui.ctx())
+                            .paint(ui.painter(), rect.center(), 1.0);
+                        if self.color == color {
+                            ui.painter().rect_stroke(
+                                rect,
+                                6.0,
+                                egui::Stroke::new(2.0, ui.visuals().selection.stroke.color),
+                            );
+                        }
+                        if response.clicked() {
+                            self.color = color;
+                        }
+                        let left = self.column_capacity - self.count(color);
+                        ui.small(left.to_string());
+                    });
+                }
+            },
+        );
+        ui.add_space(6.0);
+
+        let size = vec2(
+            SLOT_SIZE * self.column_count as f32,
+            SLOT_SIZE * self.column_capacity as f32,
+        );
+        let (board_rect, _) = ui.allocate_exact_size(size, Sense::hover());
+        for column in 0..self.column_count {
+            let column_rect = egui::Rect::from_min_size(
+                board_rect.min + vec2(SLOT_SIZE * column as f32, 0.0),
+                vec2(SLOT_SIZE, board_rect.height()),
+            );
+            let response = ui.interact(
+                column_rect,
+                ui.id().with(("editor", column)),
+                Sense::click(),
+            );
+            if response.clicked()
+                && self.columns[column].len() < self.column_capacity
+                && self.count(self.color) < self.column_capacity
+            {
+                self.columns[column].push(self.color);
+                self.copied = false;
+            }
+            if response.secondary_clicked() && self.columns[column].pop().is_some() {
+                self.copied = false;
+            }
+            for row in 0..self.column_capacity {
+                let center = column_rect.min
+                    + vec2(SLOT_SIZE / 2.0, SLOT_SIZE * row as f32 + SLOT_SIZE / 2.0);
+                self.slot(row, column)
+                    .color(ui.ctx())
+                    .paint(ui.painter(), center, 1.0);
+            }
+        }
+        ui.small(Msg::EditorHelp);
+        ui.add_space(6.0);
+
+        egui::Grid::new("puzzle_details")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(Msg::PuzzleName);
+                ui.text_edit_singleline(&mut self.name);
+                ui.end_row();
+                ui.label(Msg::PuzzleAuthor);
+                ui.text_edit_singleline(&mut self.author);
+                ui.end_row();
+                ui.label(Msg::Difficulty);
+                ui.with_layout(crate::i18n::horizontal_layout(), |ui| {
+                    ui.selectable_value(&mut self.difficulty, None, "—");
+                    for difficulty in 1..=crate::puzzle::MAX_DIFFICULTY {
+                        ui.selectable_value(
+                            &mut self.difficulty,
+                            Some(difficulty),
+                            difficulty.to_string(),
+                        );
+                    }
+                });
+                ui.end_row();
+            });
+        ui.add_space(6.0);
+
+        let puzzle = self.puzzle();
+        if puzzle.is_none() {
+            ui.label(Msg::BallsLeft(self.balls_left()));
+        }
+        if self.copied {
+            ui.label(Msg::PuzzleCopied);
+        }
+        ui.with_layout(crate::i18n::horizontal_layout(), |ui| {
+            let game = puzzle.as_ref().and_then(Puzzle::game);
+            if ui
+                .add_enabled(game.is_some(), egui::Button::new(Msg::Play))
+                .clicked()
+            {
+                outcome = game.map(|game| Outcome::Play(Box::new(game)));
+            }
+            if ui
+                .add_enabled(puzzle.is_some(), egui::Button::new(Msg::CopyPuzzle))
+                .clicked()
+            {
+                if let Some(puzzle) = &puzzle {
+                    ui.ctx()
+                        .output_mut(|output| output.copied_text = puzzle.encode());
+                    self.copied = true;
+                }
+            }
+            if ui
+                .add_enabled(puzzle.is_some(), egui::Button::new(Msg::ExportPuzzle))
+                .clicked()
+            {
+                outcome = puzzle.clone().map(Outcome::Export);
+            }
+            if ui.button(Msg::ClearPuzzle).clicked() {
+                self.clear();
+            }
+            if ui.button(Msg::Cancel).clicked() {
+                outcome = Some(Outcome::Close);
+            }
+        });
+
+        outcome
+    }
+}
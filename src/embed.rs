@@ -0,0 +1,140 @@
+//! JavaScript API of the web build, so that other sites can embed the game on a canvas of their
+//! own and script it. Trunk puts the functions on `window.wasmBindings`:
+//!
+//! ```js
+//! wasmBindings.mount("my_canvas");
+//! wasmBindings.newGame('{"column_count": 8, "column_capacity": 4, "mode": "Hex"}', 42);
+//! wasmBindings.onWin((moves) => console.log(`won in ${moves} moves`));
+//! const game = JSON.parse(wasmBindings.currentState());
+//! ```
+//!
+//! The page shipped with the game has a canvas with id [`DEFAULT_CANVAS_ID`], which the game
+//! mounts itself on.
+
+use std::cell::RefCell;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{BallStyle, BouleApp, GameConfig, Mode, State};
+
+/// Canvas the game mounts itself on when the page has one.
+pub const DEFAULT_CANVAS_ID: &str = "the_canvas_id";
+
+/// What the page asked for and what it may ask about.
+#[derive(Default)]
+struct Embedding {
+    /// Context of the running app, to wake it up when a game is asked for.
+    ctx: Option<egui::Context>,
+
+    /// Games asked for and not started yet, with their seed.
+    new_games: Vec<(GameConfig, u64)>,
+
+    /// Functions called with the move count whenever a game is won.
+    win_listeners: Vec<js_sys::Function>,
+
+    /// Game being played, as of the last frame.
+    current: Option<State>,
+}
+
+thread_local! {
+    static EMBEDDING: RefCell<Embedding> = RefCell::new(Embedding::default());
+}
+
+/// Whether the page has an element with the given id.
+pub fn has_element(id: &str) -> bool {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(id))
+        .is_some()
+}
+
+/// Run the game on the canvas with the given id. It's meant to be called once per page.
+#[wasm_bindgen]
+pub fn mount(canvas_id: String) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let started = eframe::WebRunner::new()
+            .start(
+                &canvas_id,
+                eframe::WebOptions::default(),
+                Box::new(|cc| {
+                    EMBEDDING.with_borrow_mut(|embedding| {
+                        embedding.ctx = Some(cc.egui_ctx.clone());
+                    });
+                    Box::new(BouleApp::new(cc))
+                }),
+            )
+            .await;
+        if let Err(err) = started {
+            log::error!("failed to start on canvas {canvas_id}: {err:?}");
+        }
+    });
+}
+
+/// Start a game set up by `config`, given as JSON such as
+/// `{"column_count": 8, "column_capacity": 4}` with optional `empty_columns`, `rules` and `mode`.
+/// The balls are shuffled from `seed`, an integer, or at random without one.
+#[wasm_bindgen(js_name = newGame)]
+pub fn new_game(config: &str, seed: Option<f64>) -> Result<(), JsError> {
+    let config: GameConfig = serde_json::from_str(config)?;
+    if !is_playable(&config) {
+        return Err(JsError::new(&format!(
+            "can't play {} columns of {} balls",
+            config.column_count, config.column_capacity
+        )));
+    }
+    let seed = seed.map_or_else(rand::random, |seed| seed as u64);
+    EMBEDDING.with_borrow_mut(|embedding| {
+        embedding.new_games.push((config, seed));
+        if let Some(ctx) = &embedding.ctx {
+            ctx.request_repaint();
+        }
+    });
+    Ok(())
+}
+
+/// Whether the setup screen could set up such a board.
+fn is_playable(config: &GameConfig) -> bool {
+    (3..=BallStyle::MAX_STYLES).contains(&config.colors())
+        && (2..=crate::MAX_COLUMN_CAPACITY).contains(&config.column_capacity)
+        && (1..=2).contains(&config.empty_columns)
+        && config.mode != Mode::Kid
+}
+
+/// Call `callback` with the number of moves whenever a game is won.
+#[wasm_bindgen(js_name = onWin)]
+pub fn on_win(callback: js_sys::Function) {
+    EMBEDDING.with_borrow_mut(|embedding| embedding.win_listeners.push(callback));
+}
+
+/// Game being played as JSON, or `undefined` when there is none.
+#[wasm_bindgen(js_name = currentState)]
+pub fn current_state() -> Result<Option<String>, JsError> {
+    EMBEDDING.with_borrow(|embedding| {
+        Ok(embedding
+            .current
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?)
+    })
+}
+
+/// Games asked for since the last call, with their seed.
+pub fn take_new_games() -> Vec<(GameConfig, u64)> {
+    EMBEDDING.with_borrow_mut(|embedding| std::mem::take(&mut embedding.new_games))
+}
+
+/// Remember the game being played for [`current_state`].
+pub fn set_current(state: Option<&State>) {
+    EMBEDDING.with_borrow_mut(|embedding| embedding.current = state.cloned());
+}
+
+/// Tell the page that a game was won in `play_count` moves.
+pub fn notify_win(play_count: usize) {
+    // listeners may call back into the API
+    let listeners = EMBEDDING.with_borrow(|embedding| embedding.win_listeners.clone());
+    for listener in listeners {
+        if let Err(err) = listener.call1(&JsValue::NULL, &JsValue::from(play_count)) {
+            log::warn!("win listener failed: {err:?}");
+        }
+    }
+}
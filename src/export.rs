@@ -0,0 +1,121 @@
+//! History export as CSV, one row per finished game, for players who'd rather analyze their games
+//! in a spreadsheet.
+
+use std::fmt::Write as _;
+
+use crate::{GameRecord, Verification};
+
+pub const FILE_NAME: &str = "boule-history.csv";
+
+pub fn history_csv(records: &[GameRecord]) -> String {
+    let mut csv =
+        "colors,height,empty_columns,rules,mode,moves,duration_seconds,finished,seed,hints,undos,\
+         verified,note\n"
+            .to_owned();
+    for record in records {
+        let config = record.config();
+        let duration = record
+            .duration
+            .map(|duration| format!("{:.1}", duration.as_secs_f32()))
+            .unwrap_or_default();
+        let finished = record.finished.map(format_timestamp).unwrap_or_default();
+        let seed = record.seed.map(|seed| seed.to_string()).unwrap_or_default();
+        // replayed, so that the export can be trusted even if the history got damaged
+        let verified = match record.verify() {
+            Verification::Verified => "yes",
+            Verification::Failed => "no",
+            Verification::Unverifiable => "",
+        };
+        writeln!(
+            csv,
+            "{},{},{},{:?},{:?},{},{duration},{finished},{seed},{},{},{verified},{}",
+            config.colors(),
+            config.column_capacity,
+            config.empty_columns,
+            config.rules,
+            config.mode,
+            record.moves,
+            record.hints,
+            record.undos,
+            quoted(&record.note),
+        )
+        .ok();
+    }
+    csv
+}
+
+/// CSV field for free text, quoted when it has to be.
+fn quoted(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+/// ISO 8601 UTC date and time of a Unix timestamp, e.g. `2024-05-17T21:04:33Z`.
+fn format_timestamp(timestamp: u64) -> String {
+    let seconds = timestamp % 86_400;
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        format_date(timestamp),
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// ISO 8601 UTC date of a Unix timestamp, e.g. `2024-05-17`.
+pub fn format_date(timestamp: u64) -> String {
+    let (year, month, day) = civil_date(timestamp);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// UTC year, month and day of a Unix timestamp.
+pub fn civil_date(timestamp: u64) -> (u64, u64, u64) {
+    let days = timestamp / 86_400;
+
+    // civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Have the browser download `contents` as a file named `name`, of media type `mime`.
+#[cfg(target_arch = "wasm32")]
+pub fn download(
+    name: &str,
+    mime: &str,
+    contents: &str,
+) -> Result<(), eframe::wasm_bindgen::JsValue> {
+    use eframe::wasm_bindgen::JsCast as _;
+
+    let parts = js_sys::Array::of1(&contents.into());
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+    let anchor: web_sys::HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url)
+}
@@ -0,0 +1,51 @@
+//! Boards starred to be played again, with the best result on each.
+
+use crate::{puzzle::Puzzle, State};
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Favorite {
+    /// Starting arrangement and rules.
+    pub puzzle: Puzzle,
+
+    /// Seed the board was shuffled from, if it was, kept by replays.
+    pub seed: Option<u64>,
+
+    /// Fewest moves it was won in.
+    pub best: Option<usize>,
+
+    /// Unix timestamp of when it was starred.
+    pub added: u64,
+
+    /// Written by the player, e.g. on how to start.
+    #[serde(default)]
+    pub note: String,
+}
+
+impl Favorite {
+    pub fn of_game(state: &State, added: u64) -> Option<Self> {
+        Some(Self {
+            puzzle: Puzzle::of_game(state)?,
+            seed: state.seed,
+            best: state.is_winning(),
+            added,
+            note: String::new(),
+        })
+    }
+
+    /// The board, ready to be played again.
+    pub fn game(&self) -> Option<State> {
+        let mut state = self.puzzle.game()?;
+        state.seed = self.seed;
+        Some(state)
+    }
+
+    pub fn record(&mut self, moves: usize) {
+        self.best = Some(self.best.map_or(moves, |best| best.min(moves)));
+    }
+}
+
+/// Whether the board `state` started from is among `favorites`.
+pub fn is_starred(favorites: &[Favorite], state: &State) -> bool {
+    Puzzle::of_game(state)
+        .is_some_and(|puzzle| favorites.iter().any(|favorite| favorite.puzzle == puzzle))
+}
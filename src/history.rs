@@ -0,0 +1,117 @@
+//! Filtering and sorting of the won games listed with the statistics.
+
+use crate::{i18n::Msg, GameRecord, Mode};
+
+/// How far back won games are listed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Period {
+    #[default]
+    AllTime,
+    LastWeek,
+    LastMonth,
+    LastYear,
+}
+
+impl Period {
+    pub const ALL: &'static [Period] = &[
+        Period::AllTime,
+        Period::LastWeek,
+        Period::LastMonth,
+        Period::LastYear,
+    ];
+
+    pub fn label(self) -> Msg {
+        match self {
+            Period::AllTime => Msg::AllTime,
+            Period::LastWeek => Msg::LastWeek,
+            Period::LastMonth => Msg::LastMonth,
+            Period::LastYear => Msg::LastYear,
+        }
+    }
+
+    fn seconds(self) -> Option<u64> {
+        const DAY: u64 = 86_400;
+        match self {
+            Period::AllTime => None,
+            Period::LastWeek => Some(7 * DAY),
+            Period::LastMonth => Some(30 * DAY),
+            Period::LastYear => Some(365 * DAY),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Recent,
+    Moves,
+    Duration,
+}
+
+impl SortOrder {
+    pub const ALL: &'static [SortOrder] =
+        &[SortOrder::Recent, SortOrder::Moves, SortOrder::Duration];
+
+    pub fn label(self) -> Msg {
+        match self {
+            SortOrder::Recent => Msg::SortRecent,
+            SortOrder::Moves => Msg::SortMoves,
+            SortOrder::Duration => Msg::SortDuration,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HistoryFilter {
+    pub period: Period,
+
+    /// Every mode if `None`.
+    pub mode: Option<Mode>,
+
+    /// Colors and height of the boards listed, every board if `None`.
+    pub board: Option<(usize, usize)>,
+
+    pub sort: SortOrder,
+}
+
+impl HistoryFilter {
+    /// Indices of the records passing the filter, in the selected order. Games won before
+    /// records were dated are only listed for [`Period::AllTime`], and come last when sorting by
+    /// recency, as do games of unknown duration when sorting by duration.
+    pub fn apply(&self, records: &[GameRecord], now: u64) -> Vec<usize> {
+        let since = self
+            .period
+            .seconds()
+            .map(|seconds| now.saturating_sub(seconds));
+        let mut listed: Vec<_> = (0..records.len())
+            .rev()
+            .filter(|&index| {
+                since.is_none_or(|since| {
+                    records[index]
+                        .finished
+                        .is_some_and(|finished| finished >= since)
+                })
+            })
+            .filter(|&index| self.mode.is_none_or(|mode| records[index].mode == mode))
+            .filter(|&index| {
+                self.board
+                    .is_none_or(|board| records[index].board() == board)
+            })
+            .collect();
+
+        // stable sorts, so that ties keep the most recent first
+        match self.sort {
+            SortOrder::Recent => {
+                listed.sort_by_key(|&index| std::cmp::Reverse(records[index].finished));
+            }
+            SortOrder::Moves => listed.sort_by_key(|&index| records[index].moves),
+            SortOrder::Duration => {
+                listed.sort_by_key(|&index| {
+                    let duration = records[index].duration;
+                    (duration.is_none(), duration)
+                });
+            }
+        }
+        listed
+    }
+}
@@ -0,0 +1,1582 @@
+//! Tiny translation layer.
+//!
+//! Every user-facing string is a [`Msg`] variant, rendered in the currently selected [`Language`]
+//! through its `Display` implementation. Keeping the catalog as a `match` means a missing
+//! translation is a compile error rather than a runtime fallback.
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: &'static [Language] = &[Language::English, Language::French];
+
+    /// Name of the language, in that language.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+
+    pub fn is_right_to_left(self) -> bool {
+        match self {
+            Language::English | Language::French => false,
+        }
+    }
+}
+
+/// User override for the horizontal direction of the UI.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum LayoutDirection {
+    /// Follow the selected language.
+    #[default]
+    Auto,
+    LeftToRight,
+    RightToLeft,
+}
+
+impl LayoutDirection {
+    pub const ALL: &'static [LayoutDirection] = &[
+        LayoutDirection::Auto,
+        LayoutDirection::LeftToRight,
+        LayoutDirection::RightToLeft,
+    ];
+
+    pub fn is_right_to_left(self, language: Language) -> bool {
+        match self {
+            LayoutDirection::Auto => language.is_right_to_left(),
+            LayoutDirection::LeftToRight => false,
+            LayoutDirection::RightToLeft => true,
+        }
+    }
+
+    pub fn label(self) -> Msg {
+        match self {
+            LayoutDirection::Auto => Msg::DirectionAuto,
+            LayoutDirection::LeftToRight => Msg::LeftToRight,
+            LayoutDirection::RightToLeft => Msg::RightToLeft,
+        }
+    }
+}
+
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(0);
+static RIGHT_TO_LEFT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_language(language: Language) {
+    CURRENT_LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match CURRENT_LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::French,
+        _ => Language::English,
+    }
+}
+
+pub fn set_right_to_left(right_to_left: bool) {
+    RIGHT_TO_LEFT.store(right_to_left, Ordering::Relaxed);
+}
+
+pub fn is_right_to_left() -> bool {
+    RIGHT_TO_LEFT.load(Ordering::Relaxed)
+}
+
+/// Horizontal layout following the current reading direction.
+pub fn horizontal_layout() -> egui::Layout {
+    if is_right_to_left() {
+        egui::Layout::right_to_left(egui::Align::TOP)
+    } else {
+        egui::Layout::left_to_right(egui::Align::TOP)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Msg {
+    Colors,
+    Height,
+    Play,
+    PlayAgain,
+    Abort,
+    UndoButton,
+    RedoButton,
+    HintButton(usize),
+    GameMenu,
+    EditMenu,
+    ViewMenu,
+    HelpMenu,
+    NewGameItem,
+    Restart,
+    AbortItem,
+    PauseItem,
+    Undo,
+    Redo,
+    Hint,
+    HintNotFound,
+    HintMove {
+        from: usize,
+        to: usize,
+    },
+    ColumnInfo {
+        column: usize,
+        balls: usize,
+    },
+    SearchingHint,
+    HintedWin(usize),
+    UndoneWin(usize),
+    PowerupWin(usize),
+    CleanWin,
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+    Theme,
+    ThemeSystem,
+    ThemeLight,
+    ThemeDark,
+    HowToPlay,
+    HowToPlayText,
+    About,
+    AboutText,
+    KeyboardShortcuts,
+    LogPanel,
+    MoveLog,
+    StartPosition,
+    CreatePuzzle,
+    PuzzleEditor,
+    EditorHelp,
+    BallsLeft(usize),
+    CopyPuzzle,
+    PuzzleCopied,
+    ClearPuzzle,
+    PuzzleName,
+    PuzzleAuthor,
+    Difficulty,
+    ExportPuzzle,
+    PuzzleExported,
+    DropPuzzle,
+    OpenPuzzle,
+    CheckingPuzzle,
+    Remix,
+    SummaryMoves,
+    Par,
+    Grade,
+    SummaryTime,
+    Undos,
+    HintsUsed,
+    TopTenRank,
+    TopTenPlace {
+        rank: usize,
+        behind: usize,
+        behind_best: usize,
+    },
+    TopTenBest,
+    Replay,
+    Favorites,
+    RecentlyPlayed,
+    WonIn(usize),
+    AbandonedAfter(usize),
+    Retry,
+    Seed(u64),
+    Note,
+    WonOn,
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    PlaysLike,
+    CrowdRated(usize),
+    SizeRated,
+    MaybeStuck,
+    LegalMoves(usize),
+    LegalMovesNote,
+    TryHarder {
+        colors: usize,
+        height: usize,
+        next_colors: usize,
+        next_height: usize,
+    },
+    TryEasier {
+        colors: usize,
+        height: usize,
+        next_colors: usize,
+        next_height: usize,
+    },
+    AddFavorite,
+    RemoveFavorite,
+    RemixNote,
+    Verify,
+    VerifyNote,
+    VerifiedWin,
+    VerificationFailed,
+    Unverifiable,
+    UnsolvablePuzzle,
+    PuzzleTooHard,
+    SkipCheck,
+    PlayAnyway,
+    DailyPuzzle,
+    PlayToday,
+    WeekdayInitials,
+    WeeklyChallenge,
+    WeeklyChallengeNote,
+    WeeklyScore {
+        completed: usize,
+        total: usize,
+        score: usize,
+    },
+    Points(usize),
+    ScrubberNote,
+    CopyLog,
+    DebugOverlay,
+    ExportCsv,
+    HistoryExported,
+    ExportFailed,
+    #[cfg(not(target_arch = "wasm32"))]
+    SaveFile,
+    #[cfg(not(target_arch = "wasm32"))]
+    DefaultSaveFile,
+    Sync,
+    SyncEnabled,
+    SyncUrl,
+    Username,
+    Password,
+    SyncNow,
+    SyncUpToDate,
+    SyncFailed,
+    Broadcast,
+    BroadcastText,
+    BroadcastEnabled,
+    Port,
+    Broadcasting(u16),
+    ListenFailed,
+    RemoteControl,
+    RemoteControlText,
+    RemoteControlEnabled,
+    RemoteControlListening(u16),
+    SyncConflict,
+    SyncConflictDetails {
+        server_newer: bool,
+    },
+    KeepLocal,
+    UseServer,
+    Leaderboard,
+    LeaderboardEnabled,
+    LeaderboardUrl,
+    Nickname,
+    Profile,
+    AvatarColor,
+    OtherProfiles,
+    NewProfile,
+    UnnamedProfile,
+    RemoveProfile,
+    RemoveProfileTitle,
+    RemoveProfileDetails,
+    WhoIsPlaying,
+    Challenge,
+    ChallengeCopied,
+    PasteChallenge,
+    AcceptChallenge,
+    InvalidChallenge,
+    ChallengeWon,
+    ChallengeLost,
+    ChallengeTied,
+    You,
+    StartWithBoard {
+        colors: usize,
+        height: usize,
+    },
+    NicknameNeeded,
+    SubmitScore,
+    ScoreSubmitted,
+    SubmitFailed,
+    Refresh,
+    NoScores,
+    LeaderboardUnavailable,
+    ReplayVerified,
+    GlobalAverage {
+        average: f32,
+        count: usize,
+    },
+    GlobalPercentile(usize),
+    UsageStatistics,
+    UsageStatisticsText,
+    SendUsageStatistics,
+    UsageStatisticsPreview,
+    Crashed,
+    GameRecovered,
+    CopyReport,
+    CorruptData,
+    CorruptDataNote,
+    ExportCorruptData,
+    CorruptDataExported,
+    Dismiss,
+    Ok,
+    #[cfg(feature = "puffin")]
+    Profiler,
+    TipDragBall,
+    TipUndo,
+    GotIt,
+    DragGesture,
+    DragGestureAction,
+    WheelGesture,
+    WheelGestureAction,
+    ShiftWheelGesture,
+    ShiftWheelGestureAction,
+    Pause,
+    Paused,
+    NewGame,
+    BackToGame,
+    UnfinishedGame,
+    UnfinishedGameDetails {
+        colors: usize,
+        height: usize,
+        moves: usize,
+    },
+    Overwrite,
+    ResumePrompt {
+        colors: usize,
+        height: usize,
+        moves: usize,
+    },
+    AbandonGame,
+    MovesPlayed(usize),
+    Abandon,
+    SaveForLater,
+    Cancel,
+    OtherGames,
+    GameSummary {
+        colors: usize,
+        height: usize,
+        moves: usize,
+    },
+    Resume,
+    Delete,
+    YouWon(usize),
+    TopTen {
+        colors: usize,
+        height: usize,
+        handicap: bool,
+    },
+    Attempts {
+        attempts: usize,
+        won_percent: usize,
+    },
+    Handicap,
+    HandicapNote,
+    Variant,
+    Powerups,
+    CountUndos,
+    CountUndosNote,
+    ClassicRules,
+    CountedUndoRules,
+    ScriptedRules,
+    ScriptedRulesNote,
+    RulesScript,
+    RulesScriptNote,
+    RulesScriptLoaded,
+    RulesScriptFailed,
+    RulesScriptNeeded,
+    PowerupsNote {
+        reshuffle_penalty: usize,
+        extra_column_penalty: usize,
+    },
+    Reshuffle,
+    ReshuffleButton(usize),
+    ExtraColumn,
+    ExtraColumnButton(usize),
+    ExtraColumnNote(usize),
+    ExtraColumnMovesLeft(usize),
+    EmptyExtraColumn,
+    PenaltyIncluded(usize),
+    RatingWin,
+    Stars(usize),
+    StarsEarned(usize),
+    Moves(usize),
+    UiScale,
+    Statistics,
+    NoStatistics,
+    Board,
+    Started,
+    Wins,
+    Abandoned,
+    Percent(usize),
+    Best,
+    PlayTime,
+    TimeSpent(std::time::Duration),
+    WonGames,
+    NoMatchingGames,
+    AllTime,
+    LastWeek,
+    LastMonth,
+    LastYear,
+    AllModes,
+    NormalMode,
+    KidMode,
+    HexMode,
+    HexModeNote,
+    NumbersMode,
+    NumbersModeNote,
+    KidModeButton,
+    WellDone,
+    AllBoards,
+    SortRecent,
+    SortMoves,
+    SortDuration,
+    Language,
+    LayoutDirection,
+    DirectionAuto,
+    LeftToRight,
+    RightToLeft,
+    MirrorBoard,
+    LeftHanded,
+    LeftHandedNote,
+    ColorSummary,
+    ColorSummaryNote,
+    CircularBoard,
+    CircularBoardNote,
+    MadeBy,
+    Source,
+}
+
+impl std::fmt::Display for Msg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match language() {
+            Language::English => match *self {
+                Msg::Colors => write!(f, "Colors"),
+                Msg::Height => write!(f, "Height"),
+                Msg::Play => write!(f, "PLAY"),
+                Msg::PlayAgain => write!(f, "PLAY AGAIN"),
+                Msg::Abort => write!(f, "ABORT"),
+                Msg::UndoButton => write!(f, "UNDO"),
+                Msg::RedoButton => write!(f, "REDO"),
+                Msg::HintButton(left) => write!(f, "HINT ({left})"),
+                Msg::GameMenu => write!(f, "Game"),
+                Msg::EditMenu => write!(f, "Edit"),
+                Msg::ViewMenu => write!(f, "View"),
+                Msg::HelpMenu => write!(f, "Help"),
+                Msg::NewGameItem => write!(f, "New game"),
+                Msg::Restart => write!(f, "Restart"),
+                Msg::AbortItem => write!(f, "Abort"),
+                Msg::PauseItem => write!(f, "Pause/resume"),
+                Msg::Undo => write!(f, "Undo"),
+                Msg::Redo => write!(f, "Redo"),
+                Msg::Hint => write!(f, "Hint"),
+                Msg::HintNotFound => write!(f, "No solution found from this position."),
+                Msg::HintMove { from, to } => {
+                    write!(f, "Hint: move {}", crate::Move { from, to })
+                }
+                Msg::ColumnInfo { column, balls } => write!(
+                    f,
+                    "Column {}, {balls} ball{}",
+                    crate::column_label(column),
+                    if balls == 1 { "" } else { "s" }
+                ),
+                Msg::SearchingHint => write!(f, "Looking for a hint…"),
+                Msg::HintedWin(hints) => {
+                    write!(
+                        f,
+                        "Won with {hints} hint{}",
+                        if hints == 1 { "" } else { "s" }
+                    )
+                }
+                Msg::UndoneWin(undos) => write!(
+                    f,
+                    "Won with {undos} undo{}",
+                    if undos == 1 { "" } else { "s" }
+                ),
+                Msg::PowerupWin(penalty) => write!(f, "Won with powerups (+{penalty} moves)"),
+                Msg::CleanWin => write!(f, "Won without hints, undos or powerups"),
+                Msg::ZoomIn => write!(f, "Zoom in"),
+                Msg::ZoomOut => write!(f, "Zoom out"),
+                Msg::ResetZoom => write!(f, "Reset zoom"),
+                Msg::Theme => write!(f, "Theme"),
+                Msg::ThemeSystem => write!(f, "System"),
+                Msg::ThemeLight => write!(f, "Light"),
+                Msg::ThemeDark => write!(f, "Dark"),
+                Msg::HowToPlay => write!(f, "How to play"),
+                Msg::HowToPlayText => write!(
+                    f,
+                    "Drag the top ball of a column onto another column that has room for it. \
+                    The game is won once every column holds balls of a single color."
+                ),
+                Msg::About => write!(f, "About"),
+                Msg::LogPanel => write!(f, "Log"),
+                Msg::MoveLog => write!(f, "Move log"),
+                Msg::StartPosition => write!(f, "Start"),
+                Msg::CreatePuzzle => write!(f, "Create a puzzle"),
+                Msg::PuzzleEditor => write!(f, "Puzzle editor"),
+                Msg::EditorHelp => write!(
+                    f,
+                    "Click a column to drop a ball of the selected color, right-click to take \
+                     its top ball back."
+                ),
+                Msg::BallsLeft(balls) => write!(f, "{balls} balls left to place."),
+                Msg::CopyPuzzle => write!(f, "Copy puzzle"),
+                Msg::PuzzleCopied => write!(
+                    f,
+                    "Puzzle copied to the clipboard. It can be pasted on the setup screen."
+                ),
+                Msg::ClearPuzzle => write!(f, "Clear"),
+                Msg::DropPuzzle => write!(f, "Drop a puzzle file to play it"),
+                Msg::OpenPuzzle => write!(f, "Open a puzzle"),
+                Msg::CheckingPuzzle => write!(f, "Checking that the puzzle can be solved…"),
+                Msg::UnsolvablePuzzle => write!(f, "This puzzle can't be solved."),
+                Msg::PuzzleTooHard => write!(
+                    f,
+                    "The solver gave up on this puzzle before finding a solution, though it may \
+                     well have one."
+                ),
+                Msg::SkipCheck => write!(f, "Play now"),
+                Msg::Remix => write!(f, "Remix"),
+                Msg::SummaryMoves => write!(f, "Moves"),
+                Msg::Par => write!(f, "Par"),
+                Msg::Grade => write!(f, "Grade"),
+                Msg::SummaryTime => write!(f, "Time"),
+                Msg::Undos => write!(f, "Undos"),
+                Msg::HintsUsed => write!(f, "Hints"),
+                Msg::TopTenRank => write!(f, "Top 10 rank"),
+                Msg::TopTenPlace {
+                    rank: 2,
+                    behind_best,
+                    ..
+                } => write!(f, "#2, {behind_best} moves behind your best"),
+                Msg::TopTenPlace {
+                    rank,
+                    behind,
+                    behind_best,
+                } => write!(
+                    f,
+                    "#{rank}, {behind} moves behind #{}, {behind_best} behind your best",
+                    rank - 1
+                ),
+                Msg::TopTenBest => write!(f, "#1, your best!"),
+                Msg::Replay => write!(f, "Replay"),
+                Msg::Favorites => write!(f, "Favorites"),
+                Msg::RecentlyPlayed => write!(f, "Recently played"),
+                Msg::WonIn(moves) => write!(f, "Won in {moves} moves"),
+                Msg::AbandonedAfter(moves) => write!(f, "Given up after {moves} moves"),
+                Msg::Retry => write!(f, "Retry"),
+                Msg::Seed(seed) => write!(f, "Seed {seed}"),
+                Msg::Note => write!(f, "Note"),
+                Msg::WonOn => write!(f, "Won on"),
+                Msg::Easy => write!(f, "Easy"),
+                Msg::Medium => write!(f, "Medium"),
+                Msg::Hard => write!(f, "Hard"),
+                Msg::Expert => write!(f, "Expert"),
+                Msg::PlaysLike => write!(f, "Plays like:"),
+                Msg::CrowdRated(scores) => write!(
+                    f,
+                    "Rated by how long players took to solve it, over {scores} leaderboard scores"
+                ),
+                Msg::SizeRated => write!(f, "Rated by its number of balls"),
+                Msg::TryHarder {
+                    colors,
+                    height,
+                    next_colors,
+                    next_height,
+                } => write!(
+                    f,
+                    "You're crushing {colors}x{height}, try {next_colors}x{next_height}!"
+                ),
+                Msg::TryEasier {
+                    colors,
+                    height,
+                    next_colors,
+                    next_height,
+                } => write!(
+                    f,
+                    "{colors}x{height} is a tough one, {next_colors}x{next_height} may be more fun."
+                ),
+                Msg::AddFavorite => write!(f, "Add this board to the favorites, to play it again"),
+                Msg::RemoveFavorite => write!(f, "Remove from the favorites"),
+                Msg::RemixNote => write!(
+                    f,
+                    "Play this board again with a few balls swapped, for a familiar but fresh \
+                     puzzle."
+                ),
+                Msg::Verify => write!(f, "Verify"),
+                Msg::VerifyNote => write!(
+                    f,
+                    "Replay the moves of this win from its board, to check that it wins in as \
+                     many moves."
+                ),
+                Msg::VerifiedWin => write!(f, "The moves replay to this win"),
+                Msg::VerificationFailed => write!(
+                    f,
+                    "The moves don't replay to this win, the history may be damaged"
+                ),
+                Msg::Unverifiable => write!(
+                    f,
+                    "This win can't be replayed: its board or moves weren't kept, or it took \
+                     powerups"
+                ),
+                Msg::PlayAnyway => write!(f, "Play anyway"),
+                Msg::PuzzleName => write!(f, "Name"),
+                Msg::PuzzleAuthor => write!(f, "Author"),
+                Msg::Difficulty => write!(f, "Difficulty"),
+                Msg::ExportPuzzle => write!(f, "Save as file"),
+                Msg::PuzzleExported => write!(
+                    f,
+                    "Puzzle saved to this file, which can be shared. Its path or contents can be \
+                     pasted on the setup screen to play it."
+                ),
+                Msg::DailyPuzzle => write!(f, "Daily puzzle"),
+                Msg::PlayToday => write!(f, "Play today's puzzle"),
+                Msg::WeekdayInitials => write!(f, "M T W T F S S"),
+                Msg::WeeklyChallenge => write!(f, "Weekly challenge"),
+                Msg::WeeklyChallengeNote => write!(
+                    f,
+                    "Five boards, the same for everyone this week. Larger boards and fewer moves \
+                     earn more points."
+                ),
+                Msg::WeeklyScore {
+                    completed,
+                    total,
+                    score,
+                } => write!(f, "{completed}/{total} solved, {score} points"),
+                Msg::Points(points) => write!(f, "{points} points"),
+                Msg::ScrubberNote => write!(
+                    f,
+                    "Drag to look back through the game, release to go back to that point."
+                ),
+                Msg::CopyLog => write!(f, "Copy to clipboard"),
+                Msg::DebugOverlay => write!(f, "Debug overlay"),
+                Msg::ExportCsv => write!(f, "Export CSV"),
+                Msg::HistoryExported => write!(f, "Your history was exported to:"),
+                Msg::ExportFailed => write!(f, "The history could not be exported:"),
+                #[cfg(not(target_arch = "wasm32"))]
+                Msg::SaveFile => write!(f, "Save file"),
+                #[cfg(not(target_arch = "wasm32"))]
+                Msg::DefaultSaveFile => write!(f, "Use the default save file"),
+                Msg::Sync => write!(f, "Sync"),
+                Msg::SyncEnabled => write!(f, "Sync games, history and settings with a server"),
+                Msg::SyncUrl => write!(f, "Address (HTTP or WebDAV)"),
+                Msg::Username => write!(f, "Username"),
+                Msg::Password => write!(f, "Password"),
+                Msg::SyncNow => write!(f, "Sync now"),
+                Msg::SyncUpToDate => write!(f, "Up to date"),
+                Msg::SyncFailed => write!(f, "Sync failed"),
+                Msg::Broadcast => write!(f, "Spectators"),
+                Msg::BroadcastText => write!(
+                    f,
+                    "Stream the game being played over the network, one line of JSON for each \
+                     board, so that another computer, an overlay or a stream dashboard can show \
+                     it live. Anyone who can reach this computer on the port may watch."
+                ),
+                Msg::BroadcastEnabled => write!(f, "Broadcast the game to spectators"),
+                Msg::Port => write!(f, "Port"),
+                Msg::Broadcasting(port) => write!(f, "Spectators can connect on port {port}"),
+                Msg::ListenFailed => write!(f, "Couldn't listen on the port"),
+                Msg::RemoteControl => write!(f, "Remote control"),
+                Msg::RemoteControlText => write!(
+                    f,
+                    "Let programs on this computer, such as bots, accessibility tools or chat \
+                     integrations, play the game being played over HTTP: GET /get_state and \
+                     /legal_moves, and POST a move such as {{\"from\": 0, \"to\": 2}} to \
+                     /apply_move as application/json."
+                ),
+                Msg::RemoteControlEnabled => write!(f, "Allow remote control"),
+                Msg::RemoteControlListening(port) => {
+                    write!(f, "Listening on http://127.0.0.1:{port}")
+                }
+                Msg::SyncConflict => write!(f, "Sync conflict"),
+                Msg::SyncConflictDetails { server_newer } => write!(
+                    f,
+                    "Your games changed both on this device and on the server since the last \
+                     sync. The copy {} is the most recent.",
+                    if server_newer {
+                        "on the server"
+                    } else {
+                        "on this device"
+                    }
+                ),
+                Msg::KeepLocal => write!(f, "Keep this device's"),
+                Msg::UseServer => write!(f, "Use the server's"),
+                Msg::Leaderboard => write!(f, "Leaderboard"),
+                Msg::LeaderboardEnabled => write!(f, "Take part in the online leaderboard"),
+                Msg::LeaderboardUrl => write!(f, "Server address"),
+                Msg::Nickname => write!(f, "Nickname"),
+                Msg::Profile => write!(f, "Profile"),
+                Msg::AvatarColor => write!(f, "Avatar color"),
+                Msg::OtherProfiles => write!(f, "Other players on this device"),
+                Msg::NewProfile => write!(f, "New player"),
+                Msg::UnnamedProfile => write!(f, "Unnamed player"),
+                Msg::RemoveProfile => write!(f, "Remove"),
+                Msg::RemoveProfileTitle => write!(f, "Remove this player?"),
+                Msg::RemoveProfileDetails => {
+                    write!(f, "Their games, history and settings will be deleted.")
+                }
+                Msg::WhoIsPlaying => write!(f, "Who's playing?"),
+                Msg::Challenge => write!(f, "Challenge a friend"),
+                Msg::ChallengeCopied => write!(
+                    f,
+                    "Challenge copied to the clipboard. Send it to a friend, who can paste it on \
+                     the setup screen."
+                ),
+                Msg::PasteChallenge => write!(f, "Paste a challenge or puzzle"),
+                Msg::AcceptChallenge => write!(f, "Accept"),
+                Msg::InvalidChallenge => write!(f, "This is not a valid challenge or puzzle."),
+                Msg::ChallengeWon => write!(f, "You won the challenge!"),
+                Msg::ChallengeLost => write!(f, "You lost the challenge."),
+                Msg::ChallengeTied => write!(f, "It's a tie!"),
+                Msg::You => write!(f, "You"),
+                Msg::StartWithBoard { colors, height } => {
+                    write!(f, "Always start with the {}x{} board", colors, height)
+                }
+                Msg::NicknameNeeded => write!(f, "Choose a nickname in your profile first."),
+                Msg::SubmitScore => write!(f, "Submit to the leaderboard"),
+                Msg::ScoreSubmitted => write!(f, "Submitted to the leaderboard."),
+                Msg::SubmitFailed => write!(f, "Submission failed"),
+                Msg::Refresh => write!(f, "Refresh"),
+                Msg::NoScores => write!(f, "No scores yet."),
+                Msg::LeaderboardUnavailable => write!(f, "The leaderboard is unavailable."),
+                Msg::ReplayVerified => write!(f, "Replaying the moves confirms this score."),
+                Msg::GlobalAverage { average, count } => write!(
+                    f,
+                    "Players average {:.1} moves on this board ({} games).",
+                    average, count
+                ),
+                Msg::GlobalPercentile(percentile) => {
+                    write!(f, "You did better than {}% of them.", percentile)
+                }
+                Msg::UsageStatistics => write!(f, "Usage statistics"),
+                Msg::UsageStatisticsText => write!(
+                    f,
+                    "Help decide what to work on next by sharing how often each board size is \
+                     played and won. Nothing else is sent, and nothing at all unless you opt in."
+                ),
+                Msg::SendUsageStatistics => write!(f, "Send anonymous usage statistics"),
+                Msg::UsageStatisticsPreview => write!(f, "What would be sent"),
+                Msg::Crashed => write!(f, "boule crashed last time"),
+                Msg::GameRecovered => write!(f, "Your game was recovered."),
+                Msg::CopyReport => write!(f, "Copy crash report"),
+                Msg::CorruptData => {
+                    write!(f, "Some saved data couldn't be read and was reset.")
+                }
+                Msg::CorruptDataNote => write!(
+                    f,
+                    "A copy of it is kept: export it to mend it, or to report the problem."
+                ),
+                Msg::ExportCorruptData => write!(f, "Export a copy"),
+                Msg::CorruptDataExported => write!(f, "The unreadable data was exported to:"),
+                Msg::Dismiss => write!(f, "Dismiss"),
+                Msg::Ok => write!(f, "OK"),
+                #[cfg(feature = "puffin")]
+                Msg::Profiler => write!(f, "Profiler"),
+                Msg::AboutText => write!(f, "A ball sorting puzzle made with egui."),
+                Msg::KeyboardShortcuts => write!(f, "Keyboard shortcuts"),
+                Msg::TipDragBall => write!(
+                    f,
+                    "Drag the top ball of a column onto another column to move it."
+                ),
+                Msg::TipUndo => write!(f, "Made a mistake? UNDO takes back the last move."),
+                Msg::GotIt => write!(f, "Got it"),
+                Msg::LegalMoves(moves) => write!(f, "{moves} possible moves"),
+                Msg::LegalMovesNote => write!(
+                    f,
+                    "Moves leading to different positions. Moving a ball to either of two empty \
+                     columns counts once."
+                ),
+                Msg::MaybeStuck => write!(
+                    f,
+                    "You might be stuck, going around in circles. Consider undoing a few moves or \
+                     restarting."
+                ),
+                Msg::DragGesture => write!(f, "Drag a ball"),
+                Msg::DragGestureAction => write!(f, "Move it to another column"),
+                Msg::WheelGesture => write!(f, "Mouse wheel over the board"),
+                Msg::WheelGestureAction => write!(f, "Select a column to take a ball from"),
+                Msg::ShiftWheelGesture => write!(f, "Shift + mouse wheel, then click"),
+                Msg::ShiftWheelGestureAction => write!(f, "Select where it goes, then move it"),
+                Msg::Pause => write!(f, "PAUSE"),
+                Msg::Paused => write!(f, "Paused"),
+                Msg::NewGame => write!(f, "NEW GAME"),
+                Msg::BackToGame => write!(f, "Back to the current game"),
+                Msg::UnfinishedGame => write!(f, "Unfinished game"),
+                Msg::UnfinishedGameDetails {
+                    colors,
+                    height,
+                    moves,
+                } => write!(
+                    f,
+                    "Your {}x{} game is not finished yet ({} moves in).",
+                    colors, height, moves
+                ),
+                Msg::Overwrite => write!(f, "Overwrite"),
+                Msg::ResumePrompt {
+                    colors,
+                    height,
+                    moves,
+                } => write!(
+                    f,
+                    "Resume your {}x{} game ({} moves in)?",
+                    colors, height, moves
+                ),
+                Msg::AbandonGame => write!(f, "Abandon this game?"),
+                Msg::MovesPlayed(moves) => write!(f, "{} moves played so far.", moves),
+                Msg::Abandon => write!(f, "Abandon"),
+                Msg::SaveForLater => write!(f, "Save for later"),
+                Msg::Cancel => write!(f, "Cancel"),
+                Msg::OtherGames => write!(f, "Other open games"),
+                Msg::GameSummary {
+                    colors,
+                    height,
+                    moves,
+                } => write!(f, "{}x{}, {} moves", colors, height, moves),
+                Msg::Resume => write!(f, "Resume"),
+                Msg::Delete => write!(f, "Delete"),
+                Msg::YouWon(moves) => write!(f, "You won in {} moves!", moves),
+                Msg::TopTen {
+                    colors,
+                    height,
+                    handicap,
+                } => write!(
+                    f,
+                    "TOP 10 ({}x{}{})",
+                    colors,
+                    height,
+                    if handicap { "*" } else { "" }
+                ),
+                Msg::Attempts {
+                    attempts,
+                    won_percent,
+                } => write!(
+                    f,
+                    "{attempts} attempt{}, {won_percent}% won",
+                    if attempts == 1 { "" } else { "s" }
+                ),
+                Msg::Powerups => write!(f, "Powerups"),
+                Msg::CountUndos => write!(f, "Count undos as moves"),
+                Msg::CountUndosNote => write!(
+                    f,
+                    "Undoing a move adds one to the count instead of taking one off. Wins are \
+                     ranked apart."
+                ),
+                Msg::ClassicRules => write!(f, "Classic"),
+                Msg::CountedUndoRules => write!(f, "Undos counted"),
+                Msg::ScriptedRules => write!(f, "Scripted rules"),
+                Msg::ScriptedRulesNote => write!(
+                    f,
+                    "Play by the rules of the script set in the settings. Wins don't go on the \
+                     leaderboard."
+                ),
+                Msg::RulesScript => write!(f, "Rules script"),
+                Msg::RulesScriptNote => write!(
+                    f,
+                    "Path of a Rhai script defining allows_move(board, from, to) or \
+                     is_column_finished(board, column), for games with scripted rules."
+                ),
+                Msg::RulesScriptLoaded => write!(f, "Script loaded"),
+                Msg::RulesScriptFailed => write!(f, "The script couldn't be loaded"),
+                Msg::RulesScriptNeeded => write!(f, "Set a rules script in the settings first"),
+                Msg::PowerupsNote {
+                    reshuffle_penalty,
+                    extra_column_penalty,
+                } => write!(
+                    f,
+                    "Once per game, the balls outside of completed columns can be reshuffled, \
+                     for {reshuffle_penalty} more moves, and an extra column can be borrowed, \
+                     for {extra_column_penalty} more moves."
+                ),
+                Msg::Reshuffle => write!(f, "Reshuffle the remaining balls"),
+                Msg::ReshuffleButton(penalty) => write!(f, "SHUFFLE (+{penalty})"),
+                Msg::ExtraColumn => write!(f, "Borrow an extra column"),
+                Msg::ExtraColumnButton(penalty) => write!(f, "+1 COLUMN (+{penalty})"),
+                Msg::ExtraColumnNote(moves) => write!(
+                    f,
+                    "Balls can be dropped onto the extra column for {moves} moves. It must be \
+                     emptied to win."
+                ),
+                Msg::ExtraColumnMovesLeft(moves) => {
+                    write!(f, "Extra column: {moves} moves left")
+                }
+                Msg::EmptyExtraColumn => write!(f, "Empty the extra column to win."),
+                Msg::PenaltyIncluded(penalty) => {
+                    write!(f, "Including {penalty} moves of penalty for powerups.")
+                }
+                Msg::RatingWin => write!(f, "Rating your win…"),
+                Msg::Stars(stars) => write!(
+                    f,
+                    "{stars} star{} out of 3, against the fewest moves solving the board",
+                    if stars == 1 { "" } else { "s" }
+                ),
+                Msg::StarsEarned(stars) => write!(f, "⭐ {stars} earned on this board"),
+                Msg::Handicap => write!(f, "One more empty column"),
+                Msg::HandicapNote => write!(
+                    f,
+                    "* with one more empty column, which makes the game easier. These wins are \
+                     ranked apart and aren't sent to the leaderboard."
+                ),
+                Msg::Variant => write!(f, "Variant"),
+                Msg::HexModeNote => write!(
+                    f,
+                    "Columns sit in two rows of hexagonal cells, and a ball only moves to the \
+                     cells next to its own. These wins are ranked apart and aren't sent to the \
+                     leaderboard."
+                ),
+                Msg::NumbersModeNote => write!(
+                    f,
+                    "Balls are numbered, and every column must go from 1 at the bottom to its \
+                     height at the top. These wins are ranked apart and aren't sent to the \
+                     leaderboard."
+                ),
+                Msg::Moves(moves) => write!(f, "{} moves", moves),
+                Msg::UiScale => write!(f, "UI scale"),
+                Msg::Statistics => write!(f, "Statistics"),
+                Msg::NoStatistics => write!(f, "No game won yet."),
+                Msg::Board => write!(f, "Board"),
+                Msg::Started => write!(f, "Started"),
+                Msg::Wins => write!(f, "Wins"),
+                Msg::Abandoned => write!(f, "Abandoned"),
+                Msg::Percent(percent) => write!(f, "{percent}%"),
+                Msg::Best => write!(f, "Best"),
+                Msg::PlayTime => write!(f, "Time played"),
+                Msg::TimeSpent(time) => {
+                    let minutes = time.as_secs() / 60;
+                    if minutes < 60 {
+                        write!(f, "You've spent {minutes} min sorting balls.")
+                    } else {
+                        write!(f, "You've spent {}h sorting balls.", minutes / 60)
+                    }
+                }
+                Msg::WonGames => write!(f, "Won games"),
+                Msg::NoMatchingGames => write!(f, "No won game matches."),
+                Msg::AllTime => write!(f, "All time"),
+                Msg::LastWeek => write!(f, "Last 7 days"),
+                Msg::LastMonth => write!(f, "Last 30 days"),
+                Msg::LastYear => write!(f, "Last year"),
+                Msg::AllModes => write!(f, "All modes"),
+                Msg::NormalMode => write!(f, "Normal"),
+                Msg::KidMode => write!(f, "Kid mode"),
+                Msg::NumbersMode => write!(f, "Numbers"),
+                Msg::HexMode => write!(f, "Hexagonal"),
+                Msg::KidModeButton => write!(f, "KID MODE"),
+                Msg::WellDone => write!(f, "Well done!"),
+                Msg::AllBoards => write!(f, "All boards"),
+                Msg::SortRecent => write!(f, "Most recent"),
+                Msg::SortMoves => write!(f, "Fewest moves"),
+                Msg::SortDuration => write!(f, "Fastest"),
+                Msg::Language => write!(f, "Language"),
+                Msg::LayoutDirection => write!(f, "Layout direction"),
+                Msg::DirectionAuto => write!(f, "Auto"),
+                Msg::LeftToRight => write!(f, "Left to right"),
+                Msg::RightToLeft => write!(f, "Right to left"),
+                Msg::MirrorBoard => write!(f, "Mirror the board in right-to-left layout"),
+                Msg::LeftHanded => write!(f, "Left-handed layout"),
+                Msg::LeftHandedNote => write!(
+                    f,
+                    "Mirror the panels, buttons and board so that your hand doesn't cover them \
+                     when playing on a touch screen."
+                ),
+                Msg::ColorSummary => write!(f, "Balls left to sort by color"),
+                Msg::ColorSummaryNote => write!(
+                    f,
+                    "Show above the board how many balls of each color aren't stacked yet with \
+                     the longest run of their color at the bottom of a column."
+                ),
+                Msg::CircularBoard => write!(f, "Circular board"),
+                Msg::CircularBoardNote => write!(
+                    f,
+                    "Draw the columns around a circle, balls stacking toward the center. \
+                     Hexagonal boards keep their rows."
+                ),
+                Msg::MadeBy => write!(f, "Made by @abey79"),
+                Msg::Source => write!(f, "(source)"),
+            },
+            Language::French => match *self {
+                Msg::Colors => write!(f, "Couleurs"),
+                Msg::Height => write!(f, "Hauteur"),
+                Msg::Play => write!(f, "JOUER"),
+                Msg::PlayAgain => write!(f, "REJOUER"),
+                Msg::Abort => write!(f, "ABANDONNER"),
+                Msg::UndoButton => write!(f, "ANNULER"),
+                Msg::RedoButton => write!(f, "RÉTABLIR"),
+                Msg::HintButton(left) => write!(f, "INDICE ({left})"),
+                Msg::GameMenu => write!(f, "Partie"),
+                Msg::EditMenu => write!(f, "Édition"),
+                Msg::ViewMenu => write!(f, "Affichage"),
+                Msg::HelpMenu => write!(f, "Aide"),
+                Msg::NewGameItem => write!(f, "Nouvelle partie"),
+                Msg::Restart => write!(f, "Recommencer"),
+                Msg::AbortItem => write!(f, "Abandonner"),
+                Msg::PauseItem => write!(f, "Pause/reprise"),
+                Msg::Undo => write!(f, "Annuler le coup"),
+                Msg::Redo => write!(f, "Rétablir le coup"),
+                Msg::Hint => write!(f, "Indice"),
+                Msg::HintNotFound => write!(f, "Aucune solution trouvée depuis cette position."),
+                Msg::HintMove { from, to } => {
+                    write!(f, "Indice : jouer {}", crate::Move { from, to })
+                }
+                Msg::ColumnInfo { column, balls } => write!(
+                    f,
+                    "Colonne {}, {balls} boule{}",
+                    crate::column_label(column),
+                    if balls > 1 { "s" } else { "" }
+                ),
+                Msg::SearchingHint => write!(f, "Recherche d'un indice…"),
+                Msg::HintedWin(hints) => {
+                    write!(
+                        f,
+                        "Gagnée avec {hints} indice{}",
+                        if hints == 1 { "" } else { "s" }
+                    )
+                }
+                Msg::UndoneWin(undos) => write!(
+                    f,
+                    "Gagnée avec {undos} annulation{}",
+                    if undos == 1 { "" } else { "s" }
+                ),
+                Msg::PowerupWin(penalty) => write!(f, "Gagnée avec des bonus (+{penalty} coups)"),
+                Msg::CleanWin => write!(f, "Gagnée sans indice, annulation ni bonus"),
+                Msg::ZoomIn => write!(f, "Agrandir"),
+                Msg::ZoomOut => write!(f, "Réduire"),
+                Msg::ResetZoom => write!(f, "Taille normale"),
+                Msg::Theme => write!(f, "Thème"),
+                Msg::ThemeSystem => write!(f, "Système"),
+                Msg::ThemeLight => write!(f, "Clair"),
+                Msg::ThemeDark => write!(f, "Sombre"),
+                Msg::HowToPlay => write!(f, "Comment jouer"),
+                Msg::HowToPlayText => write!(
+                    f,
+                    "Faites glisser la boule du haut d'une colonne vers une autre colonne \
+                    qui a de la place. La partie est gagnée quand chaque colonne ne contient \
+                    plus qu'une seule couleur."
+                ),
+                Msg::About => write!(f, "À propos"),
+                Msg::LogPanel => write!(f, "Journal"),
+                Msg::MoveLog => write!(f, "Coups joués"),
+                Msg::StartPosition => write!(f, "Début"),
+                Msg::CreatePuzzle => write!(f, "Créer un puzzle"),
+                Msg::PuzzleEditor => write!(f, "Éditeur de puzzle"),
+                Msg::EditorHelp => write!(
+                    f,
+                    "Cliquez sur une colonne pour y poser une boule de la couleur choisie, \
+                     clic droit pour reprendre sa boule du dessus."
+                ),
+                Msg::BallsLeft(balls) => write!(f, "Encore {balls} boules à placer."),
+                Msg::CopyPuzzle => write!(f, "Copier le puzzle"),
+                Msg::PuzzleCopied => write!(
+                    f,
+                    "Puzzle copié dans le presse-papiers. Il peut être collé sur l'écran de \
+                     configuration."
+                ),
+                Msg::ClearPuzzle => write!(f, "Vider"),
+                Msg::DropPuzzle => write!(f, "Déposer un fichier de puzzle pour y jouer"),
+                Msg::OpenPuzzle => write!(f, "Ouvrir un puzzle"),
+                Msg::CheckingPuzzle => write!(f, "Vérification que le puzzle a une solution…"),
+                Msg::UnsolvablePuzzle => write!(f, "Ce puzzle n'a pas de solution."),
+                Msg::PuzzleTooHard => write!(
+                    f,
+                    "Le solveur a abandonné avant de trouver une solution à ce puzzle, qui en a \
+                     peut-être bien une."
+                ),
+                Msg::SkipCheck => write!(f, "Jouer tout de suite"),
+                Msg::Remix => write!(f, "Variante"),
+                Msg::SummaryMoves => write!(f, "Coups"),
+                Msg::Par => write!(f, "Par"),
+                Msg::Grade => write!(f, "Note"),
+                Msg::SummaryTime => write!(f, "Temps"),
+                Msg::Undos => write!(f, "Annulations"),
+                Msg::HintsUsed => write!(f, "Indices"),
+                Msg::TopTenRank => write!(f, "Rang au top 10"),
+                Msg::TopTenPlace {
+                    rank: 2,
+                    behind_best,
+                    ..
+                } => write!(f, "n°2, à {behind_best} coups de votre meilleur score"),
+                Msg::TopTenPlace {
+                    rank,
+                    behind,
+                    behind_best,
+                } => write!(
+                    f,
+                    "n°{rank}, à {behind} coups du n°{} et {behind_best} de votre meilleur score",
+                    rank - 1
+                ),
+                Msg::TopTenBest => write!(f, "n°1, votre meilleur score !"),
+                Msg::Replay => write!(f, "Rejouer ce plateau"),
+                Msg::Favorites => write!(f, "Favoris"),
+                Msg::RecentlyPlayed => write!(f, "Parties récentes"),
+                Msg::WonIn(moves) => write!(f, "Gagnée en {moves} coups"),
+                Msg::AbandonedAfter(moves) => write!(f, "Abandonnée après {moves} coups"),
+                Msg::Retry => write!(f, "Réessayer"),
+                Msg::Seed(seed) => write!(f, "Graine {seed}"),
+                Msg::Note => write!(f, "Note"),
+                Msg::WonOn => write!(f, "Gagnée le"),
+                Msg::Easy => write!(f, "Facile"),
+                Msg::Medium => write!(f, "Moyen"),
+                Msg::Hard => write!(f, "Difficile"),
+                Msg::Expert => write!(f, "Expert"),
+                Msg::PlaysLike => write!(f, "Se joue comme :"),
+                Msg::CrowdRated(scores) => write!(
+                    f,
+                    "Évalué selon le temps mis par les joueurs pour le résoudre, sur {scores} \
+                     scores du classement"
+                ),
+                Msg::SizeRated => write!(f, "Évalué selon son nombre de boules"),
+                Msg::TryHarder {
+                    colors,
+                    height,
+                    next_colors,
+                    next_height,
+                } => write!(
+                    f,
+                    "Le {colors}x{height} n'a plus de secret pour vous, essayez le \
+                     {next_colors}x{next_height} !"
+                ),
+                Msg::TryEasier {
+                    colors,
+                    height,
+                    next_colors,
+                    next_height,
+                } => write!(
+                    f,
+                    "Le {colors}x{height} est coriace, le {next_colors}x{next_height} sera \
+                     peut-être plus plaisant."
+                ),
+                Msg::AddFavorite => {
+                    write!(f, "Ajouter ce plateau aux favoris, pour y rejouer")
+                }
+                Msg::RemoveFavorite => write!(f, "Retirer des favoris"),
+                Msg::RemixNote => write!(
+                    f,
+                    "Rejouer ce plateau avec quelques boules échangées, pour un puzzle familier \
+                     mais nouveau."
+                ),
+                Msg::Verify => write!(f, "Vérifier"),
+                Msg::VerifyNote => write!(
+                    f,
+                    "Rejouer les coups de cette victoire depuis son plateau, pour vérifier \
+                     qu'elle est gagnée en autant de coups."
+                ),
+                Msg::VerifiedWin => write!(f, "Les coups rejoués mènent à cette victoire"),
+                Msg::VerificationFailed => write!(
+                    f,
+                    "Les coups rejoués ne mènent pas à cette victoire, l'historique est peut-être \
+                     endommagé"
+                ),
+                Msg::Unverifiable => write!(
+                    f,
+                    "Impossible de rejouer cette victoire : son plateau ou ses coups n'ont pas \
+                     été conservés, ou elle a utilisé des bonus"
+                ),
+                Msg::PlayAnyway => write!(f, "Jouer quand même"),
+                Msg::PuzzleName => write!(f, "Nom"),
+                Msg::PuzzleAuthor => write!(f, "Auteur"),
+                Msg::Difficulty => write!(f, "Difficulté"),
+                Msg::ExportPuzzle => write!(f, "Enregistrer un fichier"),
+                Msg::PuzzleExported => write!(
+                    f,
+                    "Puzzle enregistré dans ce fichier, qui peut être partagé. Son chemin ou son \
+                     contenu peut être collé sur l'écran de configuration pour y jouer."
+                ),
+                Msg::DailyPuzzle => write!(f, "Puzzle du jour"),
+                Msg::PlayToday => write!(f, "Jouer le puzzle du jour"),
+                Msg::WeekdayInitials => write!(f, "L M M J V S D"),
+                Msg::WeeklyChallenge => write!(f, "Défi de la semaine"),
+                Msg::WeeklyChallengeNote => write!(
+                    f,
+                    "Cinq plateaux, les mêmes pour tout le monde cette semaine. Les grands \
+                     plateaux et les parties en moins de coups rapportent plus de points."
+                ),
+                Msg::WeeklyScore {
+                    completed,
+                    total,
+                    score,
+                } => write!(f, "{completed}/{total} résolus, {score} points"),
+                Msg::Points(points) => write!(f, "{points} points"),
+                Msg::ScrubberNote => write!(
+                    f,
+                    "Faites glisser pour revoir la partie, relâchez pour reprendre à ce point."
+                ),
+                Msg::CopyLog => write!(f, "Copier dans le presse-papiers"),
+                Msg::DebugOverlay => write!(f, "Informations de débogage"),
+                Msg::ExportCsv => write!(f, "Exporter en CSV"),
+                Msg::HistoryExported => write!(f, "Votre historique a été exporté vers :"),
+                Msg::ExportFailed => write!(f, "L'historique n'a pas pu être exporté :"),
+                #[cfg(not(target_arch = "wasm32"))]
+                Msg::SaveFile => write!(f, "Fichier de sauvegarde"),
+                #[cfg(not(target_arch = "wasm32"))]
+                Msg::DefaultSaveFile => write!(f, "Utiliser le fichier par défaut"),
+                Msg::Sync => write!(f, "Synchronisation"),
+                Msg::SyncEnabled => write!(
+                    f,
+                    "Synchroniser parties, historique et réglages avec un serveur"
+                ),
+                Msg::SyncUrl => write!(f, "Adresse (HTTP ou WebDAV)"),
+                Msg::Username => write!(f, "Nom d'utilisateur"),
+                Msg::Password => write!(f, "Mot de passe"),
+                Msg::SyncNow => write!(f, "Synchroniser"),
+                Msg::SyncUpToDate => write!(f, "À jour"),
+                Msg::SyncFailed => write!(f, "Échec de la synchronisation"),
+                Msg::Broadcast => write!(f, "Spectateurs"),
+                Msg::BroadcastText => write!(
+                    f,
+                    "Diffuser la partie en cours sur le réseau, une ligne de JSON par plateau, \
+                     pour qu'un autre ordinateur, une incrustation ou un tableau de bord de \
+                     stream l'affiche en direct. Toute personne pouvant joindre cet ordinateur \
+                     sur le port peut regarder."
+                ),
+                Msg::BroadcastEnabled => write!(f, "Diffuser la partie aux spectateurs"),
+                Msg::Port => write!(f, "Port"),
+                Msg::Broadcasting(port) => {
+                    write!(f, "Les spectateurs peuvent se connecter sur le port {port}")
+                }
+                Msg::ListenFailed => write!(f, "Impossible d'écouter sur le port"),
+                Msg::RemoteControl => write!(f, "Contrôle à distance"),
+                Msg::RemoteControlText => write!(
+                    f,
+                    "Permettre aux programmes de cet ordinateur, comme des bots, des outils \
+                     d'accessibilité ou des intégrations de chat, de jouer la partie en cours \
+                     en HTTP : GET /get_state et /legal_moves, et POST d'un coup comme \
+                     {{\"from\": 0, \"to\": 2}} sur /apply_move en application/json."
+                ),
+                Msg::RemoteControlEnabled => write!(f, "Autoriser le contrôle à distance"),
+                Msg::RemoteControlListening(port) => {
+                    write!(f, "À l'écoute sur http://127.0.0.1:{port}")
+                }
+                Msg::SyncConflict => write!(f, "Conflit de synchronisation"),
+                Msg::SyncConflictDetails { server_newer } => write!(
+                    f,
+                    "Vos parties ont changé sur cet appareil et sur le serveur depuis la \
+                     dernière synchronisation. La copie {} est la plus récente.",
+                    if server_newer {
+                        "du serveur"
+                    } else {
+                        "de cet appareil"
+                    }
+                ),
+                Msg::KeepLocal => write!(f, "Garder celle de cet appareil"),
+                Msg::UseServer => write!(f, "Utiliser celle du serveur"),
+                Msg::Leaderboard => write!(f, "Classement"),
+                Msg::LeaderboardEnabled => write!(f, "Participer au classement en ligne"),
+                Msg::LeaderboardUrl => write!(f, "Adresse du serveur"),
+                Msg::Nickname => write!(f, "Pseudo"),
+                Msg::Profile => write!(f, "Profil"),
+                Msg::AvatarColor => write!(f, "Couleur de l'avatar"),
+                Msg::OtherProfiles => write!(f, "Autres joueurs sur cet appareil"),
+                Msg::NewProfile => write!(f, "Nouveau joueur"),
+                Msg::UnnamedProfile => write!(f, "Joueur sans nom"),
+                Msg::RemoveProfile => write!(f, "Supprimer"),
+                Msg::RemoveProfileTitle => write!(f, "Supprimer ce joueur ?"),
+                Msg::RemoveProfileDetails => {
+                    write!(
+                        f,
+                        "Ses parties, son historique et ses réglages seront effacés."
+                    )
+                }
+                Msg::WhoIsPlaying => write!(f, "Qui joue ?"),
+                Msg::Challenge => write!(f, "Défier un ami"),
+                Msg::ChallengeCopied => write!(
+                    f,
+                    "Défi copié dans le presse-papiers. Envoyez-le à un ami, qui pourra le coller \
+                     sur l'écran de configuration."
+                ),
+                Msg::PasteChallenge => write!(f, "Coller un défi ou un puzzle"),
+                Msg::AcceptChallenge => write!(f, "Relever"),
+                Msg::InvalidChallenge => write!(f, "Ce défi ou puzzle n'est pas valide."),
+                Msg::ChallengeWon => write!(f, "Vous avez remporté le défi !"),
+                Msg::ChallengeLost => write!(f, "Vous avez perdu le défi."),
+                Msg::ChallengeTied => write!(f, "Égalité !"),
+                Msg::You => write!(f, "Vous"),
+                Msg::StartWithBoard { colors, height } => {
+                    write!(
+                        f,
+                        "Toujours commencer avec le plateau {}x{}",
+                        colors, height
+                    )
+                }
+                Msg::NicknameNeeded => write!(f, "Choisissez d'abord un pseudo dans votre profil."),
+                Msg::SubmitScore => write!(f, "Envoyer au classement"),
+                Msg::ScoreSubmitted => write!(f, "Envoyé au classement."),
+                Msg::SubmitFailed => write!(f, "Échec de l'envoi"),
+                Msg::Refresh => write!(f, "Actualiser"),
+                Msg::NoScores => write!(f, "Aucun score pour l'instant."),
+                Msg::LeaderboardUnavailable => write!(f, "Le classement est indisponible."),
+                Msg::ReplayVerified => write!(f, "Rejouer les coups confirme ce score."),
+                Msg::GlobalAverage { average, count } => write!(
+                    f,
+                    "Les joueurs font en moyenne {:.1} coups sur ce plateau ({} parties).",
+                    average, count
+                ),
+                Msg::GlobalPercentile(percentile) => {
+                    write!(f, "Vous avez fait mieux que {} % d'entre eux.", percentile)
+                }
+                Msg::UsageStatistics => write!(f, "Statistiques d'utilisation"),
+                Msg::UsageStatisticsText => write!(
+                    f,
+                    "Aidez à choisir les prochaines évolutions en partageant combien de fois \
+                     chaque taille de plateau est jouée et gagnée. Rien d'autre n'est envoyé, et \
+                     rien du tout sans votre accord."
+                ),
+                Msg::SendUsageStatistics => {
+                    write!(f, "Envoyer des statistiques d'utilisation anonymes")
+                }
+                Msg::UsageStatisticsPreview => write!(f, "Ce qui serait envoyé"),
+                Msg::Crashed => write!(f, "boule a planté la dernière fois"),
+                Msg::GameRecovered => write!(f, "Votre partie a été récupérée."),
+                Msg::CopyReport => write!(f, "Copier le rapport de plantage"),
+                Msg::CorruptData => write!(
+                    f,
+                    "Des données enregistrées n'ont pas pu être lues et ont été réinitialisées."
+                ),
+                Msg::CorruptDataNote => write!(
+                    f,
+                    "Une copie en est conservée : exportez-la pour la réparer, ou pour signaler \
+                     le problème."
+                ),
+                Msg::ExportCorruptData => write!(f, "Exporter une copie"),
+                Msg::CorruptDataExported => {
+                    write!(f, "Les données illisibles ont été exportées vers :")
+                }
+                Msg::Dismiss => write!(f, "Ignorer"),
+                Msg::Ok => write!(f, "OK"),
+                #[cfg(feature = "puffin")]
+                Msg::Profiler => write!(f, "Profileur"),
+                Msg::AboutText => write!(f, "Un jeu de tri de boules réalisé avec egui."),
+                Msg::KeyboardShortcuts => write!(f, "Raccourcis clavier"),
+                Msg::TipDragBall => write!(
+                    f,
+                    "Faites glisser la boule du haut d'une colonne vers une autre pour la déplacer."
+                ),
+                Msg::TipUndo => write!(f, "Une erreur ? ANNULER reprend le dernier coup."),
+                Msg::GotIt => write!(f, "Compris"),
+                Msg::LegalMoves(moves) => write!(f, "{moves} coups possibles"),
+                Msg::LegalMovesNote => write!(
+                    f,
+                    "Coups menant à des positions différentes. Déplacer une boule vers l'une ou \
+                     l'autre de deux colonnes vides compte pour un."
+                ),
+                Msg::MaybeStuck => write!(
+                    f,
+                    "Vous semblez tourner en rond. Pourquoi ne pas annuler quelques coups, ou \
+                     recommencer ?"
+                ),
+                Msg::DragGesture => write!(f, "Glisser une boule"),
+                Msg::DragGestureAction => write!(f, "La déplacer vers une autre colonne"),
+                Msg::WheelGesture => write!(f, "Molette de la souris sur le plateau"),
+                Msg::WheelGestureAction => {
+                    write!(f, "Choisir la colonne d'où prendre une boule")
+                }
+                Msg::ShiftWheelGesture => write!(f, "Maj + molette, puis clic"),
+                Msg::ShiftWheelGestureAction => write!(f, "Choisir où la poser, puis la déplacer"),
+                Msg::Pause => write!(f, "PAUSE"),
+                Msg::Paused => write!(f, "En pause"),
+                Msg::NewGame => write!(f, "NOUVELLE PARTIE"),
+                Msg::BackToGame => write!(f, "Retour à la partie en cours"),
+                Msg::UnfinishedGame => write!(f, "Partie en cours"),
+                Msg::UnfinishedGameDetails {
+                    colors,
+                    height,
+                    moves,
+                } => write!(
+                    f,
+                    "Votre partie {}x{} n'est pas terminée ({} coups joués).",
+                    colors, height, moves
+                ),
+                Msg::Overwrite => write!(f, "Écraser"),
+                Msg::ResumePrompt {
+                    colors,
+                    height,
+                    moves,
+                } => write!(
+                    f,
+                    "Reprendre votre partie {}x{} ({} coups joués) ?",
+                    colors, height, moves
+                ),
+                Msg::AbandonGame => write!(f, "Abandonner cette partie ?"),
+                Msg::MovesPlayed(moves) => write!(f, "{} coups joués jusqu'ici.", moves),
+                Msg::Abandon => write!(f, "Abandonner"),
+                Msg::SaveForLater => write!(f, "Sauvegarder pour plus tard"),
+                Msg::Cancel => write!(f, "Annuler"),
+                Msg::OtherGames => write!(f, "Autres parties en cours"),
+                Msg::GameSummary {
+                    colors,
+                    height,
+                    moves,
+                } => write!(f, "{}x{}, {} coups", colors, height, moves),
+                Msg::Resume => write!(f, "Reprendre"),
+                Msg::Delete => write!(f, "Supprimer"),
+                Msg::YouWon(moves) => write!(f, "Gagné en {} coups !", moves),
+                Msg::TopTen {
+                    colors,
+                    height,
+                    handicap,
+                } => write!(
+                    f,
+                    "TOP 10 ({}x{}{})",
+                    colors,
+                    height,
+                    if handicap { "*" } else { "" }
+                ),
+                Msg::Attempts {
+                    attempts,
+                    won_percent,
+                } => write!(
+                    f,
+                    "{attempts} essai{}, {won_percent} % gagnés",
+                    if attempts > 1 { "s" } else { "" }
+                ),
+                Msg::Powerups => write!(f, "Bonus"),
+                Msg::CountUndos => write!(f, "Compter les annulations comme des coups"),
+                Msg::CountUndosNote => write!(
+                    f,
+                    "Annuler un coup en ajoute un au compte au lieu d'en retirer un. Les victoires \
+                     sont classées à part."
+                ),
+                Msg::ClassicRules => write!(f, "Classique"),
+                Msg::CountedUndoRules => write!(f, "Annulations comptées"),
+                Msg::ScriptedRules => write!(f, "Règles scriptées"),
+                Msg::ScriptedRulesNote => write!(
+                    f,
+                    "Jouer selon les règles du script choisi dans les réglages. Les victoires \
+                     ne vont pas au classement."
+                ),
+                Msg::RulesScript => write!(f, "Script de règles"),
+                Msg::RulesScriptNote => write!(
+                    f,
+                    "Chemin d'un script Rhai définissant allows_move(board, from, to) ou \
+                     is_column_finished(board, column), pour les parties aux règles scriptées."
+                ),
+                Msg::RulesScriptLoaded => write!(f, "Script chargé"),
+                Msg::RulesScriptFailed => write!(f, "Le script n'a pas pu être chargé"),
+                Msg::RulesScriptNeeded => {
+                    write!(
+                        f,
+                        "Choisissez d'abord un script de règles dans les réglages"
+                    )
+                }
+                Msg::PowerupsNote {
+                    reshuffle_penalty,
+                    extra_column_penalty,
+                } => write!(
+                    f,
+                    "Une fois par partie, les boules hors des colonnes terminées peuvent être \
+                     remélangées, pour {reshuffle_penalty} coups de plus, et une colonne \
+                     supplémentaire peut être empruntée, pour {extra_column_penalty} coups de \
+                     plus."
+                ),
+                Msg::Reshuffle => write!(f, "Remélanger les boules restantes"),
+                Msg::ReshuffleButton(penalty) => write!(f, "MÉLANGER (+{penalty})"),
+                Msg::ExtraColumn => write!(f, "Emprunter une colonne supplémentaire"),
+                Msg::ExtraColumnButton(penalty) => write!(f, "+1 COLONNE (+{penalty})"),
+                Msg::ExtraColumnNote(moves) => write!(
+                    f,
+                    "Des boules peuvent être posées sur la colonne supplémentaire pendant \
+                     {moves} coups. Elle doit être vidée pour gagner."
+                ),
+                Msg::ExtraColumnMovesLeft(moves) => {
+                    write!(f, "Colonne supplémentaire : encore {moves} coups")
+                }
+                Msg::EmptyExtraColumn => write!(f, "Videz la colonne supplémentaire pour gagner."),
+                Msg::PenaltyIncluded(penalty) => {
+                    write!(f, "Dont {penalty} coups de pénalité pour les bonus.")
+                }
+                Msg::RatingWin => write!(f, "Évaluation de votre victoire…"),
+                Msg::Stars(stars) => write!(
+                    f,
+                    "{stars} étoile{} sur 3, par rapport au plus petit nombre de coups pour résoudre le plateau",
+                    if stars == 1 { "" } else { "s" }
+                ),
+                Msg::StarsEarned(stars) => write!(f, "⭐ {stars} gagnées sur ce plateau"),
+                Msg::Handicap => write!(f, "Une colonne vide en plus"),
+                Msg::HandicapNote => write!(
+                    f,
+                    "* avec une colonne vide en plus, ce qui facilite la partie. Ces victoires \
+                     sont classées à part et ne vont pas au classement en ligne."
+                ),
+                Msg::Variant => write!(f, "Variante"),
+                Msg::HexModeNote => write!(
+                    f,
+                    "Les colonnes forment deux rangées de cases hexagonales, et une boule ne va \
+                     que sur les cases voisines de la sienne. Ces victoires sont classées à part \
+                     et ne vont pas au classement en ligne."
+                ),
+                Msg::NumbersModeNote => write!(
+                    f,
+                    "Les boules sont numérotées, et chaque colonne doit aller de 1 en bas à sa \
+                     hauteur en haut. Ces victoires sont classées à part et ne vont pas au \
+                     classement en ligne."
+                ),
+                Msg::Moves(moves) => write!(f, "{} coups", moves),
+                Msg::UiScale => write!(f, "Taille de l'interface"),
+                Msg::Statistics => write!(f, "Statistiques"),
+                Msg::NoStatistics => write!(f, "Aucune partie gagnée pour l'instant."),
+                Msg::Board => write!(f, "Plateau"),
+                Msg::Started => write!(f, "Commencées"),
+                Msg::Wins => write!(f, "Victoires"),
+                Msg::Abandoned => write!(f, "Abandonnées"),
+                Msg::Percent(percent) => write!(f, "{percent} %"),
+                Msg::Best => write!(f, "Meilleur"),
+                Msg::PlayTime => write!(f, "Temps de jeu"),
+                Msg::TimeSpent(time) => {
+                    let minutes = time.as_secs() / 60;
+                    if minutes < 60 {
+                        write!(f, "Vous avez passé {minutes} min à trier des boules.")
+                    } else {
+                        write!(f, "Vous avez passé {} h à trier des boules.", minutes / 60)
+                    }
+                }
+                Msg::WonGames => write!(f, "Parties gagnées"),
+                Msg::NoMatchingGames => write!(f, "Aucune partie gagnée ne correspond."),
+                Msg::AllTime => write!(f, "Depuis toujours"),
+                Msg::LastWeek => write!(f, "7 derniers jours"),
+                Msg::LastMonth => write!(f, "30 derniers jours"),
+                Msg::LastYear => write!(f, "Dernière année"),
+                Msg::AllModes => write!(f, "Tous les modes"),
+                Msg::NormalMode => write!(f, "Normal"),
+                Msg::KidMode => write!(f, "Mode enfant"),
+                Msg::HexMode => write!(f, "Hexagonal"),
+                Msg::NumbersMode => write!(f, "Nombres"),
+                Msg::KidModeButton => write!(f, "MODE ENFANT"),
+                Msg::WellDone => write!(f, "Bravo !"),
+                Msg::AllBoards => write!(f, "Tous les plateaux"),
+                Msg::SortRecent => write!(f, "Plus récentes"),
+                Msg::SortMoves => write!(f, "Moins de coups"),
+                Msg::SortDuration => write!(f, "Plus rapides"),
+                Msg::Language => write!(f, "Langue"),
+                Msg::LayoutDirection => write!(f, "Sens de lecture"),
+                Msg::DirectionAuto => write!(f, "Auto"),
+                Msg::LeftToRight => write!(f, "Gauche à droite"),
+                Msg::RightToLeft => write!(f, "Droite à gauche"),
+                Msg::MirrorBoard => write!(f, "Inverser le plateau de droite à gauche"),
+                Msg::LeftHanded => write!(f, "Disposition pour gaucher"),
+                Msg::LeftHandedNote => write!(
+                    f,
+                    "Inverser les panneaux, les boutons et le plateau pour que votre main ne les \
+                     cache pas sur un écran tactile."
+                ),
+                Msg::ColorSummary => write!(f, "Boules à trier par couleur"),
+                Msg::ColorSummaryNote => write!(
+                    f,
+                    "Afficher au-dessus du plateau combien de boules de chaque couleur ne sont pas \
+                     encore empilées sur la plus longue série de leur couleur au bas d'une colonne."
+                ),
+                Msg::CircularBoard => write!(f, "Plateau circulaire"),
+                Msg::CircularBoardNote => write!(
+                    f,
+                    "Disposer les colonnes en cercle, les boules s'empilant vers le centre. Les \
+                     plateaux hexagonaux gardent leurs rangées."
+                ),
+                Msg::MadeBy => write!(f, "Créé par @abey79"),
+                Msg::Source => write!(f, "(code source)"),
+            },
+        }
+    }
+}
+
+impl From<Msg> for egui::RichText {
+    fn from(msg: Msg) -> Self {
+        egui::RichText::new(msg.to_string())
+    }
+}
+
+impl From<Msg> for egui::WidgetText {
+    fn from(msg: Msg) -> Self {
+        egui::RichText::from(msg).into()
+    }
+}
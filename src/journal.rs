@@ -0,0 +1,141 @@
+//! Moves played since the last save, appended to storage as they happen, so that a crash or a
+//! killed browser tab loses at most the last move rather than everything since the periodic save.
+
+/// Change to a game since the last save.
+///
+/// The number of moves the game had before tells whether the change already made it into the
+/// save, should the app stop between writing the save and clearing the journal.
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+pub enum Entry {
+    Moved {
+        game: usize,
+        moves: usize,
+        from: usize,
+        to: usize,
+    },
+    Undone {
+        game: usize,
+        moves: usize,
+    },
+    Redone {
+        game: usize,
+        moves: usize,
+    },
+}
+
+impl Entry {
+    /// Index of the game, and its number of moves before the change.
+    pub fn position(self) -> (usize, usize) {
+        match self {
+            Entry::Moved { game, moves, .. }
+            | Entry::Undone { game, moves }
+            | Entry::Redone { game, moves } => (game, moves),
+        }
+    }
+}
+
+/// Journal of a save: next to the save file on desktop, and in localStorage on the web, which
+/// unlike IndexedDB is written right away.
+pub struct Journal {
+    #[cfg(not(target_arch = "wasm32"))]
+    path: std::path::PathBuf,
+
+    #[cfg(target_arch = "wasm32")]
+    key: String,
+}
+
+impl Journal {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(save_path: &std::path::Path) -> Self {
+        Self {
+            path: crate::save_file::with_suffix(save_path, ".journal"),
+        }
+    }
+
+    /// Journal of profile `id`.
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(id: u32) -> Self {
+        Self {
+            key: format!("boule_journal{}", crate::profiles::suffix(id)),
+        }
+    }
+
+    /// Returns whether the entry was written.
+    pub fn append(&self, entry: Entry) -> bool {
+        match ron::to_string(&entry) {
+            Ok(line) => self.append_line(&line),
+            Err(err) => {
+                log::warn!("failed to serialize journal entry: {err}");
+                false
+            }
+        }
+    }
+
+    /// Entries since the last save, oldest first.
+    pub fn read(&self) -> Vec<Entry> {
+        self.contents()
+            .lines()
+            // the last line may have been cut short by a crash
+            .filter_map(|line| ron::from_str(line).ok())
+            .collect()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn append_line(&self, line: &str) -> bool {
+        use std::io::Write as _;
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{line}"))
+            .map_err(|err| log::warn!("failed to write {}: {err}", self.path.display()))
+            .is_ok()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn contents(&self) -> String {
+        std::fs::read_to_string(&self.path).unwrap_or_default()
+    }
+
+    /// Forget the entries, once they made it into a save.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn clear(&self) {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => log::warn!("failed to remove {}: {err}", self.path.display()),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn append_line(&self, line: &str) -> bool {
+        let Some(storage) = local_storage() else {
+            return false;
+        };
+        let mut contents = self.contents();
+        contents.push_str(line);
+        contents.push('\n');
+        storage.set_item(&self.key, &contents).is_ok()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn contents(&self) -> String {
+        local_storage()
+            .and_then(|storage| storage.get_item(&self.key).ok().flatten())
+            .unwrap_or_default()
+    }
+
+    /// Forget the entries, once they made it into a save.
+    #[cfg(target_arch = "wasm32")]
+    pub fn clear(&self) {
+        if let Some(storage) = local_storage() {
+            storage.remove_item(&self.key).ok();
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<eframe::web_sys::Storage> {
+    eframe::web_sys::window()?.local_storage().ok()?
+}
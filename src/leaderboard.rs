@@ -0,0 +1,268 @@
+//! Client for an optional online leaderboard, a small REST service where scores are submitted per
+//! board size under a nickname:
+//!
+//! - `POST {url}/scores` with a [`Submission`] as JSON;
+//! - `GET {url}/scores?colors=…&height=…` returns the best [`Entry`]s as a JSON array;
+//! - `GET {url}/stats?puzzle_hash=…&moves=…` returns [`GlobalStats`] as JSON, or
+//!   `GET {url}/stats?colors=…&height=…&moves=…` for a board size;
+//! - `GET {url}/solves?colors=…&height=…&empty_columns=…&rules=…&mode=…` returns [`SolveStats`]
+//!   as JSON.
+
+use std::sync::{Arc, Mutex};
+
+/// Scores shown per board size.
+pub const TOP_COUNT: usize = 100;
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct LeaderboardSettings {
+    pub enabled: bool,
+    pub url: String,
+
+    /// Nickname of older versions, moved to the profile when loading.
+    #[serde(skip_serializing)]
+    pub nickname: String,
+}
+
+impl Default for LeaderboardSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: option_env!("BOULE_LEADERBOARD_URL")
+                .unwrap_or_default()
+                .to_owned(),
+            nickname: String::new(),
+        }
+    }
+}
+
+impl LeaderboardSettings {
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.url.trim().is_empty()
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{path}", self.url.trim().trim_end_matches('/'))
+    }
+}
+
+/// A win, along with what it takes to replay it, so that claimed scores can be checked.
+#[derive(serde::Serialize)]
+pub struct Submission {
+    pub nickname: String,
+    pub colors: usize,
+    pub height: usize,
+    pub moves: usize,
+    pub duration_seconds: f32,
+
+    /// Seed the puzzle was shuffled from, if any.
+    pub seed: Option<u64>,
+
+    /// Fingerprint of `start`, to tell games of the same puzzle, seeded or not.
+    pub puzzle_hash: String,
+
+    /// Starting arrangement as slot codes, column by column (see [`crate::Slot::code`]).
+    pub start: Vec<u8>,
+
+    /// Every move played, as source and destination columns.
+    pub move_list: Vec<[usize; 2]>,
+}
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Entry {
+    pub nickname: String,
+    pub moves: usize,
+    #[serde(default)]
+    pub duration_seconds: Option<f32>,
+    #[serde(default)]
+    pub start: Vec<u8>,
+    #[serde(default)]
+    pub move_list: Vec<[usize; 2]>,
+
+    /// Whether replaying `move_list` from `start` wins in `moves`.
+    #[serde(skip)]
+    pub verified: bool,
+}
+
+/// FNV-1a hash of a starting arrangement, as hex. Unlike the standard library's hasher, it's the
+/// same everywhere.
+pub fn puzzle_hash(start: &[u8]) -> String {
+    let hash = start.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &code| {
+        (hash ^ u64::from(code)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Whether playing `move_list` from `start` wins the game in `moves`.
+fn replay_wins(
+    (column_count, column_capacity): (usize, usize),
+    start: &[u8],
+    move_list: &[[usize; 2]],
+    moves: usize,
+) -> bool {
+    let Some(mut state) = crate::State::from_codes(
+        column_count,
+        column_capacity,
+        crate::board::Goal::Colors,
+        start,
+    ) else {
+        return false;
+    };
+    for &[from, to] in move_list {
+        if from >= column_count || to >= column_count || !state.move_ball(from, to) {
+            return false;
+        }
+    }
+    state.is_winning() == Some(moves)
+}
+
+/// How a move count compares to every score submitted for a puzzle, or a board size.
+#[derive(Clone, serde::Deserialize)]
+pub struct GlobalStats {
+    /// Scores submitted.
+    pub count: usize,
+    pub average_moves: f32,
+
+    /// Share of scores with more moves, between 0 and 1.
+    pub percentile: f32,
+}
+
+/// How players fare on a board played a given way, from every score submitted for it.
+#[derive(Clone, serde::Deserialize)]
+pub struct SolveStats {
+    /// Scores submitted.
+    pub count: usize,
+
+    /// Average time of the scores that have one.
+    #[serde(default)]
+    pub average_seconds: Option<f32>,
+}
+
+/// Request for the best scores of a board size.
+pub type TopRequest = Request<Vec<Entry>>;
+
+/// Request in flight, then its outcome.
+pub enum Request<T> {
+    Pending(Arc<Mutex<Option<Result<T, String>>>>),
+    Done(Result<T, String>),
+}
+
+impl<T: Send + 'static> Request<T> {
+    fn send(
+        request: ehttp::Request,
+        ctx: egui::Context,
+        parse: impl 'static + Send + FnOnce(&ehttp::Response) -> Result<T, String>,
+    ) -> Self {
+        let result = Arc::new(Mutex::new(None));
+        let pending = result.clone();
+        ehttp::fetch(request, move |response| {
+            let outcome = match response {
+                Ok(response) if response.ok => parse(&response),
+                Ok(response) => Err(format!("{} {}", response.status, response.status_text)),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = &outcome {
+                log::warn!("leaderboard request failed: {err}");
+            }
+            if let Ok(mut result) = result.lock() {
+                *result = Some(outcome);
+            }
+            ctx.request_repaint();
+        });
+        Self::Pending(pending)
+    }
+
+    /// The outcome once the request is done.
+    pub fn poll(&mut self) -> Option<&Result<T, String>> {
+        if let Self::Pending(pending) = self {
+            let outcome = pending.lock().ok()?.take()?;
+            *self = Self::Done(outcome);
+        }
+        match self {
+            Self::Pending(_) => None,
+            Self::Done(outcome) => Some(outcome),
+        }
+    }
+}
+
+/// Start submitting a score, repainting `ctx` once done.
+pub fn submit(
+    settings: &LeaderboardSettings,
+    submission: &Submission,
+    ctx: egui::Context,
+) -> Request<()> {
+    match ehttp::Request::json(settings.endpoint("scores"), submission) {
+        Ok(request) => Request::send(request, ctx, |_| Ok(())),
+        Err(err) => Request::Done(Err(err.to_string())),
+    }
+}
+
+/// Start fetching the best scores for a board size, repainting `ctx` once done.
+pub fn fetch_top(
+    settings: &LeaderboardSettings,
+    (column_count, column_capacity): (usize, usize),
+    ctx: egui::Context,
+) -> TopRequest {
+    let url = format!(
+        "{}?colors={}&height={column_capacity}&limit={TOP_COUNT}",
+        settings.endpoint("scores"),
+        column_count.saturating_sub(1),
+    );
+    Request::send(ehttp::Request::get(url), ctx, move |response| {
+        let mut entries = response
+            .json::<Vec<Entry>>()
+            .map_err(|err| err.to_string())?;
+        entries.truncate(TOP_COUNT);
+        for entry in &mut entries {
+            entry.verified = replay_wins(
+                (column_count, column_capacity),
+                &entry.start,
+                &entry.move_list,
+                entry.moves,
+            );
+        }
+        Ok(entries)
+    })
+}
+
+/// Start fetching how `moves` compares to the scores for the puzzle of `puzzle_hash` (see
+/// [`puzzle_hash`]), or for a board size if there's none, repainting `ctx` once done.
+pub fn fetch_stats(
+    settings: &LeaderboardSettings,
+    (column_count, column_capacity): (usize, usize),
+    puzzle_hash: Option<&str>,
+    moves: usize,
+    ctx: egui::Context,
+) -> Request<GlobalStats> {
+    let puzzle = match puzzle_hash {
+        Some(hash) => format!("puzzle_hash={hash}"),
+        None => format!(
+            "colors={}&height={column_capacity}",
+            column_count.saturating_sub(1)
+        ),
+    };
+    let url = format!("{}?{puzzle}&moves={moves}", settings.endpoint("stats"));
+    Request::send(ehttp::Request::get(url), ctx, |response| {
+        response.json().map_err(|err| err.to_string())
+    })
+}
+
+/// Start fetching how players fare on games of `config`, repainting `ctx` once done.
+pub fn fetch_solves(
+    settings: &LeaderboardSettings,
+    config: &crate::GameConfig,
+    ctx: egui::Context,
+) -> Request<SolveStats> {
+    let url = format!(
+        "{}?colors={}&height={}&empty_columns={}&rules={:?}&mode={:?}",
+        settings.endpoint("solves"),
+        config.colors(),
+        config.column_capacity,
+        config.empty_columns,
+        config.rules,
+        config.mode,
+    );
+    Request::send(ehttp::Request::get(url), ctx, |response| {
+        response.json().map_err(|err| err.to_string())
+    })
+}
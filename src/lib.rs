@@ -0,0 +1,7819 @@
+//! Boule, a ball sorting puzzle game. The app runs with [`run`], and other egui applications can
+//! embed a playable board with the [`BouleBoard`] widget.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    f32::consts::{FRAC_PI_2, PI, TAU},
+    sync::Arc,
+    time::Duration,
+};
+
+use eframe::Storage;
+use egui::{vec2, NumExt, Sense};
+use egui_extras::Column;
+use rand::{RngCore as _, SeedableRng as _};
+
+use board::{Goal, PackedBoard, Topology};
+use i18n::{Language, LayoutDirection, Msg};
+use rules::{RuleSet, Rules};
+use solver::{Search, Unsolved};
+
+/// Profiler scope lasting until the end of the enclosing block, with the `puffin` feature.
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "puffin")]
+        puffin::profile_scope!($name);
+    };
+}
+
+mod board;
+#[cfg(not(target_arch = "wasm32"))]
+mod broadcast;
+#[cfg(target_arch = "wasm32")]
+mod bulk_storage;
+mod challenge;
+mod crash;
+mod daily;
+mod editor;
+#[cfg(target_arch = "wasm32")]
+mod embed;
+mod export;
+mod favorites;
+mod history;
+mod i18n;
+mod journal;
+mod leaderboard;
+mod logs;
+mod profiles;
+mod puzzle;
+mod recent;
+#[cfg(feature = "rerun")]
+mod recording;
+#[cfg(not(target_arch = "wasm32"))]
+mod remote;
+mod rules;
+#[cfg(not(target_arch = "wasm32"))]
+mod save_file;
+#[cfg(feature = "scripting")]
+mod scripting;
+mod solver;
+mod sound;
+mod sync;
+mod telemetry;
+mod weekly;
+mod widget;
+
+pub use widget::BouleBoard;
+
+/// Name of the native window and of the directory where data is stored.
+#[cfg(not(target_arch = "wasm32"))]
+const APP_ID: &str = "boule";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_native() -> Result<(), eframe::Error> {
+    // Log to stderr (if you run with `RUST_LOG=debug`), and to the in-app log panel.
+    let logger = env_logger::Builder::from_default_env().build();
+    let level = logger.filter();
+    logs::init(Box::new(logger), level, log::LevelFilter::Info).ok();
+    crash::install_panic_hook();
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([500.0, 500.0])
+            .with_drag_and_drop(true),
+        // restore the size and position of the last session, if any
+        persist_window: true,
+        ..Default::default()
+    };
+    eframe::run_native(APP_ID, options, Box::new(|cc| Box::new(BouleApp::new(cc))))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn run_web() -> Result<(), eframe::Error> {
+    // Redirect `log` message to `console.log` and friends, and to the in-app log panel:
+    logs::init(
+        Box::new(eframe::WebLogger::new(log::LevelFilter::Debug)),
+        log::LevelFilter::Debug,
+        log::LevelFilter::Info,
+    )
+    .ok();
+    crash::install_panic_hook();
+
+    // other sites embedding the game mount it on a canvas of their choosing
+    if embed::has_element(embed::DEFAULT_CANVAS_ID) {
+        embed::mount(embed::DEFAULT_CANVAS_ID.to_owned());
+    }
+
+    Ok(())
+}
+
+/// Open the app, in a native window or on the web page.
+pub fn run() -> Result<(), eframe::Error> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return run_native();
+
+    #[cfg(target_arch = "wasm32")]
+    return run_web();
+}
+
+const BALL_COLORS: &[egui::Color32] = &[
+    // from https://colorkit.co/palette/ffef3e-ffa0c5-a98467-32dba9-ffa600-c7522a-476f95-ffd380-893f71-92ba92/
+    egui::Color32::from_rgb(255, 239, 62),  // #ffef3e
+    egui::Color32::from_rgb(255, 160, 197), // #ffa0c5
+    egui::Color32::from_rgb(169, 132, 103), // #a98467
+    egui::Color32::from_rgb(50, 219, 169),  // #32dba9
+    egui::Color32::from_rgb(255, 166, 0),   // #ffa600
+    egui::Color32::from_rgb(199, 82, 42),   // #c7522a
+    egui::Color32::from_rgb(71, 111, 149),  // #476f95
+    egui::Color32::from_rgb(255, 211, 128), // #ffd380
+    egui::Color32::from_rgb(137, 63, 113),  // #893f71
+    egui::Color32::from_rgb(146, 186, 146), // #92ba92
+];
+
+/// Initials of [`BALL_COLORS`] for text boards, lowercase for the hollow balls: yellow, pink,
+/// brown, teal, orange, red, navy, sand, violet and green.
+const BALL_INITIALS: &[char] = &['Y', 'P', 'B', 'T', 'O', 'R', 'N', 'S', 'V', 'G'];
+
+/// Tallest columns the setup screen sets up, well within what [`board::PackedBoard`] holds.
+const MAX_COLUMN_CAPACITY: usize = 20;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum BallTheme {
+    Plain,
+    Hole,
+}
+
+impl BallTheme {
+    pub fn from_index(index: usize) -> Self {
+        match index % 2 {
+            0 => BallTheme::Plain,
+            1 => BallTheme::Hole,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct BallStyle {
+    color: egui::Color32,
+    theme: BallTheme,
+}
+
+impl BallStyle {
+    const MAX_STYLES: usize = BALL_COLORS.len() * 2;
+
+    pub fn shape(&self, pos: egui::Pos2, scale: f32) -> egui::Shape {
+        match self.theme {
+            BallTheme::Plain => egui::Shape::circle_filled(pos, 12.0 * scale, self.color),
+            BallTheme::Hole => {
+                egui::Shape::circle_stroke(pos, 8.0 * scale, (8.0 * scale, self.color))
+            }
+        }
+    }
+
+    pub fn paint(&self, painter: &egui::Painter, pos: egui::Pos2, scale: f32) {
+        painter.add(self.shape(pos, scale));
+    }
+}
+
+/// Tessellated balls centered on the origin, so that a whole board can be painted as a single
+/// mesh without tessellating hundreds of circles every frame. Kept in the egui memory behind an
+/// `Arc`, so that it isn't copied every frame.
+#[derive(Default)]
+struct BallMeshCache {
+    /// Tessellation depends on these, so the cache is flushed when they change.
+    pixels_per_point: f32,
+    font_image_size: [usize; 2],
+
+    meshes: HashMap<BallStyle, egui::Mesh>,
+}
+
+impl BallMeshCache {
+    fn mesh(&mut self, ctx: &egui::Context, style: BallStyle) -> &egui::Mesh {
+        let pixels_per_point = ctx.pixels_per_point();
+        let font_image_size = ctx.fonts(|fonts| fonts.font_image_size());
+        if pixels_per_point != self.pixels_per_point || font_image_size != self.font_image_size {
+            self.pixels_per_point = pixels_per_point;
+            self.font_image_size = font_image_size;
+            self.meshes.clear();
+        }
+
+        self.meshes.entry(style).or_insert_with(|| {
+            let mut tessellator = egui::epaint::Tessellator::new(
+                pixels_per_point,
+                ctx.tessellation_options(|options| *options),
+                font_image_size,
+                ctx.fonts(|fonts| fonts.texture_atlas().lock().prepared_discs()),
+            );
+            let mut mesh = egui::Mesh::default();
+            tessellator.tessellate_shape(style.shape(egui::Pos2::ZERO, 1.0), &mut mesh);
+            mesh
+        })
+    }
+
+    /// Append the ball mesh for `style`, centered at `pos` and enlarged by `scale`.
+    fn add_ball(
+        &mut self,
+        ctx: &egui::Context,
+        target: &mut egui::Mesh,
+        style: BallStyle,
+        pos: egui::Pos2,
+        scale: f32,
+    ) {
+        let ball = self.mesh(ctx, style);
+        let base = target.vertices.len() as u32;
+        target
+            .indices
+            .extend(ball.indices.iter().map(|index| base + index));
+        target
+            .vertices
+            .extend(ball.vertices.iter().map(|vertex| egui::epaint::Vertex {
+                pos: pos + vertex.pos.to_vec2() * scale,
+                ..*vertex
+            }));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum Slot {
+    Empty,
+    Ball(usize),
+}
+
+impl Slot {
+    /// Compact form, for puzzle codes: `0` for an empty slot, the color index plus one otherwise.
+    pub fn code(self) -> Option<u8> {
+        match self {
+            Slot::Empty => Some(0),
+            Slot::Ball(color) => u8::try_from(color + 1).ok(),
+        }
+    }
+
+    /// Character of the slot in text boards: `.` if empty, the initial of the color otherwise.
+    pub fn initial(self) -> char {
+        match self {
+            Slot::Empty => '.',
+            Slot::Ball(color) => {
+                let initial = BALL_INITIALS[color % BALL_INITIALS.len()];
+                match BallTheme::from_index(color / BALL_INITIALS.len()) {
+                    BallTheme::Plain => initial,
+                    BallTheme::Hole => initial.to_ascii_lowercase(),
+                }
+            }
+        }
+    }
+
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0 => Slot::Empty,
+            code => Slot::Ball(usize::from(code) - 1),
+        }
+    }
+
+    pub fn color(&self, ctx: &egui::Context) -> BallStyle {
+        match self {
+            Slot::Empty => BallStyle {
+                color: ctx.style().visuals.code_bg_color,
+                theme: BallTheme::Plain,
+            },
+            Slot::Ball(color_idx) => BallStyle {
+                color: BALL_COLORS[*color_idx % BALL_COLORS.len()],
+                theme: BallTheme::from_index(*color_idx / BALL_COLORS.len()),
+            },
+        }
+    }
+}
+
+/// A ball taken from the top of one column and dropped onto another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Move {
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Seed from a counter such as a day or a week, so that consecutive values don't give related
+/// boards (splitmix64).
+fn mix_seed(value: u64) -> u64 {
+    let mut seed = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    seed ^ (seed >> 31)
+}
+
+/// Written with column labels, such as `C → F`.
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} → {}", column_label(self.from), column_label(self.to))
+    }
+}
+
+/// Board as a grid of color initials, top row first, with column labels underneath:
+///
+/// ```text
+/// Y . P
+/// P . Y
+/// A B C
+/// ```
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = column_label(self.column_count.saturating_sub(1)).len();
+        for row in 0..self.column_capacity {
+            let line: Vec<_> = (0..self.column_count)
+                .map(|col| format!("{:width$}", self.slot(row, col).initial()))
+                .collect();
+            writeln!(f, "{}", line.join(" ").trim_end())?;
+        }
+        let labels: Vec<_> = (0..self.column_count)
+            .map(|col| format!("{:width$}", column_label(col)))
+            .collect();
+        write!(f, "{}", labels.join(" ").trim_end())
+    }
+}
+
+/// Letter of a column, counting from `A` on the left, then `AA`, `AB`… past `Z`.
+fn column_label(column: usize) -> String {
+    let mut label = Vec::new();
+    let mut rest = column + 1;
+    while rest > 0 {
+        rest -= 1;
+        label.push(b'A' + (rest % 26) as u8);
+        rest /= 26;
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap_or_default()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Hint {
+    Searching,
+    Move(Move),
+    NotFound,
+}
+
+/// A game: its board, the moves played and the clock.
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct State {
+    column_count: usize,
+    column_capacity: usize,
+    play_count: usize,
+    slots: Vec<Slot>,
+
+    /// Time spent playing, excluding pauses.
+    #[serde(default)]
+    elapsed: Duration,
+
+    #[serde(default)]
+    paused: bool,
+
+    /// Seed the board was shuffled from, unless it came from elsewhere (older versions, a
+    /// challenge…).
+    #[serde(default)]
+    seed: Option<u64>,
+
+    /// Shuffled board the game started from, used to restart it.
+    #[serde(default)]
+    initial_slots: Vec<Slot>,
+
+    /// Moves played so far, most recent last.
+    #[serde(default)]
+    moves: Vec<Move>,
+
+    /// Undone moves, most recently undone last.
+    #[serde(default)]
+    undone_moves: Vec<Move>,
+
+    /// Whether the win was already added to the history.
+    #[serde(default)]
+    win_recorded: bool,
+
+    /// Whether the win was submitted to the leaderboard.
+    #[serde(default)]
+    score_submitted: bool,
+
+    /// Challenge this game was started from, to compare results once won.
+    #[serde(default)]
+    challenge: Option<challenge::Challenge>,
+
+    /// Week and index of the weekly challenge puzzle this game is.
+    #[serde(default)]
+    weekly: Option<(weekly::Week, usize)>,
+
+    /// Day of the daily puzzle this game is.
+    #[serde(default)]
+    daily: Option<daily::Day>,
+
+    /// Whether the remaining balls were reshuffled, which is allowed once per game.
+    #[serde(default)]
+    reshuffled: bool,
+
+    /// Moves added to `play_count` for using powerups.
+    #[serde(default)]
+    penalty: usize,
+
+    /// Moves left during which balls may be dropped onto the temporary extra column, the last
+    /// one, if the game has it. The column goes away once emptied after that, and must be for
+    /// the game to be won.
+    #[serde(default)]
+    extra_column: Option<usize>,
+
+    /// Number of `moves` when the extra column was added, the moves past it spending its budget.
+    #[serde(default)]
+    extra_column_added: usize,
+
+    /// Whether the temporary extra column was granted, which is allowed once per game.
+    #[serde(default)]
+    extra_column_used: bool,
+
+    /// Hints asked for, out of [`HINT_BUDGET`]. Restarting doesn't give them back, the board
+    /// having been hinted all the same.
+    #[serde(default)]
+    hints_used: usize,
+
+    /// Moves taken back during the game.
+    #[serde(default)]
+    undos: usize,
+
+    /// Fewest moves solving the initial board, looked for once the game is won. Unknown if the
+    /// search gave up, as it does on the larger boards.
+    #[serde(default)]
+    par: Option<usize>,
+
+    #[serde(default)]
+    rules: Rules,
+
+    #[serde(default)]
+    mode: Mode,
+
+    /// Per-column uniformity, kept up to date by moves. Empty until [`Self::refresh_cache`] is
+    /// called, in which case [`Self::is_winning`] falls back to scanning the board.
+    #[serde(skip)]
+    sorted_columns: Vec<bool>,
+
+    /// Answer to the last hint request, until the board changes.
+    #[serde(skip)]
+    hint: Option<Hint>,
+
+    /// Column whose top ball was tapped, to be moved onto the next column tapped (kid mode).
+    #[serde(skip)]
+    selected_column: Option<usize>,
+
+    /// Column picked with the shift+mouse wheel to receive the selected column's top ball, on the
+    /// next click.
+    #[serde(skip)]
+    target_column: Option<usize>,
+
+    /// Mouse wheel scrolling over the board not yet turned into column steps.
+    #[serde(skip)]
+    wheel_scroll: egui::Vec2,
+
+    /// Moves played when the stuck warning was dismissed, so that it's quiet for a while.
+    #[serde(skip)]
+    stuck_dismissed: Option<usize>,
+
+    /// Seconds into the balls dropping into place as the game starts, until they all have. Games
+    /// loaded from a save are shown at once.
+    #[serde(skip)]
+    intro: Option<f32>,
+}
+
+impl State {
+    pub fn new(column_count: usize, column_capacity: usize) -> Self {
+        Self::from_seed(column_count, column_capacity, rand::random())
+    }
+
+    /// Game shuffled from `seed`, to the same board on every platform.
+    pub fn from_seed(column_count: usize, column_capacity: usize, seed: u64) -> Self {
+        Self::shuffled(
+            column_count,
+            column_capacity,
+            column_count.saturating_sub(1),
+            Goal::Colors,
+            seed,
+        )
+    }
+
+    /// Game played as `config` says, shuffled from `seed`.
+    pub(crate) fn from_config(config: GameConfig, seed: u64) -> Self {
+        let mut state = Self::shuffled(
+            config.column_count,
+            config.column_capacity,
+            config.colors(),
+            config.mode.goal(),
+            seed,
+        );
+        state.rules = config.rules;
+        state.mode = config.mode;
+        state
+    }
+
+    /// `color_count` columns worth of balls shuffled from `seed`, the remaining columns being
+    /// empty. They're as many sets of numbers as there are colors when the goal is numbers.
+    fn shuffled(
+        column_count: usize,
+        column_capacity: usize,
+        color_count: usize,
+        goal: Goal,
+        seed: u64,
+    ) -> Self {
+        let mut slots = vec![Slot::Empty; column_count * column_capacity];
+        for col in 0..color_count {
+            for row in 0..column_capacity {
+                slots[col * column_capacity + row] = match goal {
+                    Goal::Colors => Slot::Ball(col),
+                    Goal::Numbers => Slot::Ball(column_capacity - 1 - row),
+                };
+            }
+        }
+
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+        shuffle(&mut slots[0..color_count * column_capacity], &mut rng);
+
+        let mut state = Self::with_slots(column_count, column_capacity, slots);
+        state.seed = Some(seed);
+        state
+    }
+
+    /// Variant of a game shuffled from `seed`, with [`REMIX_SWAPS`] pairs of balls of different
+    /// colors swapped, picked with `remix`. The result isn't shuffled from a seed of its own.
+    pub(crate) fn remixed(config: GameConfig, seed: u64, remix: u64) -> Self {
+        let color_count = config.colors();
+        let mut state = Self::from_config(config, seed);
+
+        let balls = color_count * config.column_capacity;
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(mix_seed(seed ^ mix_seed(remix)));
+        let mut swaps = 0;
+        // give up eventually, should all balls be of the same color
+        for _ in 0..100 * REMIX_SWAPS {
+            if swaps == REMIX_SWAPS {
+                break;
+            }
+            let a = (rng.next_u64() % balls as u64) as usize;
+            let b = (rng.next_u64() % balls as u64) as usize;
+            if state.slots[a] != state.slots[b] {
+                state.slots.swap(a, b);
+                swaps += 1;
+            }
+        }
+
+        let mut state = Self::with_slots(state.column_count, state.column_capacity, state.slots);
+        state.rules = config.rules;
+        state.mode = config.mode;
+        state
+    }
+
+    /// The same board from the start, as a new game.
+    pub fn replayed(&self) -> Option<Self> {
+        let mut state = puzzle::Puzzle::of_game(self)?.game()?;
+        state.seed = self.seed;
+        state.mode = self.mode;
+        Some(state)
+    }
+
+    /// Game starting from an arrangement of slot codes (see [`Slot::code`]), column by column, if
+    /// it holds together: as many balls of each color as fit in a column (or of each number as
+    /// there are full columns, when the goal is numbers), and one column worth of room or more.
+    pub fn from_codes(
+        column_count: usize,
+        column_capacity: usize,
+        goal: Goal,
+        codes: &[u8],
+    ) -> Option<Self> {
+        if !(2..=MAX_COLUMN_CAPACITY).contains(&column_capacity)
+            || column_count > BallStyle::MAX_STYLES + 2
+        {
+            return None;
+        }
+        if codes.len() != column_count * column_capacity {
+            return None;
+        }
+        let mut counts = vec![0; column_count];
+        for &code in codes {
+            *counts.get_mut(usize::from(code))? += 1;
+        }
+        let empty_columns = counts[0] / column_capacity;
+        if !(1..=2).contains(&empty_columns) || !counts[0].is_multiple_of(column_capacity) {
+            return None;
+        }
+        // only boards the setup screen could have set up
+        let color_count = column_count.saturating_sub(empty_columns);
+        if !(3..=BallStyle::MAX_STYLES).contains(&color_count) {
+            return None;
+        }
+        let (kinds, each) = match goal {
+            Goal::Colors => (color_count, column_capacity),
+            Goal::Numbers => (column_capacity, color_count),
+        };
+        let expected = |kind: usize| if kind < kinds { each } else { 0 };
+        if (counts[1..].iter().enumerate()).any(|(kind, &count)| count != expected(kind)) {
+            return None;
+        }
+
+        let slots = codes.iter().copied().map(Slot::from_code).collect();
+        Some(Self::with_slots(column_count, column_capacity, slots))
+    }
+
+    /// Arrangement the game started from, as slot codes.
+    pub fn initial_codes(&self) -> Option<Vec<u8>> {
+        self.initial_slots.iter().map(|slot| slot.code()).collect()
+    }
+
+    /// Game starting from the given arrangement, column by column.
+    pub(crate) fn with_slots(
+        column_count: usize,
+        column_capacity: usize,
+        slots: Vec<Slot>,
+    ) -> Self {
+        let mut state = Self {
+            column_count,
+            column_capacity,
+            play_count: 0,
+            initial_slots: slots.clone(),
+            slots,
+            elapsed: Duration::ZERO,
+            paused: false,
+            moves: Vec::new(),
+            undone_moves: Vec::new(),
+            win_recorded: false,
+            score_submitted: false,
+            challenge: None,
+            weekly: None,
+            daily: None,
+            reshuffled: false,
+            penalty: 0,
+            extra_column: None,
+            extra_column_added: 0,
+            extra_column_used: false,
+            hints_used: 0,
+            undos: 0,
+            par: None,
+            seed: None,
+            rules: Rules::default(),
+            mode: Mode::default(),
+            sorted_columns: Vec::new(),
+            hint: None,
+            selected_column: None,
+            target_column: None,
+            wheel_scroll: egui::Vec2::ZERO,
+            stuck_dismissed: None,
+            intro: Some(0.0),
+        };
+        state.refresh_cache();
+        state
+    }
+
+    pub fn color_count(&self) -> usize {
+        self.config().colors()
+    }
+
+    /// How this game is played, which sets it apart in the history.
+    pub(crate) fn config(&self) -> GameConfig {
+        let balls = self
+            .slots
+            .iter()
+            .filter(|slot| **slot != Slot::Empty)
+            .count();
+        let column_count = self.base_column_count();
+        GameConfig {
+            column_count,
+            column_capacity: self.column_capacity,
+            empty_columns: column_count.saturating_sub(balls / self.column_capacity.max(1)),
+            rules: self.rules,
+            mode: self.mode,
+        }
+    }
+
+    /// Rules the game is played by.
+    pub fn rule_set(&self) -> &'static dyn RuleSet {
+        self.rules.rule_set()
+    }
+
+    /// Rebuild derived data that isn't persisted.
+    pub fn refresh_cache(&mut self) {
+        let board = PackedBoard::from(&*self);
+        self.sorted_columns = (0..self.column_count)
+            .map(|col| self.rule_set().is_column_finished(&board, col))
+            .collect();
+    }
+
+    /// Whether `column` is finished, as [`RuleSet::is_column_finished`] would tell. Only scripts
+    /// need the whole board packed, the other rules being checked on the column alone.
+    fn is_column_sorted(&self, column: usize) -> bool {
+        if self.rules == Rules::Scripted {
+            return self
+                .rule_set()
+                .is_column_finished(&PackedBoard::from(self), column);
+        }
+        let height = self.column_capacity - self.first_ball(column).unwrap_or(self.column_capacity);
+        height == 0 || (height == self.column_capacity && self.base_run(column) == height)
+    }
+
+    /// Balls stacked from the bottom of `column` as they'll be once it's finished: of the bottom
+    /// ball's color, or numbered in order from 1.
+    fn base_run(&self, column: usize) -> usize {
+        let bottom = self.column_capacity - 1;
+        let base = self.slot(bottom, column);
+        if base == Slot::Empty {
+            return 0;
+        }
+        let goal = self.mode.goal();
+        (0..self.column_capacity)
+            .rev()
+            .take_while(|&row| match goal {
+                Goal::Colors => self.slot(row, column) == base,
+                Goal::Numbers => self.slot(row, column) == Slot::Ball(bottom - row),
+            })
+            .count()
+    }
+
+    /// Balls of each color not yet in their final run, the longest run of the color at the bottom
+    /// of a column.
+    pub fn unsorted_balls(&self) -> Vec<usize> {
+        let mut longest_runs = vec![0; self.color_count()];
+        for column in 0..self.column_count {
+            if let Slot::Ball(color) = self.slot(self.column_capacity - 1, column) {
+                if let Some(longest) = longest_runs.get_mut(color) {
+                    *longest = (*longest).max(self.base_run(column));
+                }
+            }
+        }
+        longest_runs
+            .into_iter()
+            .map(|run| self.column_capacity - run)
+            .collect()
+    }
+
+    fn update_sorted_column(&mut self, column: usize) {
+        if column < self.sorted_columns.len() {
+            self.sorted_columns[column] = self.is_column_sorted(column);
+        }
+    }
+
+    /// Advance the game clock by `dt` seconds, unless the game is paused or won.
+    ///
+    /// Only called while the game is on screen, so time spent in other tabs isn't counted.
+    /// Returns the time counted.
+    pub fn tick(&mut self, dt: f32) -> Duration {
+        // the clock starts once the balls are in place
+        if let Some(intro) = &mut self.intro {
+            *intro += dt;
+            if *intro >= INTRO_DURATION + INTRO_FALL {
+                self.intro = None;
+            }
+            return Duration::ZERO;
+        }
+        if self.paused || self.is_winning().is_some() {
+            return Duration::ZERO;
+        }
+        let played = Duration::from_secs_f32(dt.clamp(0.0, MAX_TICK));
+        self.elapsed += played;
+        played
+    }
+
+    pub fn summary(&self) -> Msg {
+        Msg::GameSummary {
+            colors: self.color_count(),
+            height: self.column_capacity,
+            moves: self.play_count,
+        }
+    }
+
+    pub(crate) fn slot(&self, row: usize, column: usize) -> Slot {
+        self.slots[column * self.column_capacity + row]
+    }
+
+    fn slot_mut(&mut self, row: usize, column: usize) -> &mut Slot {
+        &mut self.slots[column * self.column_capacity + row]
+    }
+
+    /// Play a move, returning whether it was possible.
+    pub fn move_ball(&mut self, from_column: usize, to_column: usize) -> bool {
+        let mv = Move {
+            from: from_column,
+            to: to_column,
+        };
+        if !self.legal_moves().contains(&mv) {
+            return false;
+        }
+
+        let moved = self.apply_move(from_column, to_column);
+        if moved {
+            self.play_count += 1;
+            self.moves.push(mv);
+            self.undone_moves.clear();
+            self.update_extra_column_budget();
+            self.settle_extra_column();
+        }
+        moved
+    }
+
+    /// Columns of the board, without the temporary extra column.
+    fn base_column_count(&self) -> usize {
+        self.column_count - usize::from(self.extra_column.is_some())
+    }
+
+    pub fn hints_left(&self) -> usize {
+        HINT_BUDGET.saturating_sub(self.hints_used)
+    }
+
+    pub fn can_add_extra_column(&self) -> bool {
+        !self.extra_column_used && self.is_winning().is_none()
+    }
+
+    /// Add an empty column for [`EXTRA_COLUMN_MOVES`] moves, for a penalty of
+    /// [`EXTRA_COLUMN_PENALTY`] moves.
+    pub fn add_extra_column(&mut self) {
+        if !self.can_add_extra_column() {
+            return;
+        }
+
+        self.column_count += 1;
+        self.slots
+            .extend(std::iter::repeat_n(Slot::Empty, self.column_capacity));
+        self.refresh_cache();
+        self.extra_column = Some(EXTRA_COLUMN_MOVES);
+        self.extra_column_added = self.moves.len();
+        self.extra_column_used = true;
+        self.penalty += EXTRA_COLUMN_PENALTY;
+        self.play_count += EXTRA_COLUMN_PENALTY;
+        self.hint = None;
+        self.selected_column = None;
+        self.target_column = None;
+    }
+
+    /// Count the moves left on the extra column from the moves played since it was added, so that
+    /// undoing a move gives its move back and redoing it spends it again. Undoing moves played
+    /// before the column was added leaves it as if added then.
+    fn update_extra_column_budget(&mut self) {
+        if self.extra_column.is_some() {
+            self.extra_column_added = self.extra_column_added.min(self.moves.len());
+            let spent = self.moves.len() - self.extra_column_added;
+            self.extra_column = Some(EXTRA_COLUMN_MOVES.saturating_sub(spent));
+        }
+    }
+
+    /// Remove the temporary extra column once it's empty, and either out of moves or the only
+    /// thing left between the player and the win.
+    fn settle_extra_column(&mut self) {
+        let Some(moves_left) = self.extra_column else {
+            return;
+        };
+        let extra = self.column_count - 1;
+        if self.first_ball(extra).is_some() {
+            return;
+        }
+        if moves_left > 0 && !(0..extra).all(|col| self.is_column_sorted(col)) {
+            return;
+        }
+
+        self.column_count -= 1;
+        self.slots
+            .truncate(self.column_count * self.column_capacity);
+        self.refresh_cache();
+        self.extra_column = None;
+        // moves to or from the column can't be undone anymore
+        self.moves.clear();
+        self.undone_moves.clear();
+        self.selected_column = None;
+        self.target_column = None;
+    }
+
+    /// Move the top ball of `from_column` to `to_column`, if possible.
+    fn apply_move(&mut self, from_column: usize, to_column: usize) -> bool {
+        if !self
+            .rule_set()
+            .allows_move(&PackedBoard::from(&*self), from_column, to_column)
+        {
+            return false;
+        }
+        self.relocate_ball(from_column, to_column)
+    }
+
+    /// Move the top ball of `from_column` to `to_column` whatever the rules say, if there's a ball
+    /// and room for it. Undoing and redoing go through this, as the reverse of a legal move
+    /// needn't be legal itself.
+    fn relocate_ball(&mut self, from_column: usize, to_column: usize) -> bool {
+        if let (Some(from_row), Some(to_row)) =
+            (self.first_ball(from_column), self.first_empty(to_column))
+        {
+            let ball = self.slot(from_row, from_column);
+            self.slot_mut(to_row, to_column).clone_from(&ball);
+            self.slot_mut(from_row, from_column)
+                .clone_from(&Slot::Empty);
+            self.update_sorted_column(from_column);
+            self.update_sorted_column(to_column);
+            self.hint = None;
+            self.selected_column = None;
+            self.target_column = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.moves.is_empty() && self.is_winning().is_none()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone_moves.is_empty()
+    }
+
+    pub fn can_restart(&self) -> bool {
+        self.initial_slots.len() == self.base_column_count() * self.column_capacity
+    }
+
+    pub fn undo(&mut self) {
+        if !self.can_undo() {
+            return;
+        }
+
+        let Some(&mv) = self.moves.last() else {
+            return;
+        };
+        // the ball came from there, so there is room to put it back
+        if self.relocate_ball(mv.to, mv.from) {
+            self.moves.pop();
+            self.play_count = self.rule_set().play_count_after_undo(self.play_count);
+            self.undos += 1;
+            self.undone_moves.push(mv);
+            self.update_extra_column_budget();
+            self.settle_extra_column();
+        }
+    }
+
+    pub fn redo(&mut self) {
+        let Some(&mv) = self.undone_moves.last() else {
+            return;
+        };
+        if self.relocate_ball(mv.from, mv.to) {
+            self.undone_moves.pop();
+            self.play_count += 1;
+            self.moves.push(mv);
+            self.update_extra_column_budget();
+            self.settle_extra_column();
+        }
+    }
+
+    /// Start over from the initial shuffle.
+    pub fn restart(&mut self) {
+        if !self.can_restart() {
+            return;
+        }
+
+        self.column_count = self.base_column_count();
+        self.slots.clone_from(&self.initial_slots);
+        self.extra_column = None;
+        self.extra_column_added = 0;
+        self.extra_column_used = false;
+        self.refresh_cache();
+        self.play_count = 0;
+        self.elapsed = Duration::ZERO;
+        self.paused = false;
+        self.moves.clear();
+        self.undone_moves.clear();
+        self.win_recorded = false;
+        self.score_submitted = false;
+        self.reshuffled = false;
+        self.penalty = 0;
+        self.hint = None;
+    }
+
+    pub fn can_reshuffle(&self) -> bool {
+        !self.reshuffled && self.is_winning().is_none()
+    }
+
+    /// Shuffle the balls outside of completed columns among the slots they occupy, for a
+    /// penalty of [`RESHUFFLE_PENALTY`] moves.
+    pub fn reshuffle(&mut self, rng: &mut impl rand::RngCore) {
+        if !self.can_reshuffle() {
+            return;
+        }
+
+        let positions: Vec<_> = (0..self.column_count)
+            .filter(|&col| !self.is_column_sorted(col))
+            .flat_map(|col| col * self.column_capacity..(col + 1) * self.column_capacity)
+            .filter(|&index| self.slots[index] != Slot::Empty)
+            .collect();
+        let mut balls: Vec<_> = positions.iter().map(|&index| self.slots[index]).collect();
+        shuffle(&mut balls, rng);
+        for (index, ball) in positions.into_iter().zip(balls) {
+            self.slots[index] = ball;
+        }
+        self.refresh_cache();
+
+        self.reshuffled = true;
+        self.penalty += RESHUFFLE_PENALTY;
+        self.play_count += RESHUFFLE_PENALTY;
+        // earlier moves don't apply to the new arrangement
+        self.moves.clear();
+        self.undone_moves.clear();
+        self.hint = None;
+        self.selected_column = None;
+        self.target_column = None;
+    }
+
+    /// Board the game started from.
+    fn initial_board(&self) -> PackedBoard {
+        let mut initial = self.clone();
+        initial.column_count = self.base_column_count();
+        initial.slots.clone_from(&self.initial_slots);
+        PackedBoard::from(&initial)
+    }
+
+    /// Stars earned by the win, once rated.
+    pub fn stars(&self) -> Option<usize> {
+        self.is_winning()
+            .zip(self.par)
+            .map(|(play_count, par)| stars(play_count, par))
+    }
+
+    /// Whether the game may go on the leaderboard, where it's replayed from its moves.
+    pub fn is_ranked(&self) -> bool {
+        !self.config().is_handicapped()
+            && self.penalty == 0
+            && self.rule_set().is_ranked()
+            && self.mode.topology() == Topology::Grid
+            && self.mode.goal() == Goal::Colors
+    }
+
+    // return play count if winning
+    pub fn is_winning(&self) -> Option<usize> {
+        profile_scope!("win detection");
+        if self.extra_column.is_some() {
+            return None;
+        }
+        let won = if self.sorted_columns.len() == self.column_count {
+            self.sorted_columns.iter().all(|&sorted| sorted)
+        } else {
+            (0..self.column_count).all(|col| self.is_column_sorted(col))
+        };
+
+        if won {
+            Some(self.play_count)
+        } else {
+            None
+        }
+    }
+
+    pub fn first_empty(&self, column: usize) -> Option<usize> {
+        (0..self.column_capacity)
+            .rev()
+            .into_iter()
+            .find(|&row| self.slot(row, column) == Slot::Empty)
+    }
+
+    pub fn first_ball(&self, column: usize) -> Option<usize> {
+        (0..self.column_capacity)
+            .into_iter()
+            .find(|&row| self.slot(row, column) != Slot::Empty)
+    }
+
+    /// Width of a column and height of a row.
+    fn slot_size(&self) -> f32 {
+        30.0 * self.mode.ball_scale()
+    }
+
+    fn board_size(&self, view: BoardView) -> egui::Vec2 {
+        if self.is_circular(view) {
+            let radius = self.inner_radius()
+                + self.slot_size() * self.column_capacity as f32
+                + self.label_room();
+            return egui::Vec2::splat(2.0 * radius);
+        }
+        self.slot_size()
+            * (self.mode.topology()).board_size(self.column_count, self.column_capacity)
+    }
+
+    /// Whether the columns are drawn around a circle, which only grids can be.
+    fn is_circular(&self, view: BoardView) -> bool {
+        view.circular && self.mode.topology() == Topology::Grid
+    }
+
+    /// Distance from the center of a circular board to the top of the columns, so that the
+    /// innermost balls of neighboring columns don't overlap.
+    fn inner_radius(&self) -> f32 {
+        let size = self.slot_size();
+        let touching = size / (2.0 * (PI / self.column_count.max(2) as f32).sin()) - size / 2.0;
+        touching.max(size / 2.0)
+    }
+
+    /// How `slot` is drawn. Numbered balls are all plain, their number telling them apart.
+    fn ball_style(&self, ctx: &egui::Context, slot: Slot) -> BallStyle {
+        let style = slot.color(ctx);
+        match self.mode.goal() {
+            Goal::Colors => style,
+            Goal::Numbers => BallStyle {
+                theme: BallTheme::Plain,
+                ..style
+            },
+        }
+    }
+
+    /// Write the number of a numbered ball over it.
+    fn paint_number(&self, painter: &egui::Painter, slot: Slot, pos: egui::Pos2, scale: f32) {
+        let (Goal::Numbers, Slot::Ball(number)) = (self.mode.goal(), slot) else {
+            return;
+        };
+        let background = self.ball_style(painter.ctx(), slot).color;
+        let color = if egui::Rgba::from(background).intensity() > 0.3 {
+            egui::Color32::BLACK
+        } else {
+            egui::Color32::WHITE
+        };
+        painter.text(
+            pos,
+            egui::Align2::CENTER_CENTER,
+            number + 1,
+            egui::FontId::proportional(13.0 * scale),
+            color,
+        );
+    }
+
+    /// Room under the columns for their labels.
+    fn label_room(&self) -> f32 {
+        // kids go by colors only
+        if self.mode == Mode::Kid {
+            0.0
+        } else {
+            28.0
+        }
+    }
+
+    /// Turn mouse wheel scrolling over the board into column selection: the wheel cycles through
+    /// the columns a ball can be taken from, shift+wheel through the columns it can go to.
+    fn wheel_select(&mut self, ui: &egui::Ui, mirrored: bool) {
+        let (delta, shift) = ui.input_mut(|i| {
+            let delta = i.raw_scroll_delta;
+            // the board takes the scrolling, not the panel it's in
+            i.raw_scroll_delta = egui::Vec2::ZERO;
+            i.smooth_scroll_delta = egui::Vec2::ZERO;
+            (delta, i.modifiers.shift)
+        });
+        // some platforms turn shift+wheel into horizontal scrolling already
+        if shift || delta.x != 0.0 {
+            self.wheel_scroll.x += delta.x + delta.y;
+        } else {
+            self.wheel_scroll.y += delta.y;
+        }
+
+        // scrolling down goes right, whichever way the columns are laid out
+        let direction = if mirrored { 1 } else { -1 };
+        let steps = |scroll: &mut f32| {
+            let steps = (*scroll / WHEEL_STEP).trunc();
+            *scroll -= steps * WHEEL_STEP;
+            steps as isize * direction
+        };
+        let cycle = |columns: Vec<usize>, current: Option<usize>, steps: isize| {
+            let position = current.and_then(|col| columns.iter().position(|&c| c == col));
+            let position = match position {
+                Some(position) => position as isize + steps,
+                None if steps > 0 => steps - 1,
+                None => steps,
+            };
+            (!columns.is_empty())
+                .then(|| columns[position.rem_euclid(columns.len() as isize) as usize])
+        };
+
+        let source_steps = steps(&mut self.wheel_scroll.y);
+        if source_steps != 0 {
+            let columns = (0..self.column_count)
+                .filter(|&col| self.first_ball(col).is_some())
+                .collect();
+            self.selected_column = cycle(columns, self.selected_column, source_steps);
+            self.target_column = None;
+        }
+        let target_steps = steps(&mut self.wheel_scroll.x);
+        if let Some(from) = self.selected_column.filter(|_| target_steps != 0) {
+            let columns = self
+                .legal_moves()
+                .into_iter()
+                .filter(|mv| mv.from == from)
+                .map(|mv| mv.to)
+                .collect();
+            self.target_column = cycle(columns, self.target_column, target_steps);
+        }
+    }
+
+    /// Draw the board as `view` says.
+    ///
+    /// Returns whether a move was played.
+    pub fn ui(&mut self, ui: &mut egui::Ui, view: BoardView) -> bool {
+        profile_scope!("board");
+        let mut moved = false;
+        let won = self.is_winning().is_some();
+        let (board_rect, _) = ui.allocate_exact_size(self.board_size(view), Sense::hover());
+        let (mirrored, circular) = (view.mirrored, self.is_circular(view));
+        if self.intro.is_some() && ui.input(|i| i.pointer.any_pressed() || !i.keys_down.is_empty())
+        {
+            self.intro = None;
+        }
+        let intro = self.intro;
+        if intro.is_some() {
+            ui.ctx().request_repaint();
+        }
+        if !won && intro.is_none() && ui.rect_contains_pointer(board_rect) {
+            self.wheel_select(ui, mirrored);
+        }
+        // balls drop column by column, from the bottom up
+        let ball_count = self
+            .slots
+            .iter()
+            .filter(|&&slot| slot != Slot::Empty)
+            .count();
+        let drop_interval = INTRO_DURATION / ball_count.max(1) as f32;
+        let mut dropped = 0;
+        // painted over the slots they fall past
+        let mut falling = Vec::new();
+        // balls whose number is painted over the board
+        let mut numbered = Vec::new();
+        let dragged_col = egui::DragAndDrop::payload::<usize>(ui.ctx()).map(|col| *col);
+        let dragged_slot = dragged_col.and_then(|col| self.first_ball(col).map(|row| (row, col)));
+
+        let (scale, size) = (self.mode.ball_scale(), self.slot_size());
+        let (column_count, column_capacity) = (self.column_count, self.column_capacity);
+        let topology = self.mode.topology();
+        let legal_moves = self.legal_moves();
+        let board_slots = topology.board_size(column_count, column_capacity);
+        let inner_radius = self.inner_radius();
+        // ends of the line through the middle of a column, top first
+        let column_axis = |col: usize| {
+            let index = if mirrored {
+                column_count - 1 - col
+            } else {
+                col
+            };
+            if circular {
+                // clockwise from the top, balls stacking toward the center
+                let angle = TAU * index as f32 / column_count as f32 - FRAC_PI_2;
+                let direction = egui::Vec2::angled(angle);
+                let top = board_rect.center() + direction * inner_radius;
+                (top, top + direction * size * column_capacity as f32)
+            } else {
+                let mut origin = topology.column_origin(col, column_capacity);
+                if mirrored {
+                    origin.x = board_slots.x - 1.0 - origin.x;
+                }
+                let top = board_rect.min + size * origin + vec2(size / 2.0, 0.0);
+                (top, top + vec2(0.0, size * column_capacity as f32))
+            }
+        };
+        let slot_center = |row: usize, col: usize| {
+            let (top, bottom) = column_axis(col);
+            top.lerp(bottom, (row as f32 + 0.5) / column_capacity as f32)
+        };
+        // a column as wide as a slot, rounded when it's upright
+        let column_shape = |col: usize, fill: egui::Color32, stroke: egui::Stroke| {
+            let (top, bottom) = column_axis(col);
+            if circular {
+                let across = (bottom - top).normalized().rot90() * size / 2.0;
+                let corners = vec![top - across, top + across, bottom + across, bottom - across];
+                egui::Shape::convex_polygon(corners, fill, stroke)
+            } else {
+                let rect = egui::Rect::from_two_pos(top, bottom).expand2(vec2(size / 2.0, 0.0));
+                let rect = if stroke.is_empty() {
+                    rect
+                } else {
+                    rect.shrink(1.0)
+                };
+                egui::epaint::RectShape::new(rect, 6.0, fill, stroke).into()
+            }
+        };
+        // taps move balls too in kid mode, the first one picking a ball and the second dropping it,
+        // and a click plays the move picked with the mouse wheel
+        let sense = if self.mode == Mode::Kid || self.target_column.is_some() {
+            Sense::click_and_drag()
+        } else {
+            Sense::drag()
+        };
+
+        let mesh_cache_id = egui::Id::new("ball_mesh_cache");
+        let mesh_cache = ui.data_mut(|data| {
+            data.get_temp_mut_or_default::<Arc<egui::mutex::Mutex<BallMeshCache>>>(mesh_cache_id)
+                .clone()
+        });
+        let mut mesh_cache = mesh_cache.lock();
+        let mut mesh = egui::Mesh::default();
+        // slot where the dragged ball would land if released now
+        let mut landing = self
+            .target_column
+            .and_then(|col| self.first_empty(col).map(|row| (row, col)));
+
+        for index in 0..self.column_count {
+            let col = if mirrored {
+                self.column_count - 1 - index
+            } else {
+                index
+            };
+            let (column_top, column_bottom) = column_axis(col);
+
+            if self.extra_column.is_some() && col == self.column_count - 1 {
+                ui.painter().add(column_shape(
+                    col,
+                    ui.visuals().faint_bg_color,
+                    egui::Stroke::NONE,
+                ));
+            }
+
+            // one interaction area per column, so that a drag starting anywhere on the column picks
+            // its top ball, or one per slot when the column is slanted
+            let response = if circular {
+                let slot_response = |row: usize| {
+                    let rect = egui::Rect::from_center_size(
+                        slot_center(row, col),
+                        egui::Vec2::splat(size),
+                    );
+                    ui.interact(rect, ui.id().with(("column", col, row)), sense)
+                };
+                (1..column_capacity).fold(slot_response(0), |response, row| {
+                    response.union(slot_response(row))
+                })
+            } else {
+                let rect = egui::Rect::from_two_pos(column_top, column_bottom)
+                    .expand2(vec2(size / 2.0, 0.0));
+                ui.interact(rect, ui.id().with(("column", col)), sense)
+            };
+            response.widget_info(|| {
+                let balls = self
+                    .first_ball(col)
+                    .map_or(0, |row| self.column_capacity - row);
+                egui::WidgetInfo::labeled(
+                    egui::WidgetType::Button,
+                    Msg::ColumnInfo { column: col, balls },
+                )
+            });
+            if !won && intro.is_none() && self.first_ball(col).is_some() {
+                response.dnd_set_drag_payload(col);
+            }
+            let hovering = response.dnd_hover_payload::<usize>();
+            if hovering.is_some_and(|from| {
+                legal_moves.contains(&Move {
+                    from: *from,
+                    to: col,
+                })
+            }) {
+                landing = self.first_empty(col).map(|row| (row, col));
+            }
+            let other: Option<Arc<usize>> = response.dnd_release_payload();
+            if let Some(other_col) = other {
+                moved |= self.move_ball(*other_col, col);
+            }
+            if let (Some(from), Some(to)) = (self.selected_column, self.target_column) {
+                if !won && intro.is_none() && response.clicked() {
+                    moved |= self.move_ball(from, to);
+                }
+            } else if !won && intro.is_none() && response.clicked() {
+                // a tap that can't drop the ball picks another one rather than doing nothing
+                let selected = self.selected_column.take();
+                if selected.is_some_and(|from| from != col && self.move_ball(from, col)) {
+                    moved = true;
+                } else if selected != Some(col) && self.first_ball(col).is_some() {
+                    self.selected_column = Some(col);
+                }
+            }
+            if self.selected_column == Some(col) {
+                ui.painter().add(column_shape(
+                    col,
+                    egui::Color32::TRANSPARENT,
+                    egui::Stroke::new(3.0, ui.visuals().selection.stroke.color),
+                ));
+            }
+            if self.target_column == Some(col) {
+                ui.painter().add(column_shape(
+                    col,
+                    egui::Color32::TRANSPARENT,
+                    egui::Stroke::new(3.0, ui.visuals().selection.stroke.color.gamma_multiply(0.5)),
+                ));
+            }
+
+            for row in (0..self.column_capacity).rev() {
+                let slot = if dragged_slot == Some((row, col)) {
+                    Slot::Empty
+                } else {
+                    self.slot(row, col)
+                };
+                let center = slot_center(row, col);
+                let Some(time) = intro.filter(|_| slot != Slot::Empty) else {
+                    let style = self.ball_style(ui.ctx(), slot);
+                    mesh_cache.add_ball(ui.ctx(), &mut mesh, style, center, scale);
+                    numbered.push((slot, center));
+                    continue;
+                };
+
+                let empty = Slot::Empty.color(ui.ctx());
+                mesh_cache.add_ball(ui.ctx(), &mut mesh, empty, center, scale);
+                let fall = (time - drop_interval * dropped as f32) / INTRO_FALL;
+                dropped += 1;
+                if fall > 0.0 {
+                    // easing out, as if landing
+                    let fall = 1.0 - (1.0 - fall.min(1.0)).powi(2);
+                    let top = column_top - (column_bottom - column_top).normalized() * size / 2.0;
+                    falling.push((slot, top.lerp(center, fall)));
+                }
+            }
+        }
+
+        for (slot, center) in falling {
+            let style = self.ball_style(ui.ctx(), slot);
+            mesh_cache.add_ball(ui.ctx(), &mut mesh, style, center, scale);
+            numbered.push((slot, center));
+        }
+        ui.painter().add(egui::Shape::mesh(mesh));
+        for (slot, center) in numbered {
+            self.paint_number(ui.painter(), slot, center, scale);
+        }
+
+        if self.label_room() > 0.0 {
+            // circular boards leave room for the labels all around already
+            let gap = if circular {
+                0.0
+            } else {
+                let (labels_rect, _) = ui.allocate_exact_size(
+                    vec2(board_rect.width(), self.label_room()),
+                    Sense::hover(),
+                );
+                labels_rect.min.y - board_rect.max.y
+            };
+            for col in 0..self.column_count {
+                // beyond the bottom of each column, wherever it is
+                let (top, bottom) = column_axis(col);
+                let label_pos = |offset: f32| bottom + (bottom - top).normalized() * (gap + offset);
+                ui.painter().text(
+                    label_pos(8.0),
+                    egui::Align2::CENTER_CENTER,
+                    column_label(col),
+                    egui::FontId::monospace(12.0),
+                    ui.visuals().weak_text_color(),
+                );
+
+                // how far the column is from being finished, from the bottom up
+                let run = self.base_run(col);
+                if run > 0 && run < self.column_capacity {
+                    ui.painter().text(
+                        label_pos(21.0),
+                        egui::Align2::CENTER_CENTER,
+                        format!("{run}/{}", self.column_capacity),
+                        egui::FontId::proportional(9.0),
+                        ui.visuals().weak_text_color(),
+                    );
+                }
+            }
+        }
+
+        if let Some(Hint::Move(mv)) = self.hint {
+            if let (Some(from_row), Some(to_row)) =
+                (self.first_ball(mv.from), self.first_empty(mv.to))
+            {
+                let from = slot_center(from_row, mv.from);
+                let to = slot_center(to_row, mv.to);
+                ui.painter().arrow(
+                    from,
+                    to - from,
+                    egui::Stroke::new(3.0, ui.visuals().selection.stroke.color),
+                );
+            }
+        }
+
+        // the ball being dragged, or the one picked with the mouse wheel
+        let picked = dragged_slot.or_else(|| {
+            let from = self
+                .selected_column
+                .filter(|_| self.target_column.is_some())?;
+            self.first_ball(from).map(|row| (row, from))
+        });
+        if let (Some((picked_row, picked_col)), Some((row, col))) = (picked, landing) {
+            let style = self.ball_style(ui.ctx(), self.slot(picked_row, picked_col));
+            let ghost = BallStyle {
+                color: style.color.gamma_multiply(0.35),
+                ..style
+            };
+            ghost.paint(ui.painter(), slot_center(row, col), scale);
+        }
+
+        if let Some((dragged_row, dragged_col)) = dragged_slot {
+            if let Some(pos) = ui.input(|i| i.pointer.interact_pos()) {
+                let slot = self.slot(dragged_row, dragged_col);
+                self.ball_style(ui.ctx(), slot)
+                    .paint(ui.painter(), pos, scale);
+                self.paint_number(ui.painter(), slot, pos, scale);
+            }
+        }
+
+        moved
+    }
+
+    /// Moves that can be played from the position under the rules of the game, leaving out the
+    /// extra column once closed. Everything asking whether a move can be played goes through
+    /// here: dropping a ball, hints, the stuck check and the moves counter.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let board = PackedBoard::from(self);
+        let extra_column_closed = self.extra_column == Some(0);
+        (0..self.column_count)
+            .flat_map(|from| (0..self.column_count).map(move |to| Move { from, to }))
+            .filter(|mv| board.can_move(mv.from, mv.to))
+            .filter(|mv| !(extra_column_closed && mv.to == self.column_count - 1))
+            .collect()
+    }
+
+    /// Legal moves leading to different positions, telling apart neither empty columns nor
+    /// columns holding the same balls.
+    pub fn distinct_moves(&self) -> usize {
+        let board = PackedBoard::from(self);
+        let positions: HashSet<_> = self
+            .legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let mut next = board.clone();
+                next.apply_move(mv.from, mv.to);
+                next.canonical()
+            })
+            .collect();
+        positions.len()
+    }
+
+    /// Whether the player seems stuck: hardly any move left, or going around in circles, the
+    /// last [`STUCK_WINDOW`] moves coming back to earlier positions [`STUCK_REPEATS`] times or
+    /// more.
+    pub fn is_stuck(&self) -> bool {
+        if self.is_winning().is_some() {
+            return false;
+        }
+        if self.legal_moves().len() < STUCK_MOVES {
+            return true;
+        }
+
+        // walk the moves back from the current position
+        let mut board = PackedBoard::from(self);
+        let mut seen = HashSet::from([board.canonical()]);
+        let mut repeats = 0;
+        for mv in self.moves.iter().rev().take(STUCK_WINDOW) {
+            board.apply_move(mv.to, mv.from);
+            if !seen.insert(board.canonical()) {
+                repeats += 1;
+            }
+        }
+        repeats >= STUCK_REPEATS
+    }
+
+    /// Whether to warn that the player seems stuck, unless dismissed lately.
+    pub fn shows_stuck_warning(&self) -> bool {
+        self.mode != Mode::Kid
+            && self
+                .stuck_dismissed
+                .is_none_or(|moves| self.play_count >= moves + STUCK_WINDOW)
+            && self.is_stuck()
+    }
+
+    /// Copy of the game brought back (or forward) to `position` moves, through undo and redo.
+    pub fn at_position(&self, position: usize) -> State {
+        let mut state = self.clone();
+        while state.moves.len() > position && state.can_undo() {
+            state.undo();
+        }
+        while state.moves.len() < position && state.can_redo() {
+            state.redo();
+        }
+        state
+    }
+
+    /// Board as text, see the [`Display`](std::fmt::Display) implementation.
+    pub fn to_ascii(&self) -> String {
+        self.to_string()
+    }
+
+    /// Stand-in for the board while the game is paused, so the position can't be studied.
+    ///
+    /// Returns whether the game was resumed.
+    pub fn paused_ui(&mut self, ui: &mut egui::Ui, view: BoardView) -> bool {
+        let size = self.board_size(view).at_least(vec2(120.0, 60.0));
+        let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+        ui.painter()
+            .rect_filled(rect, 6.0, ui.visuals().code_bg_color);
+        ui.allocate_ui_at_rect(rect, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space((rect.height() / 2.0 - 24.0).at_least(0.0));
+                ui.strong(Msg::Paused);
+                if ui.button(Msg::Resume).clicked() {
+                    self.paused = false;
+                }
+            });
+        });
+
+        !self.paused
+    }
+}
+
+/// Pairs of balls swapped to remix a board: enough to play differently, few enough to feel
+/// familiar.
+const REMIX_SWAPS: usize = 4;
+
+/// Fisher-Yates shuffle. Unlike `SliceRandom::shuffle`, whose algorithm may change between `rand`
+/// versions, it gives the same order for a given seed for good.
+fn shuffle<T>(items: &mut [T], rng: &mut impl rand::RngCore) {
+    for i in (1..items.len()).rev() {
+        // the modulo bias is negligible with 64 bits
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// Longest gap between frames counted as play time, in seconds. The clock repaints every second
+/// while the game is on screen, so longer gaps mean the app was in the background or the computer
+/// asleep.
+const MAX_TICK: f32 = 2.0;
+
+/// Format a duration as `mm:ss`, or `h:mm:ss` past one hour.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Seconds since the Unix epoch.
+fn unix_time() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// How a board is drawn, which leaves the game the same.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BoardView {
+    /// Columns laid out from right to left.
+    pub mirrored: bool,
+
+    /// Columns around a circle, where the board allows it, see [`State::is_circular`].
+    pub circular: bool,
+}
+
+/// Variations on the game, each with its own history.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+enum Mode {
+    #[default]
+    Normal,
+
+    /// Few colors and big balls, moved by tapping too, without counters.
+    Kid,
+
+    /// Columns in hexagonal cells, a ball only moving to the cells next to its own.
+    Hex,
+
+    /// Numbered balls, to put in order in every column.
+    Numbers,
+}
+
+impl Mode {
+    const ALL: &'static [Mode] = &[Mode::Normal, Mode::Kid, Mode::Hex, Mode::Numbers];
+
+    /// Modes to pick from on the setup screen, kid mode having a button of its own.
+    const VARIANTS: &'static [Mode] = &[Mode::Normal, Mode::Hex, Mode::Numbers];
+
+    fn label(self) -> Msg {
+        match self {
+            Mode::Normal => Msg::NormalMode,
+            Mode::Kid => Msg::KidMode,
+            Mode::Hex => Msg::HexMode,
+            Mode::Numbers => Msg::NumbersMode,
+        }
+    }
+
+    fn note(self) -> Option<Msg> {
+        match self {
+            Mode::Normal | Mode::Kid => None,
+            Mode::Hex => Some(Msg::HexModeNote),
+            Mode::Numbers => Some(Msg::NumbersModeNote),
+        }
+    }
+
+    fn ball_scale(self) -> f32 {
+        match self {
+            Mode::Normal | Mode::Hex | Mode::Numbers => 1.0,
+            Mode::Kid => 2.0,
+        }
+    }
+
+    fn topology(self) -> Topology {
+        match self {
+            Mode::Normal | Mode::Kid | Mode::Numbers => Topology::Grid,
+            Mode::Hex => Topology::Hex,
+        }
+    }
+
+    fn goal(self) -> Goal {
+        match self {
+            Mode::Numbers => Goal::Numbers,
+            Mode::Normal | Mode::Kid | Mode::Hex => Goal::Colors,
+        }
+    }
+}
+
+/// Setups chosen in one click on the setup screen, from easiest to hardest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Preset {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Preset {
+    const ALL: &'static [Preset] = &[Preset::Easy, Preset::Medium, Preset::Hard, Preset::Expert];
+
+    fn label(self) -> Msg {
+        match self {
+            Preset::Easy => Msg::Easy,
+            Preset::Medium => Msg::Medium,
+            Preset::Hard => Msg::Hard,
+            Preset::Expert => Msg::Expert,
+        }
+    }
+
+    fn board(self) -> (usize, usize) {
+        let (colors, height, ..) = self.settings();
+        (colors, height)
+    }
+
+    /// Configuration of the games started with the preset.
+    fn config(self) -> GameConfig {
+        let (colors, height, handicap, rules) = self.settings();
+        let mut config = GameConfig::new(colors + 1, height);
+        config.rules = rules;
+        if handicap {
+            config = config.with_extra_empty_column();
+        }
+        config
+    }
+
+    fn easier(self) -> Option<Preset> {
+        let index = Preset::ALL.iter().position(|&preset| preset == self)?;
+        Preset::ALL.get(index.checked_sub(1)?).copied()
+    }
+
+    fn harder(self) -> Option<Preset> {
+        let index = Preset::ALL.iter().position(|&preset| preset == self)?;
+        Preset::ALL.get(index + 1).copied()
+    }
+
+    /// Colors, height, whether there's an extra empty column, and rules. The extra column of the
+    /// hard preset makes room on a board that would otherwise be mostly luck.
+    fn settings(self) -> (usize, usize, bool, Rules) {
+        match self {
+            Preset::Easy => (4, 4, false, Rules::Classic),
+            Preset::Medium => (6, 5, false, Rules::Classic),
+            Preset::Hard => (10, 6, true, Rules::Classic),
+            Preset::Expert => (12, 7, false, Rules::CountedUndo),
+        }
+    }
+}
+
+/// Scores a board size needs on the leaderboard for its difficulty to be rated by them.
+const CROWD_RATING_SCORES: usize = 10;
+
+/// Recent games on a preset's board needed to recommend another preset.
+const RECOMMENDATION_GAMES: usize = 3;
+
+/// Moves added to the count for reshuffling the remaining balls.
+const RESHUFFLE_PENALTY: usize = 5;
+
+/// Stars for a win in `moves`, against the `par` fewest moves solving the board: all of them for
+/// doing as well, two for up to half as many moves again, and one otherwise.
+fn stars(moves: usize, par: usize) -> usize {
+    if moves <= par {
+        MAX_STARS
+    } else if 2 * moves <= 3 * par {
+        2
+    } else {
+        1
+    }
+}
+
+const MAX_STARS: usize = 3;
+
+/// Seconds from the first ball dropping into place as a game starts to the last one.
+const INTRO_DURATION: f32 = 0.8;
+
+/// Seconds each ball takes to drop into place.
+const INTRO_FALL: f32 = 0.15;
+
+/// Mouse wheel scrolling, in points, stepping to the next column (one notch of a usual wheel).
+const WHEEL_STEP: f32 = 50.0;
+
+/// Fewer legal moves than this and the player is likely stuck.
+const STUCK_MOVES: usize = 2;
+
+/// Last moves looked at for going around in circles.
+const STUCK_WINDOW: usize = 16;
+
+/// Returns to earlier positions among the last moves that hint at going around in circles.
+const STUCK_REPEATS: usize = 4;
+
+/// Hints available per game.
+const HINT_BUDGET: usize = 3;
+
+/// Moves during which balls may be dropped onto the temporary extra column.
+const EXTRA_COLUMN_MOVES: usize = 10;
+
+/// Moves added to the count for the temporary extra column.
+const EXTRA_COLUMN_PENALTY: usize = 3;
+
+/// Boards with at least this many balls may be played with an extra empty column.
+const HANDICAP_MIN_BALLS: usize = 36;
+
+/// Height of the boards of kid mode, which have three or four colors.
+const KID_COLUMN_CAPACITY: usize = 4;
+
+/// Everything that sets games apart in the history, so that wins only compare to games played
+/// the same way.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+struct GameConfig {
+    column_count: usize,
+    column_capacity: usize,
+
+    #[serde(default = "default_empty_columns")]
+    empty_columns: usize,
+
+    #[serde(default)]
+    rules: Rules,
+
+    #[serde(default)]
+    mode: Mode,
+}
+
+fn default_empty_columns() -> usize {
+    1
+}
+
+impl GameConfig {
+    /// Board of the given size, played the usual way.
+    fn new(column_count: usize, column_capacity: usize) -> Self {
+        Self {
+            column_count,
+            column_capacity,
+            empty_columns: default_empty_columns(),
+            rules: Rules::default(),
+            mode: Mode::default(),
+        }
+    }
+
+    fn colors(&self) -> usize {
+        self.column_count.saturating_sub(self.empty_columns)
+    }
+
+    /// The same board, with one more empty column.
+    fn with_extra_empty_column(self) -> Self {
+        Self {
+            column_count: self.column_count + 1,
+            empty_columns: self.empty_columns + 1,
+            ..self
+        }
+    }
+
+    /// Whether the board has more room than usual.
+    fn is_handicapped(&self) -> bool {
+        self.empty_columns > default_empty_columns()
+    }
+
+    /// Whether `other` is played on the same board, whatever its rules and mode.
+    fn is_same_board(&self, other: &Self) -> bool {
+        (self.column_count, self.column_capacity, self.empty_columns)
+            == (
+                other.column_count,
+                other.column_capacity,
+                other.empty_columns,
+            )
+    }
+
+    /// Rules and mode, unless they're the usual ones.
+    fn variations(&self) -> Vec<String> {
+        [
+            (self.rules != Rules::default()).then(|| self.rules.label().to_string()),
+            (self.mode != Mode::default()).then(|| self.mode.label().to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Board size, such as `6x7`, asterisked when handicapped, followed by the rules and mode
+    /// unless they're the usual ones.
+    fn label(&self) -> String {
+        let handicap = if self.is_handicapped() { "*" } else { "" };
+        let size = format!("{}x{}{handicap}", self.colors(), self.column_capacity);
+        let variations = self.variations();
+        if variations.is_empty() {
+            size
+        } else {
+            format!("{size} ({})", variations.join(", "))
+        }
+    }
+
+    /// Rules and mode, such as `Hex`, or the usual mode's name.
+    fn variant_label(&self) -> String {
+        let variations = self.variations();
+        if variations.is_empty() {
+            Mode::default().label().to_string()
+        } else {
+            variations.join(", ")
+        }
+    }
+}
+
+/// How the games of a configuration went, for the statistics.
+#[derive(Clone, Copy, Default)]
+struct Outcomes {
+    won: usize,
+    abandoned: usize,
+
+    /// Still being played.
+    open: usize,
+}
+
+impl Outcomes {
+    fn started(&self) -> usize {
+        self.won + self.abandoned + self.open
+    }
+
+    /// Share of `count` among the games that are over, in percent, if any is.
+    fn percent_of_over(&self, count: usize) -> Option<usize> {
+        let over = self.won + self.abandoned;
+        (over > 0).then(|| count * 100 / over)
+    }
+}
+
+/// Move counts of every won game, fewest first, for each game configuration.
+type History = HashMap<GameConfig, Vec<usize>>;
+
+/// Add a win in `play_count` moves to `history`, after the wins in as many moves.
+fn insert_play_count(history: &mut History, config: GameConfig, play_count: usize) {
+    let play_counts = history.entry(config).or_default();
+    let index = play_counts.partition_point(|&moves| moves <= play_count);
+    play_counts.insert(index, play_count);
+}
+
+/// Rank from 0 of a win in `play_count` moves among `play_counts`, fewest first: the wins in as
+/// many moves share it.
+fn rank(play_counts: &[usize], play_count: usize) -> usize {
+    play_counts.partition_point(|&moves| moves < play_count)
+}
+
+/// History key, also read from the `(column_count, column_capacity)` pairs of older versions,
+/// which only told board sizes apart.
+#[derive(PartialEq, Eq, Hash)]
+struct HistoryKey(GameConfig);
+
+impl<'de> serde::Deserialize<'de> for HistoryKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = HistoryKey;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a game configuration or a board size")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                let column_count = seq
+                    .next_element()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+                let column_capacity = seq
+                    .next_element()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
+                Ok(HistoryKey(GameConfig::new(column_count, column_capacity)))
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> Result<Self::Value, A::Error> {
+                serde::Deserialize::deserialize(serde::de::value::MapAccessDeserializer::new(map))
+                    .map(HistoryKey)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+fn deserialize_history<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<History, D::Error> {
+    // sets in older versions, which kept each move count once
+    let keyed: HashMap<HistoryKey, Vec<usize>> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(keyed
+        .into_iter()
+        .map(|(HistoryKey(config), mut moves)| {
+            moves.sort_unstable();
+            (config, moves)
+        })
+        .collect())
+}
+
+/// A won game, as kept in the history.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+struct GameRecord {
+    column_count: usize,
+    column_capacity: usize,
+
+    #[serde(default = "default_empty_columns")]
+    empty_columns: usize,
+
+    #[serde(default)]
+    rules: Rules,
+
+    #[serde(default)]
+    mode: Mode,
+
+    /// Including `penalty`.
+    moves: usize,
+
+    /// Moves added for using powerups.
+    #[serde(default)]
+    penalty: usize,
+
+    /// Hints asked for during the game.
+    #[serde(default)]
+    hints: usize,
+
+    /// Moves taken back during the game.
+    #[serde(default)]
+    undos: usize,
+
+    /// Fewest moves solving the board, unknown until they're found, if they are.
+    #[serde(default)]
+    par: Option<usize>,
+
+    /// Unknown for games won before records were kept, as is `finished`.
+    duration: Option<Duration>,
+
+    /// Unix timestamp of the win.
+    finished: Option<u64>,
+
+    /// Seed of the board, if it was shuffled from one.
+    #[serde(default)]
+    seed: Option<u64>,
+
+    /// Written by the player, e.g. on how the board went.
+    #[serde(default)]
+    note: String,
+
+    /// Moves played, to replay the win from `seed`. Empty for wins that predate it.
+    #[serde(default)]
+    move_list: Vec<Move>,
+
+    /// Outcome of [`GameRecord::verify`], once asked for.
+    #[serde(skip)]
+    verification: Option<Verification>,
+}
+
+impl GameRecord {
+    /// Whether the game was won without hints, undos or powerups.
+    fn is_clean(&self) -> bool {
+        self.hints == 0 && self.undos == 0 && self.penalty == 0
+    }
+
+    fn stars(&self) -> Option<usize> {
+        self.par.map(|par| stars(self.moves, par))
+    }
+
+    /// Replay `move_list` on the board shuffled from `seed`, to check that it wins in `moves`.
+    /// Wins without a seed or moves to go by can't be checked, nor can those that took powerups
+    /// or counted undos, whose moves don't add up to the play count.
+    fn verify(&self) -> Verification {
+        let Some(seed) = self.seed else {
+            return Verification::Unverifiable;
+        };
+        if self.move_list.is_empty() || self.penalty > 0 || !self.rules.rule_set().is_ranked() {
+            return Verification::Unverifiable;
+        }
+
+        let mut state = State::from_config(self.config(), seed);
+        let replayed = self
+            .move_list
+            .iter()
+            .all(|mv| state.move_ball(mv.from, mv.to));
+        if replayed && state.is_winning() == Some(self.moves) {
+            Verification::Verified
+        } else {
+            Verification::Failed
+        }
+    }
+
+    fn config(&self) -> GameConfig {
+        GameConfig {
+            column_count: self.column_count,
+            column_capacity: self.column_capacity,
+            empty_columns: self.empty_columns,
+            rules: self.rules,
+            mode: self.mode,
+        }
+    }
+
+    /// Colors and height of the board.
+    fn board(&self) -> (usize, usize) {
+        (
+            self.column_count.saturating_sub(self.empty_columns),
+            self.column_capacity,
+        )
+    }
+}
+
+/// Whether a win holds up when replayed, see [`GameRecord::verify`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Verification {
+    Verified,
+    Failed,
+    Unverifiable,
+}
+
+impl Verification {
+    fn label(self) -> Msg {
+        match self {
+            Verification::Verified => Msg::VerifiedWin,
+            Verification::Failed => Msg::VerificationFailed,
+            Verification::Unverifiable => Msg::Unverifiable,
+        }
+    }
+}
+
+/// Key of the [`BulkData`] of profile `id` in IndexedDB, where older versions kept games and
+/// history together.
+#[cfg(target_arch = "wasm32")]
+fn bulk_data_key(id: u32) -> String {
+    format!("__app_bulk__{}", profiles::suffix(id))
+}
+
+/// Data that outgrows eframe's storage: kept in IndexedDB on the web, and in a save file with
+/// backups on desktop.
+#[derive(serde::Deserialize)]
+struct BulkData {
+    #[serde(default)]
+    games: Vec<State>,
+    #[serde(default, deserialize_with = "deserialize_history")]
+    history: History,
+    #[serde(default)]
+    records: Vec<GameRecord>,
+    #[serde(default)]
+    play_time: HashMap<GameConfig, Duration>,
+    #[serde(default)]
+    abandoned: HashMap<GameConfig, usize>,
+}
+
+/// Borrowed [`BulkData`], for saving.
+#[derive(serde::Serialize)]
+struct BulkDataRef<'a> {
+    games: &'a Vec<State>,
+    history: &'a History,
+    records: &'a Vec<GameRecord>,
+    play_time: &'a HashMap<GameConfig, Duration>,
+    abandoned: &'a HashMap<GameConfig, usize>,
+}
+
+/// Where [`CorruptData`] was read from.
+#[derive(Clone, Copy, Debug)]
+enum DataStore {
+    /// eframe's storage.
+    Storage,
+
+    #[cfg(target_arch = "wasm32")]
+    IndexedDb,
+
+    /// The save file, whose backups already keep the unreadable version.
+    #[cfg(not(target_arch = "wasm32"))]
+    SaveFile,
+}
+
+/// Stored data that couldn't be read, kept as is so that it can be exported and mended rather
+/// than lost once the defaults used instead are saved over it.
+struct CorruptData {
+    store: DataStore,
+
+    /// Key it was stored under, or name of the file.
+    key: String,
+
+    contents: String,
+    error: String,
+
+    /// Whether a copy was stored next to it, under [`CorruptData::stash_key`].
+    stashed: bool,
+}
+
+impl CorruptData {
+    fn new(store: DataStore, key: String, contents: String, error: impl ToString) -> Self {
+        let error = error.to_string();
+        log::warn!("failed to read {key}: {error}");
+        Self {
+            store,
+            key,
+            contents,
+            error,
+            stashed: false,
+        }
+    }
+
+    fn stash_key(&self) -> String {
+        format!("{}.corrupt", self.key)
+    }
+
+    /// Name of the file it's exported to.
+    fn file_name(&self) -> String {
+        format!("{}.corrupt.ron", self.key.trim_matches('_'))
+    }
+}
+
+/// History part of [`BulkData`], stored under a key of its own (see [`profiles::history_key`]).
+#[derive(serde::Deserialize)]
+struct HistoryData {
+    #[serde(default, deserialize_with = "deserialize_history")]
+    history: History,
+    #[serde(default)]
+    records: Vec<GameRecord>,
+    #[serde(default)]
+    play_time: HashMap<GameConfig, Duration>,
+    #[serde(default)]
+    abandoned: HashMap<GameConfig, usize>,
+}
+
+/// Borrowed [`HistoryData`], for saving.
+#[derive(serde::Serialize)]
+struct HistoryDataRef<'a> {
+    history: &'a History,
+    records: &'a Vec<GameRecord>,
+    play_time: &'a HashMap<GameConfig, Duration>,
+    abandoned: &'a HashMap<GameConfig, usize>,
+}
+
+/// The player, as shown on the leaderboard.
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct Profile {
+    nickname: String,
+
+    /// Index in [`BALL_COLORS`].
+    avatar_color: usize,
+
+    /// Board size selected at launch, rather than the last one played.
+    default_board: Option<(usize, usize)>,
+}
+
+impl Profile {
+    fn avatar_ui(&self, ui: &mut egui::Ui, diameter: f32) -> egui::Response {
+        avatar_ui(ui, &self.nickname, self.avatar_color, diameter)
+    }
+}
+
+/// Disc in the avatar color with the initial of the nickname.
+fn avatar_ui(
+    ui: &mut egui::Ui,
+    nickname: &str,
+    avatar_color: usize,
+    diameter: f32,
+) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(egui::Vec2::splat(diameter), Sense::click());
+    let color = BALL_COLORS[avatar_color % BALL_COLORS.len()];
+    ui.painter()
+        .circle_filled(rect.center(), diameter / 2.0, color);
+    if let Some(initial) = nickname.trim().chars().next() {
+        ui.painter().text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            initial.to_uppercase(),
+            egui::FontId::proportional(diameter * 0.6),
+            egui::Color32::BLACK,
+        );
+    }
+    response
+}
+
+/// Nickname of a profile, or a placeholder if it has none.
+fn profile_name(nickname: &str) -> egui::WidgetText {
+    match nickname.trim() {
+        "" => Msg::UnnamedProfile.into(),
+        nickname => nickname.into(),
+    }
+}
+
+/// Change to the local profiles, applied when storage is at hand.
+#[derive(Clone, Copy)]
+enum ProfileChange {
+    Switch(u32),
+    Remove(u32),
+}
+
+/// Settings shared between devices through sync. The UI scale stays per device.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+struct SyncedSettings {
+    column_count: usize,
+    column_capacity: usize,
+    language: Language,
+    layout_direction: LayoutDirection,
+    mirror_board: bool,
+    dark_mode: Option<bool>,
+    #[serde(default)]
+    profile: Profile,
+}
+
+/// Everything synced between devices, stamped with the time it last changed (see [`sync`]).
+#[derive(serde::Deserialize)]
+struct SyncDocument {
+    modified: u64,
+    data: BulkData,
+    settings: SyncedSettings,
+}
+
+/// Borrowed [`SyncDocument`], for pushing.
+#[derive(serde::Serialize)]
+struct SyncDocumentRef<'a> {
+    modified: u64,
+    data: BulkDataRef<'a>,
+    settings: SyncedSettings,
+}
+
+/// Step of a sync with the server.
+#[derive(Clone, Copy)]
+enum SyncStep {
+    Pull,
+
+    /// Pushing the local copy stamped `modified`.
+    Push {
+        modified: u64,
+    },
+}
+
+#[derive(Default)]
+enum SyncStatus {
+    #[default]
+    Idle,
+    Syncing,
+    UpToDate,
+    Failed(String),
+}
+
+/// Local changes are pushed at most this often, in seconds.
+const SYNC_INTERVAL: f64 = 30.0;
+
+/// Coach marks shown to new players, each until dismissed.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+enum Tip {
+    DragBall,
+    Undo,
+}
+
+impl Tip {
+    const ALL: &'static [Tip] = &[Tip::DragBall, Tip::Undo];
+
+    fn text(self) -> Msg {
+        match self {
+            Tip::DragBall => Msg::TipDragBall,
+            Tip::Undo => Msg::TipUndo,
+        }
+    }
+
+    fn applies_to(self, state: &State) -> bool {
+        match self {
+            Tip::DragBall => state.play_count == 0,
+            Tip::Undo => state.play_count > 0,
+        }
+    }
+}
+
+const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.5..=2.5;
+
+/// Commands shared by the in-game buttons, the menu bar, and the keyboard shortcuts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    NewGame,
+    Restart,
+    Abort,
+    SaveForLater,
+    Pause,
+    Undo,
+    Redo,
+    Hint,
+    Reshuffle,
+    ExtraColumn,
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+    HowToPlay,
+    Shortcuts,
+    About,
+    LogPanel,
+    MoveLog,
+    DebugOverlay,
+    ExportCsv,
+    #[cfg(feature = "puffin")]
+    Profiler,
+}
+
+/// Keyboard shortcuts, checked in order. Since modifiers match loosely (Ctrl+Shift+Z also
+/// matches Ctrl+Z), a more specific shortcut must come before a less specific one.
+const KEYBINDINGS: &[(Action, egui::KeyboardShortcut)] = &[
+    (
+        Action::Redo,
+        egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+            egui::Key::Z,
+        ),
+    ),
+    (
+        Action::Undo,
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z),
+    ),
+    (
+        Action::NewGame,
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::N),
+    ),
+    (
+        Action::Restart,
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::R),
+    ),
+    (
+        Action::Pause,
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::P),
+    ),
+    (
+        Action::NewGame,
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::N),
+    ),
+    (
+        Action::Restart,
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::R),
+    ),
+    (
+        Action::Hint,
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::H),
+    ),
+    (
+        Action::Undo,
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::U),
+    ),
+    (
+        Action::Pause,
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::P),
+    ),
+    (
+        Action::ZoomIn,
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Plus),
+    ),
+    (
+        Action::ZoomIn,
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Equals),
+    ),
+    (
+        Action::ZoomOut,
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Minus),
+    ),
+    (
+        Action::ResetZoom,
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Num0),
+    ),
+    (
+        Action::Shortcuts,
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Questionmark),
+    ),
+    (
+        Action::LogPanel,
+        egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+            egui::Key::L,
+        ),
+    ),
+    (
+        Action::MoveLog,
+        egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+            egui::Key::M,
+        ),
+    ),
+    (
+        Action::DebugOverlay,
+        egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+            egui::Key::D,
+        ),
+    ),
+    #[cfg(feature = "puffin")]
+    (
+        Action::Profiler,
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F12),
+    ),
+];
+
+/// Pointer interactions, listed alongside the keyboard shortcuts.
+const GESTURES: &[(Msg, Msg)] = &[
+    (Msg::DragGesture, Msg::DragGestureAction),
+    (Msg::WheelGesture, Msg::WheelGestureAction),
+    (Msg::ShiftWheelGesture, Msg::ShiftWheelGestureAction),
+];
+
+impl Action {
+    fn label(self) -> Msg {
+        match self {
+            Action::NewGame => Msg::NewGameItem,
+            Action::Restart => Msg::Restart,
+            Action::Abort => Msg::AbortItem,
+            Action::SaveForLater => Msg::SaveForLater,
+            Action::Pause => Msg::PauseItem,
+            Action::Undo => Msg::Undo,
+            Action::Redo => Msg::Redo,
+            Action::Hint => Msg::Hint,
+            Action::Reshuffle => Msg::Reshuffle,
+            Action::ExtraColumn => Msg::ExtraColumn,
+            Action::ZoomIn => Msg::ZoomIn,
+            Action::ZoomOut => Msg::ZoomOut,
+            Action::ResetZoom => Msg::ResetZoom,
+            Action::HowToPlay => Msg::HowToPlay,
+            Action::Shortcuts => Msg::KeyboardShortcuts,
+            Action::About => Msg::About,
+            Action::LogPanel => Msg::LogPanel,
+            Action::MoveLog => Msg::MoveLog,
+            Action::DebugOverlay => Msg::DebugOverlay,
+            Action::ExportCsv => Msg::ExportCsv,
+            #[cfg(feature = "puffin")]
+            Action::Profiler => Msg::Profiler,
+        }
+    }
+
+    /// First shortcut bound to this action, if any.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn shortcut(self) -> Option<&'static egui::KeyboardShortcut> {
+        KEYBINDINGS
+            .iter()
+            .find(|(action, _)| *action == self)
+            .map(|(_, shortcut)| shortcut)
+    }
+
+    /// Every key bound to this action, for a button's tooltip.
+    fn shortcut_hint(self, ctx: &egui::Context) -> String {
+        KEYBINDINGS
+            .iter()
+            .filter(|(action, _)| *action == self)
+            .map(|(_, shortcut)| ctx.format_shortcut(shortcut))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct BouleApp {
+    column_count: usize,
+    column_capacity: usize,
+
+    /// Play large boards with an extra empty column.
+    handicap: bool,
+
+    /// Mode of the games started from the setup screen, among [`Mode::VARIANTS`].
+    variant: Mode,
+
+    /// Offer powerups, which cost moves.
+    powerups: bool,
+
+    /// Rules of the games started from the setup screen.
+    rules: Rules,
+
+    /// Open games, each shown as a tab.
+    ///
+    /// Games and history are stored under keys of their own (see [`profiles::games_key`]), and in
+    /// the save file on desktop, so that one failing to load leaves the rest. They're only read
+    /// from here when upgrading from older versions.
+    #[serde(skip_serializing)]
+    games: Vec<State>,
+
+    /// Index in `games` of the game being played, if any.
+    current_game: Option<usize>,
+
+    /// Move counts of won games, for each game configuration.
+    #[serde(skip_serializing, deserialize_with = "deserialize_history")]
+    history: History,
+
+    /// Every won game, oldest first.
+    #[serde(skip_serializing)]
+    records: Vec<GameRecord>,
+
+    /// Time spent in games, won or not, for each game configuration.
+    #[serde(skip_serializing)]
+    play_time: HashMap<GameConfig, Duration>,
+
+    /// Games closed before being won, for each game configuration.
+    #[serde(skip)]
+    abandoned: HashMap<GameConfig, usize>,
+
+    /// Zoom factor applied on top of the native pixels-per-point.
+    ui_scale: f32,
+
+    language: Language,
+    layout_direction: LayoutDirection,
+
+    /// Also mirror the column order when the layout is right-to-left.
+    mirror_board: bool,
+
+    /// Show above the board how many balls of each color are left to sort.
+    show_color_summary: bool,
+
+    /// Mirror the layout for left-handed touch play. Kept per device, like the UI scale.
+    left_handed: bool,
+
+    /// Draw the board around a circle, see [`BoardView::circular`].
+    circular_board: bool,
+
+    /// eframe restores the native window geometry but not its maximized state.
+    window_maximized: bool,
+
+    /// Forced dark (`true`) or light (`false`) theme, or follow the system if `None`.
+    dark_mode: Option<bool>,
+
+    /// Coach marks already dismissed.
+    seen_tips: BTreeSet<Tip>,
+
+    /// Weekly challenge results, past weeks included.
+    weekly: BTreeMap<weekly::Week, weekly::WeekResults>,
+
+    /// Boards starred to be played again, oldest first.
+    favorites: Vec<favorites::Favorite>,
+
+    /// Games closed lately, most recent first.
+    recent: VecDeque<recent::RecentGame>,
+
+    /// Puzzle being made in the editor.
+    #[serde(skip)]
+    editor: Option<editor::Editor>,
+
+    /// Week shown in the weekly challenge section, the current one if `None`.
+    #[serde(skip)]
+    weekly_shown: Option<weekly::Week>,
+
+    /// Fewest moves each daily puzzle was solved in.
+    daily: BTreeMap<daily::Day, usize>,
+
+    /// Month shown in the daily puzzle calendar, the current one if `None`.
+    #[serde(skip)]
+    daily_shown: Option<daily::Month>,
+
+    sync: sync::SyncSettings,
+
+    /// When games, history or synced settings last changed, in seconds since the Unix epoch.
+    modified: u64,
+
+    /// Changes to the synced data since `modified` was stamped, moves included but not the
+    /// ticking of the game clocks.
+    #[serde(skip)]
+    changes: usize,
+
+    /// `modified` of the copy last pulled from or pushed to the sync server.
+    synced: u64,
+
+    profile: Profile,
+
+    telemetry: telemetry::Telemetry,
+
+    leaderboard: leaderboard::LeaderboardSettings,
+
+    /// Single game slot of older versions, moved to `games` when loading.
+    #[serde(skip_serializing)]
+    state: Option<State>,
+
+    /// Unsaved changes worth persisting right away.
+    #[serde(skip)]
+    dirty: bool,
+
+    #[serde(skip)]
+    confirm_abort: bool,
+
+    /// Show the setup screen even though a game is in progress.
+    #[serde(skip)]
+    show_setup: bool,
+
+    #[serde(skip)]
+    confirm_new_game: bool,
+
+    /// Offer to resume the game found in storage at launch.
+    #[serde(skip)]
+    resume_prompt: bool,
+
+    #[serde(skip)]
+    show_statistics: bool,
+
+    /// Won games listed with the statistics.
+    #[serde(skip)]
+    history_filter: history::HistoryFilter,
+
+    #[serde(skip)]
+    show_how_to_play: bool,
+
+    #[serde(skip)]
+    show_about: bool,
+
+    #[serde(skip)]
+    show_shortcuts: bool,
+
+    #[serde(skip)]
+    show_log_panel: bool,
+
+    /// List the moves of the game being played alongside the board.
+    show_move_log: bool,
+
+    #[serde(skip)]
+    show_debug_overlay: bool,
+
+    /// Size of the app state in storage as of the last save.
+    #[serde(skip)]
+    storage_size: Option<usize>,
+
+    #[cfg(feature = "puffin")]
+    #[serde(skip)]
+    show_profiler: bool,
+
+    /// Save file, if not the default one.
+    #[cfg(not(target_arch = "wasm32"))]
+    save_path: Option<std::path::PathBuf>,
+
+    /// Save file path being edited.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    save_path_edit: Option<String>,
+
+    /// Script of the scripted rules, if any.
+    #[cfg(feature = "scripting")]
+    rules_script: String,
+
+    /// Whether the script of the scripted rules loaded, or what's wrong with it.
+    #[cfg(feature = "scripting")]
+    #[serde(skip)]
+    rules_script_status: Option<Result<(), String>>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    broadcast: broadcast::BroadcastSettings,
+
+    /// Spectator broadcast as set in `broadcast`, or the port it couldn't listen on and why.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    broadcaster: Option<Result<broadcast::Broadcaster, (u16, String)>>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    remote: remote::RemoteSettings,
+
+    /// Remote control as set in `remote`, or the port it couldn't listen on and why.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    remote_control: Option<Result<remote::RemoteControl, (u16, String)>>,
+
+    /// Stored data that couldn't be read this session, shown in a banner until exported or
+    /// dismissed.
+    #[serde(skip)]
+    corrupt_data: Vec<CorruptData>,
+
+    /// Where the unreadable data was exported, or why it failed, until dismissed.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    exported_corrupt_data: Option<Result<std::path::PathBuf, String>>,
+
+    /// Where the history was exported as CSV, or why it failed, until dismissed.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    exported_csv: Option<Result<std::path::PathBuf, String>>,
+
+    /// Where the puzzle made in the editor was saved, or why it failed.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    exported_puzzle: Option<Result<std::path::PathBuf, String>>,
+
+    /// Whether the save file was already backed up this session.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    backups_rotated: bool,
+
+    /// Panic report of the previous run, shown until dismissed.
+    #[serde(skip)]
+    crash_report: Option<String>,
+
+    /// Game being played when the previous run crashed, and its tab index, until restored.
+    #[serde(skip)]
+    recovered_game: Option<(usize, State)>,
+
+    /// Whether the game being played when the previous run crashed was restored.
+    #[serde(skip)]
+    game_recovered: bool,
+
+    #[cfg(feature = "rerun")]
+    #[serde(skip)]
+    recording: Option<recording::Recording>,
+
+    /// Games and history being read from IndexedDB, see [`BouleApp::bulk_keys`].
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    bulk_load: Option<bulk_storage::PendingLoad>,
+
+    /// Keys of IndexedDB that couldn't be read, and aren't written this session so that what
+    /// they hold isn't lost.
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    unreadable_keys: Vec<String>,
+
+    /// Whether IndexedDB still holds games and history under the key of older versions, to be
+    /// cleared once they are stored under their own keys.
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    legacy_bulk_data: bool,
+
+    /// Solver looking for a hint for the game at the given index.
+    #[serde(skip)]
+    hint_search: Option<(usize, Search)>,
+
+    /// Moves of the current game previewed while dragging the scrubber.
+    #[serde(skip)]
+    scrub: Option<usize>,
+
+    /// Solvers rating the wins of the games at the given indices, if still open, recorded at the
+    /// given times, in the order they were won.
+    #[serde(skip)]
+    par_searches: VecDeque<(Option<usize>, u64, Search)>,
+
+    /// Shared or edited puzzle about to be played, with the solver checking that it can be
+    /// solved, or why it found no solution.
+    #[serde(skip)]
+    puzzle_check: Option<(Box<State>, Result<Search, Unsolved>)>,
+
+    #[serde(skip)]
+    sync_request: Option<(SyncStep, sync::PendingRequest)>,
+
+    /// Pull from the sync server as soon as possible.
+    #[serde(skip)]
+    sync_requested: bool,
+
+    /// Time of the last pull, as of `egui::InputState::time`.
+    #[serde(skip)]
+    last_sync: Option<f64>,
+
+    /// Server copy changed along with the local one, until the player picks one.
+    #[serde(skip)]
+    sync_conflict: Option<SyncDocument>,
+
+    #[serde(skip)]
+    sync_status: SyncStatus,
+
+    #[serde(skip)]
+    show_leaderboard: bool,
+
+    #[serde(skip)]
+    show_profile: bool,
+
+    /// Local profiles, stored apart from any of them.
+    #[serde(skip)]
+    profiles: profiles::Profiles,
+
+    /// Ask who's playing at launch.
+    #[serde(skip)]
+    choose_profile: bool,
+
+    #[serde(skip)]
+    profile_change: Option<ProfileChange>,
+
+    #[serde(skip)]
+    confirm_remove_profile: Option<u32>,
+
+    /// Challenge string being pasted, and whether it was found invalid.
+    #[serde(skip)]
+    challenge_edit: (String, bool),
+
+    /// Whether the file dropped on the window was not a puzzle.
+    #[serde(skip)]
+    invalid_drop: bool,
+
+    /// Game index whose challenge string was copied.
+    #[serde(skip)]
+    challenge_copied: Option<usize>,
+
+    /// Best scores for a board size, being fetched or fetched.
+    #[serde(skip)]
+    leaderboard_top: Option<((usize, usize), leaderboard::TopRequest)>,
+
+    /// How the game at the given index, won in the given moves, compares to everyone's scores,
+    /// being fetched or fetched.
+    #[serde(skip)]
+    global_stats: Option<(
+        (usize, usize),
+        leaderboard::Request<leaderboard::GlobalStats>,
+    )>,
+
+    /// Score of the game at the given index being submitted, or why it failed.
+    #[serde(skip)]
+    score_submission: Option<(usize, leaderboard::Request<()>)>,
+
+    /// How players fare on the games of each configuration, being fetched or fetched.
+    #[serde(skip)]
+    solve_stats: HashMap<GameConfig, leaderboard::Request<leaderboard::SolveStats>>,
+
+    /// Last title sent to the native window.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    window_title: String,
+}
+
+impl Default for BouleApp {
+    fn default() -> Self {
+        Self {
+            column_count: 7,
+            column_capacity: 7,
+            handicap: false,
+            variant: Mode::Normal,
+            powerups: false,
+            rules: Rules::default(),
+            games: Vec::new(),
+            current_game: None,
+            history: HashMap::new(),
+            play_time: HashMap::new(),
+            abandoned: HashMap::new(),
+            records: Vec::new(),
+            ui_scale: 1.0,
+            language: Language::default(),
+            layout_direction: LayoutDirection::default(),
+            mirror_board: true,
+            show_color_summary: false,
+            left_handed: false,
+            circular_board: false,
+            window_maximized: false,
+            dark_mode: None,
+            seen_tips: BTreeSet::new(),
+            weekly: BTreeMap::new(),
+            weekly_shown: None,
+            favorites: Vec::new(),
+            recent: VecDeque::new(),
+            editor: None,
+            daily: BTreeMap::new(),
+            daily_shown: None,
+            sync: sync::SyncSettings::default(),
+            modified: 0,
+            changes: 0,
+            synced: 0,
+            profile: Profile::default(),
+            telemetry: telemetry::Telemetry::default(),
+            leaderboard: leaderboard::LeaderboardSettings::default(),
+            state: None,
+            dirty: false,
+            confirm_abort: false,
+            show_setup: false,
+            confirm_new_game: false,
+            resume_prompt: false,
+            show_statistics: false,
+            history_filter: Default::default(),
+            show_how_to_play: false,
+            show_about: false,
+            show_shortcuts: false,
+            show_log_panel: false,
+            show_move_log: false,
+            show_debug_overlay: false,
+            storage_size: None,
+            #[cfg(feature = "puffin")]
+            show_profiler: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            save_path: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            save_path_edit: None,
+            #[cfg(feature = "scripting")]
+            rules_script: String::new(),
+            #[cfg(feature = "scripting")]
+            rules_script_status: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            broadcast: broadcast::BroadcastSettings::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            broadcaster: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            remote: remote::RemoteSettings::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            remote_control: None,
+            corrupt_data: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            exported_corrupt_data: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            exported_csv: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            exported_puzzle: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            backups_rotated: false,
+            crash_report: None,
+            recovered_game: None,
+            game_recovered: false,
+            #[cfg(feature = "rerun")]
+            recording: None,
+            #[cfg(target_arch = "wasm32")]
+            bulk_load: None,
+            #[cfg(target_arch = "wasm32")]
+            unreadable_keys: Vec::new(),
+            #[cfg(target_arch = "wasm32")]
+            legacy_bulk_data: false,
+            hint_search: None,
+            scrub: None,
+            par_searches: VecDeque::new(),
+            puzzle_check: None,
+            sync_request: None,
+            sync_requested: false,
+            last_sync: None,
+            sync_conflict: None,
+            sync_status: SyncStatus::Idle,
+            show_leaderboard: false,
+            show_profile: false,
+            profiles: profiles::Profiles::default(),
+            choose_profile: false,
+            profile_change: None,
+            confirm_remove_profile: None,
+            challenge_edit: (String::new(), false),
+            invalid_drop: false,
+            challenge_copied: None,
+            leaderboard_top: None,
+            score_submission: None,
+            solve_stats: HashMap::new(),
+            global_stats: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            window_title: "boule".to_owned(),
+        }
+    }
+}
+
+impl eframe::App for BouleApp {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if let Some(change) = self.profile_change.take() {
+            if let Some(storage) = frame.storage_mut() {
+                self.change_profiles(storage, ctx, change);
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if !self.poll_bulk_load() {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.centered_and_justified(|ui| ui.spinner());
+            });
+            return;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            for (config, seed) in embed::take_new_games() {
+                self.start_game(config, seed);
+            }
+            embed::set_current(self.current());
+        }
+
+        // eframe only marks frames for the profiler on native
+        #[cfg(all(feature = "puffin", target_arch = "wasm32"))]
+        puffin::GlobalProfiler::lock().new_frame();
+
+        i18n::set_language(self.language);
+        // left-handed play flips whatever the reading direction is
+        i18n::set_right_to_left(
+            self.layout_direction.is_right_to_left(self.language) != self.left_handed,
+        );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(maximized) = ctx.input(|i| i.viewport().maximized) {
+            if maximized != self.window_maximized {
+                self.window_maximized = maximized;
+                self.dirty = true;
+            }
+        }
+
+        let dark_mode = self.dark_mode.or_else(|| {
+            frame
+                .info()
+                .system_theme
+                .map(|theme| theme == eframe::Theme::Dark)
+        });
+        if let Some(dark_mode) = dark_mode {
+            if ctx.style().visuals.dark_mode != dark_mode {
+                ctx.set_visuals(if dark_mode {
+                    egui::Visuals::dark()
+                } else {
+                    egui::Visuals::light()
+                });
+            }
+        }
+
+        self.handle_shortcuts(ctx);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.menu_bar_ui(ctx);
+
+        self.corrupt_data_banner(ctx);
+
+        if !self.resume_prompt && !self.choose_profile {
+            self.tabs_ui(ctx);
+        }
+
+        if self.show_log_panel {
+            Self::log_panel(ctx);
+        }
+        let playing = self.current_game.is_some()
+            && !self.show_setup
+            && !self.resume_prompt
+            && !self.choose_profile;
+        if self.show_move_log && playing {
+            self.move_log_panel(ctx);
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .auto_shrink(false)
+                .show(ui, |ui| {
+                    if self.choose_profile {
+                        self.choose_profile_ui(ui);
+                    } else if self.resume_prompt {
+                        self.resume_prompt_ui(ui);
+                    } else if self.current_game.is_some() && !self.show_setup {
+                        self.game_ui(ui);
+                    } else {
+                        self.setup_ui(ui);
+                    }
+                });
+        });
+
+        if self.show_statistics {
+            self.statistics_viewport(ctx);
+        }
+
+        self.help_windows(ctx);
+        self.editor_window(ctx);
+        self.dropped_files(ctx);
+        self.invalid_drop_dialog(ctx);
+        self.puzzle_check_window(ctx);
+        self.crash_dialog(ctx);
+        self.sync_conflict_dialog(ctx);
+        if self.show_leaderboard {
+            self.leaderboard_window(ctx);
+        }
+        self.profile_window(ctx);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.exported_csv_dialog(ctx);
+        #[cfg(not(target_arch = "wasm32"))]
+        exported_file_dialog(
+            ctx,
+            &mut self.exported_corrupt_data,
+            Msg::ExportCorruptData,
+            Msg::CorruptDataExported,
+        );
+        #[cfg(not(target_arch = "wasm32"))]
+        exported_file_dialog(
+            ctx,
+            &mut self.exported_puzzle,
+            Msg::ExportPuzzle,
+            Msg::PuzzleExported,
+        );
+        #[cfg(feature = "puffin")]
+        if self.show_profiler && !puffin_egui::profiler_window(ctx) {
+            self.apply(ctx, Action::Profiler);
+        }
+        self.poll_hint_search();
+        self.poll_par_search();
+        self.record_win(ctx);
+        self.update_sync(ctx);
+        self.dirty |= self.telemetry.report_if_due(&self.records, unix_time());
+
+        #[cfg(feature = "rerun")]
+        if let (Some(recording), Some(index)) = (&mut self.recording, self.current_game) {
+            if let Some(state) = self.games.get(index) {
+                recording.log_game(index, state);
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_remote_control(ctx);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_broadcast();
+
+        if self.show_debug_overlay {
+            self.debug_overlay(ctx, frame);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_window_title(ctx);
+    }
+
+    fn save(&mut self, storage: &mut dyn Storage) {
+        profile_scope!("save");
+        // changes aren't saved, but the time they were made at is
+        self.track_changes();
+        let id = self.profiles.active;
+        let keys = [
+            profiles::app_key(id),
+            profiles::games_key(id),
+            profiles::history_key(id),
+        ];
+        self.stash_corrupt_data(storage);
+        eframe::set_value(storage, &keys[0], self);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(value) = self.games_data() {
+                storage.set_string(&keys[1], value);
+            }
+            if let Some(value) = self.history_data() {
+                storage.set_string(&keys[2], value);
+            }
+        }
+        if let Some(entry) = self.profiles.entry_mut(self.profiles.active) {
+            entry.nickname.clone_from(&self.profile.nickname);
+            entry.avatar_color = self.profile.avatar_color;
+        }
+        self.profiles.save(storage);
+        // don't overwrite the stored games before they are loaded
+        #[cfg(target_arch = "wasm32")]
+        if self.bulk_load.is_none() {
+            let writable = |key: &String| !self.unreadable_keys.contains(key);
+            let games = self.games_data().filter(|_| writable(&keys[1]));
+            let history = self.history_data().filter(|_| writable(&keys[2]));
+            if games.is_some() {
+                journal::Journal::new(id).clear();
+            }
+            // the key of older versions goes once both are stored under their own
+            let replaced = (self.legacy_bulk_data && games.is_some() && history.is_some())
+                .then(|| bulk_data_key(id));
+            if replaced.is_some() {
+                self.legacy_bulk_data = false;
+            }
+            let values = [(&keys[1], games), (&keys[2], history)]
+                .into_iter()
+                .filter_map(|(key, value)| Some((key.clone(), value?)))
+                .collect();
+            bulk_storage::save_all(values, replaced);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let (Some(path), Some(contents)) = (self.save_file_path(), self.bulk_data()) {
+            // back up the previous save once per session
+            match save_file::write(&path, &contents, !self.backups_rotated) {
+                Ok(()) => {
+                    self.backups_rotated = true;
+                    journal::Journal::new(&path).clear();
+                }
+                Err(err) => log::warn!("failed to write {}: {err}", path.display()),
+            }
+        }
+        crash::set_snapshot(self.current_game.zip(self.current().cloned()));
+        self.storage_size = Some(
+            keys.iter()
+                .filter_map(|key| storage.get_string(key))
+                .map(|value| value.len())
+                .sum(),
+        );
+        self.dirty = false;
+    }
+
+    /// Changes are saved right away, while moves are journaled and the game clock only rides
+    /// along with the periodic saves.
+    fn auto_save_interval(&self) -> Duration {
+        if self.dirty {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs(30)
+        }
+    }
+}
+
+impl BouleApp {
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let profiles = profiles::Profiles::load(cc.storage);
+        let choose_profile = profiles.entries.len() > 1;
+        let mut app = Self::load(cc.storage, profiles, &cc.egui_ctx, crash::take_report());
+        app.choose_profile = choose_profile;
+        #[cfg(feature = "rerun")]
+        {
+            app.recording = recording::Recording::spawn();
+        }
+        app
+    }
+
+    /// Load the active profile from `storage`, bringing back the game of `crash` if any.
+    fn load(
+        storage: Option<&dyn Storage>,
+        profiles: profiles::Profiles,
+        ctx: &egui::Context,
+        crash: Option<crash::CrashReport>,
+    ) -> Self {
+        let key = profiles::app_key(profiles.active);
+        let mut corrupt_data = None;
+        let mut app: Self = storage
+            .and_then(|storage| storage.get_string(&key))
+            .filter(|value| !value.is_empty())
+            .and_then(|value| match ron::from_str(&value) {
+                Ok(app) => Some(app),
+                Err(err) => {
+                    corrupt_data = Some(CorruptData::new(DataStore::Storage, key, value, err));
+                    None
+                }
+            })
+            .unwrap_or_default();
+        app.corrupt_data.extend(corrupt_data);
+        app.profiles = profiles;
+        if let Some(game) = app.state.take() {
+            app.games.push(game);
+            app.current_game = Some(app.games.len() - 1);
+        }
+        if app.profile.nickname.is_empty() {
+            app.profile.nickname = std::mem::take(&mut app.leaderboard.nickname);
+        }
+        if let Some((column_count, column_capacity)) = app.profile.default_board {
+            app.column_count = column_count;
+            app.column_capacity = column_capacity;
+        }
+        if let Some(crash) = crash {
+            log::warn!("recovering from a crash: {}", crash.report);
+            app.crash_report = Some(crash.report);
+            app.recovered_game = crash.game;
+        }
+        // scripted games are only played right once their rules are back
+        #[cfg(feature = "scripting")]
+        app.load_rules_script();
+        // on the web, games are only known once loaded from IndexedDB
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(storage) = storage {
+                let id = app.profiles.active;
+                app.set_stored_parts(
+                    DataStore::Storage,
+                    storage.get_string(&profiles::games_key(id)),
+                    storage.get_string(&profiles::history_key(id)),
+                );
+            }
+            app.load_save_file();
+            app.restore_recovered_game();
+            app.replay_journal();
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let keys = Self::bulk_keys(app.profiles.active).to_vec();
+            app.bulk_load = Some(bulk_storage::load(keys, ctx.clone()));
+        }
+        app.backfill_records();
+        app.rebuild_history();
+        app.games.iter_mut().for_each(State::refresh_cache);
+        app.resume_prompt = app.has_unfinished_game();
+        app.sync_requested = app.sync.is_active();
+
+        // the persisted UI scale is the only source of truth for the zoom factor
+        ctx.options_mut(|o| o.zoom_with_keyboard = false);
+        ctx.set_zoom_factor(app.ui_scale);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if app.window_maximized {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Maximized(true));
+        }
+
+        app
+    }
+
+    /// Switch to another profile, saving the current one first, or remove one.
+    fn change_profiles(
+        &mut self,
+        storage: &mut dyn Storage,
+        ctx: &egui::Context,
+        change: ProfileChange,
+    ) {
+        match change {
+            ProfileChange::Switch(id) => {
+                eframe::App::save(self, storage);
+                let mut profiles = std::mem::take(&mut self.profiles);
+                profiles.active = id;
+                profiles.save(storage);
+
+                let mut app = Self::load(Some(&*storage), profiles, ctx, None);
+                // a new profile is named first
+                app.show_profile = app.profile.nickname.is_empty();
+                #[cfg(feature = "rerun")]
+                {
+                    app.recording = self.recording.take();
+                }
+                // still listening on the ports, to be stopped next frame if the profile doesn't
+                // broadcast or take remote control
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    app.broadcaster = self.broadcaster.take();
+                    app.remote_control = self.remote_control.take();
+                }
+                *self = app;
+            }
+            ProfileChange::Remove(id) if id != self.profiles.active => {
+                self.profiles.entries.retain(|entry| entry.id != id);
+                self.profiles.save(storage);
+                // the save file, if any, is left alone
+                storage.set_string(&profiles::app_key(id), String::new());
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    storage.set_string(&profiles::games_key(id), String::new());
+                    storage.set_string(&profiles::history_key(id), String::new());
+                }
+                #[cfg(target_arch = "wasm32")]
+                for key in Self::bulk_keys(id) {
+                    bulk_storage::save(key, String::new());
+                }
+            }
+            ProfileChange::Remove(_) => {}
+        }
+    }
+
+    /// Keys of the games and history of profile `id` in IndexedDB, followed by the key where
+    /// older versions kept both.
+    #[cfg(target_arch = "wasm32")]
+    fn bulk_keys(id: u32) -> [String; 3] {
+        [
+            profiles::games_key(id),
+            profiles::history_key(id),
+            bulk_data_key(id),
+        ]
+    }
+
+    /// Take in the games and history read from IndexedDB, returning whether they are loaded.
+    #[cfg(target_arch = "wasm32")]
+    fn poll_bulk_load(&mut self) -> bool {
+        let Some(pending) = &self.bulk_load else {
+            return true;
+        };
+        let Some(values) = pending.poll() else {
+            return false;
+        };
+        self.bulk_load = None;
+
+        // keys that failed to read are taken as missing, but left alone when saving
+        let mut readable = Vec::new();
+        for (value, key) in values
+            .into_iter()
+            .zip(Self::bulk_keys(self.profiles.active))
+        {
+            readable.push(value.unwrap_or_else(|_| {
+                self.unreadable_keys.push(key);
+                None
+            }));
+        }
+        let mut values = readable.into_iter();
+
+        // whatever isn't stored yet is kept from the key of older versions, or else from
+        // localStorage, to be moved on next save
+        let (games, history) = (values.next().flatten(), values.next().flatten());
+        if let Some(value) = values.next().flatten().filter(|value| !value.is_empty()) {
+            self.legacy_bulk_data = true;
+            let key = bulk_data_key(self.profiles.active);
+            self.set_bulk_data(DataStore::IndexedDb, key, value);
+        }
+        self.set_stored_parts(DataStore::IndexedDb, games, history);
+        self.restore_recovered_game();
+        self.replay_journal();
+        self.games.iter_mut().for_each(State::refresh_cache);
+        self.resume_prompt = self.has_unfinished_game();
+        self.dirty = true;
+        true
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn bulk_data(&self) -> Option<String> {
+        let bulk_data = BulkDataRef {
+            games: &self.games,
+            history: &self.history,
+            records: &self.records,
+            play_time: &self.play_time,
+            abandoned: &self.abandoned,
+        };
+        ron::to_string(&bulk_data)
+            .map_err(|err| log::warn!("failed to serialize games: {err}"))
+            .ok()
+    }
+
+    /// Open games, serialized to be stored under their own key.
+    fn games_data(&self) -> Option<String> {
+        ron::to_string(&self.games)
+            .map_err(|err| log::warn!("failed to serialize games: {err}"))
+            .ok()
+    }
+
+    /// History, serialized to be stored under its own key.
+    fn history_data(&self) -> Option<String> {
+        let history_data = HistoryDataRef {
+            history: &self.history,
+            records: &self.records,
+            play_time: &self.play_time,
+            abandoned: &self.abandoned,
+        };
+        ron::to_string(&history_data)
+            .map_err(|err| log::warn!("failed to serialize the history: {err}"))
+            .ok()
+    }
+
+    /// Take in the games and history stored under their own keys, each on its own so that one
+    /// failing to load leaves the other. Missing ones leave what is already there.
+    fn set_stored_parts(
+        &mut self,
+        store: DataStore,
+        games: Option<String>,
+        history: Option<String>,
+    ) {
+        let id = self.profiles.active;
+        if let Some(value) = games.filter(|value| !value.is_empty()) {
+            match ron::from_str(&value) {
+                Ok(games) => self.take_games(games),
+                Err(err) => {
+                    let key = profiles::games_key(id);
+                    self.corrupt_data
+                        .push(CorruptData::new(store, key, value, err));
+                }
+            }
+        }
+        if let Some(value) = history.filter(|value| !value.is_empty()) {
+            match ron::from_str(&value) {
+                Ok(history_data) => self.take_history(history_data),
+                Err(err) => {
+                    let key = profiles::history_key(id);
+                    self.corrupt_data
+                        .push(CorruptData::new(store, key, value, err));
+                }
+            }
+        }
+    }
+
+    fn set_bulk_data(&mut self, store: DataStore, key: String, value: String) {
+        match ron::from_str::<BulkData>(&value) {
+            Ok(bulk_data) => self.take_bulk_data(bulk_data),
+            Err(err) => self
+                .corrupt_data
+                .push(CorruptData::new(store, key, value, err)),
+        }
+    }
+
+    fn take_bulk_data(&mut self, bulk_data: BulkData) {
+        self.take_games(bulk_data.games);
+        self.take_history(HistoryData {
+            history: bulk_data.history,
+            records: bulk_data.records,
+            play_time: bulk_data.play_time,
+            abandoned: bulk_data.abandoned,
+        });
+    }
+
+    fn take_games(&mut self, games: Vec<State>) {
+        self.games = games;
+        self.current_game = self.current_game.filter(|&index| index < self.games.len());
+    }
+
+    fn take_history(&mut self, history_data: HistoryData) {
+        self.history = history_data.history;
+        self.records = history_data.records;
+        self.play_time = history_data.play_time;
+        self.abandoned = history_data.abandoned;
+        self.backfill_records();
+        self.backfill_play_time();
+        self.rebuild_history();
+    }
+
+    /// Count again the wins from their records, which older versions kept even when the history
+    /// only kept each move count once.
+    fn rebuild_history(&mut self) {
+        let mut history = History::new();
+        for record in &self.records {
+            insert_play_count(&mut history, record.config(), record.moves);
+        }
+        self.history = history;
+    }
+
+    /// Add records for the wins of older versions, which only kept move counts.
+    fn backfill_records(&mut self) {
+        if !self.records.is_empty() {
+            return;
+        }
+        for (config, moves) in &self.history {
+            self.records.extend(moves.iter().map(|&moves| GameRecord {
+                column_count: config.column_count,
+                column_capacity: config.column_capacity,
+                empty_columns: config.empty_columns,
+                rules: config.rules,
+                mode: config.mode,
+                moves,
+                penalty: 0,
+                hints: 0,
+                undos: 0,
+                par: None,
+                duration: None,
+                finished: None,
+                seed: None,
+                note: String::new(),
+                move_list: Vec::new(),
+                verification: None,
+            }));
+        }
+    }
+
+    /// Count the time of won and open games for older versions, which didn't track play time.
+    fn backfill_play_time(&mut self) {
+        if !self.play_time.is_empty() {
+            return;
+        }
+        for record in &self.records {
+            *self.play_time.entry(record.config()).or_default() +=
+                record.duration.unwrap_or_default();
+        }
+        // won games are already counted through their record
+        for state in self.games.iter().filter(|state| !state.win_recorded) {
+            *self.play_time.entry(state.config()).or_default() += state.elapsed;
+        }
+        self.play_time.retain(|_, time| !time.is_zero());
+    }
+
+    fn synced_settings(&self) -> SyncedSettings {
+        SyncedSettings {
+            column_count: self.column_count,
+            column_capacity: self.column_capacity,
+            language: self.language,
+            layout_direction: self.layout_direction,
+            mirror_board: self.mirror_board,
+            dark_mode: self.dark_mode,
+            profile: self.profile.clone(),
+        }
+    }
+
+    fn sync_document_ref(&self) -> SyncDocumentRef<'_> {
+        SyncDocumentRef {
+            modified: self.modified,
+            data: BulkDataRef {
+                games: &self.games,
+                history: &self.history,
+                records: &self.records,
+                play_time: &self.play_time,
+                abandoned: &self.abandoned,
+            },
+            settings: self.synced_settings(),
+        }
+    }
+
+    fn sync_document(&self) -> Option<String> {
+        ron::to_string(&self.sync_document_ref())
+            .map_err(|err| log::warn!("failed to serialize the synced data: {err}"))
+            .ok()
+    }
+
+    /// Stamp the synced data with the current time if it changed since last time, as counted in
+    /// `changes` wherever it's changed.
+    fn track_changes(&mut self) {
+        if self.changes > 0 {
+            self.changes = 0;
+            self.modified = unix_time();
+        }
+    }
+
+    /// Replace games, history and synced settings with the server copy.
+    fn apply_sync_document(&mut self, document: SyncDocument) {
+        self.take_bulk_data(document.data);
+        self.games.iter_mut().for_each(State::refresh_cache);
+        let settings = document.settings;
+        self.column_count = settings.column_count;
+        self.column_capacity = settings.column_capacity;
+        self.language = settings.language;
+        self.layout_direction = settings.layout_direction;
+        self.mirror_board = settings.mirror_board;
+        self.dark_mode = settings.dark_mode;
+        self.profile = settings.profile;
+
+        self.modified = document.modified;
+        self.synced = document.modified;
+        self.changes = 0;
+        self.sync_status = SyncStatus::UpToDate;
+        self.dirty = true;
+        log::info!("pulled the synced data");
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_file_path(&self) -> Option<std::path::PathBuf> {
+        self.save_path
+            .clone()
+            .or_else(|| save_file::default_path(self.profiles.active))
+    }
+
+    /// Take in the games and history from the save file, if there is one. Otherwise, the ones
+    /// from eframe's storage are kept, and written to the save file on next save.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_save_file(&mut self) {
+        let Some(path) = self.save_file_path() else {
+            return;
+        };
+        match save_file::read(&path) {
+            Ok(Some(contents)) => {
+                let name = path.file_name().unwrap_or(path.as_os_str());
+                let name = name.to_string_lossy().into_owned();
+                self.set_bulk_data(DataStore::SaveFile, name, contents);
+            }
+            Ok(None) => {}
+            Err(err) => log::warn!("failed to read {}: {err}", path.display()),
+        }
+        self.games.iter_mut().for_each(State::refresh_cache);
+    }
+
+    /// Put back the game saved when the previous run crashed, over its stored version.
+    fn restore_recovered_game(&mut self) {
+        let Some((index, game)) = self.recovered_game.take() else {
+            return;
+        };
+        match self.games.get_mut(index) {
+            Some(stored) => *stored = game,
+            None => self.games.push(game),
+        }
+        self.current_game = Some(index.min(self.games.len() - 1));
+        self.game_recovered = true;
+    }
+
+    /// Journal of the moves played since the last save.
+    fn journal(&self) -> Option<journal::Journal> {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self
+            .save_file_path()
+            .map(|path| journal::Journal::new(&path));
+        #[cfg(target_arch = "wasm32")]
+        return Some(journal::Journal::new(self.profiles.active));
+    }
+
+    /// Play again the moves journaled since the last save, which a crash kept from being saved.
+    fn replay_journal(&mut self) {
+        let Some(journal) = self.journal() else {
+            return;
+        };
+        for entry in journal.read() {
+            let (game, moves) = entry.position();
+            let Some(state) = self
+                .games
+                .get_mut(game)
+                .filter(|state| state.moves.len() == moves)
+            else {
+                continue;
+            };
+            match entry {
+                journal::Entry::Moved { from, to, .. } => {
+                    state.move_ball(from, to);
+                }
+                journal::Entry::Undone { .. } => state.undo(),
+                journal::Entry::Redone { .. } => state.redo(),
+            }
+        }
+    }
+
+    fn current(&self) -> Option<&State> {
+        self.current_game.and_then(|index| self.games.get(index))
+    }
+
+    /// Reflect the game being played in the window title, so progress shows in the taskbar.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_window_title(&mut self, ctx: &egui::Context) {
+        let title = match self.current() {
+            Some(state) if !self.show_setup && !self.resume_prompt => format!(
+                "boule — {}, {}",
+                state.summary(),
+                format_duration(state.elapsed)
+            ),
+            _ => "boule".to_owned(),
+        };
+
+        if title != self.window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = title;
+        }
+    }
+
+    fn tabs_ui(&mut self, ctx: &egui::Context) {
+        if self.games.is_empty() {
+            return;
+        }
+
+        egui::TopBottomPanel::top("tabs").show(ctx, |ui| {
+            ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+                let mut selected = None;
+                for (index, game) in self.games.iter().enumerate() {
+                    let is_current = self.current_game == Some(index) && !self.show_setup;
+                    if ui.selectable_label(is_current, game.summary()).clicked() {
+                        selected = Some(index);
+                    }
+                }
+                if let Some(index) = selected {
+                    self.select_game(index);
+                }
+
+                if ui
+                    .selectable_label(self.current_game.is_none(), "+")
+                    .on_hover_text(Msg::NewGame)
+                    .clicked()
+                {
+                    self.current_game = None;
+                    self.show_setup = false;
+                    self.dirty = true;
+                }
+            });
+        });
+    }
+
+    fn setup_ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            ui.style_mut().wrap = Some(true);
+
+            ui.with_layout(i18n::horizontal_layout(), |ui| {
+                let avatar = self.profile.avatar_ui(ui, 24.0);
+                let nickname = self.profile.nickname.trim();
+                let button = if nickname.is_empty() {
+                    ui.button(Msg::Profile)
+                } else {
+                    ui.button(nickname)
+                };
+                if avatar.clicked() || button.clicked() {
+                    self.show_profile = !self.show_profile;
+                }
+            });
+            ui.add_space(12.0);
+
+            ui.strong(Msg::Difficulty);
+            let recommendation = self.recommended_preset();
+            let presets = self.calibrated_presets(ui.ctx());
+            ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+                for &(preset, labeled) in &presets {
+                    let mut label = egui::RichText::from(labeled.label());
+                    if recommendation.is_some_and(|(recommended, _)| recommended == preset) {
+                        label = label.strong().color(ui.visuals().warn_fg_color);
+                    }
+                    if ui
+                        .selectable_label(self.preset() == Some(preset), label)
+                        .clicked()
+                    {
+                        let (colors, height, handicap, rules) = preset.settings();
+                        self.column_count = colors + 1;
+                        self.column_capacity = height;
+                        self.handicap = handicap;
+                        self.rules = rules;
+                        self.changes += 1;
+                        self.dirty = true;
+                    }
+                }
+            });
+            if let Some((recommended, message)) = recommendation {
+                if self.preset() != Some(recommended) {
+                    ui.label(message);
+                }
+            }
+
+            ui.add_space(12.0);
+
+            ui.strong(Msg::Colors);
+            let mut color_count = self.column_count.saturating_sub(1);
+            let mut resized =
+                selectable_label_range(ui, 3..=BallStyle::MAX_STYLES, &mut color_count);
+            self.column_count = color_count + 1;
+
+            ui.add_space(12.0);
+
+            ui.strong(Msg::Height);
+            resized |=
+                selectable_label_range(ui, 2..=MAX_COLUMN_CAPACITY, &mut self.column_capacity);
+            if resized {
+                self.changes += 1;
+                self.dirty = true;
+            }
+
+            let config = self.setup_config();
+            let (rated, scores) = self.rated_difficulty(config, ui.ctx());
+            let board = (config.colors(), config.column_capacity);
+            let preset_of_board = Preset::ALL.iter().copied().find(|p| p.board() == board);
+            if preset_of_board != Some(rated) {
+                let note = match scores {
+                    Some(scores) => Msg::CrowdRated(scores),
+                    None => Msg::SizeRated,
+                };
+                let label = presets
+                    .iter()
+                    .find(|&&(preset, _)| preset == rated)
+                    .map_or(rated, |&(_, labeled)| labeled);
+                ui.label(format!("{} {}", Msg::PlaysLike, label.label()))
+                    .on_hover_text(note);
+            }
+
+            if self.handicap_allowed() {
+                ui.add_space(6.0);
+                self.dirty |= ui
+                    .checkbox(&mut self.handicap, Msg::Handicap)
+                    .on_hover_text(Msg::HandicapNote)
+                    .changed();
+            }
+            self.dirty |= ui
+                .checkbox(&mut self.powerups, Msg::Powerups)
+                .on_hover_text(Msg::PowerupsNote {
+                    reshuffle_penalty: RESHUFFLE_PENALTY,
+                    extra_column_penalty: EXTRA_COLUMN_PENALTY,
+                })
+                .changed();
+            let mut counted_undo = self.rules == Rules::CountedUndo;
+            if ui
+                .checkbox(&mut counted_undo, Msg::CountUndos)
+                .on_hover_text(Msg::CountUndosNote)
+                .changed()
+            {
+                self.rules = if counted_undo {
+                    Rules::CountedUndo
+                } else {
+                    Rules::Classic
+                };
+                self.dirty = true;
+            }
+            #[cfg(feature = "scripting")]
+            {
+                let mut scripted = self.rules == Rules::Scripted;
+                if ui
+                    .add_enabled(
+                        scripting::is_loaded(),
+                        egui::Checkbox::new(&mut scripted, Msg::ScriptedRules),
+                    )
+                    .on_hover_text(Msg::ScriptedRulesNote)
+                    .on_disabled_hover_text(Msg::RulesScriptNeeded)
+                    .changed()
+                {
+                    self.rules = if scripted {
+                        Rules::Scripted
+                    } else {
+                        Rules::Classic
+                    };
+                    self.dirty = true;
+                }
+            }
+
+            ui.add_space(12.0);
+
+            ui.strong(Msg::Variant);
+            ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+                for &variant in Mode::VARIANTS {
+                    let response = ui.selectable_value(&mut self.variant, variant, variant.label());
+                    let response = match variant.note() {
+                        Some(note) => response.on_hover_text(note),
+                        None => response,
+                    };
+                    self.dirty |= response.changed();
+                }
+            });
+
+            let config = self.setup_config();
+            let stars: usize = self
+                .records
+                .iter()
+                .filter(|record| record.config() == config)
+                .filter_map(GameRecord::stars)
+                .sum();
+            if stars > 0 {
+                ui.add_space(6.0);
+                ui.label(Msg::StarsEarned(stars));
+            }
+
+            ui.add_space(12.0);
+
+            if ui
+                .button(egui::RichText::from(Msg::Play).strong())
+                .clicked()
+            {
+                if self.has_unfinished_game() {
+                    self.confirm_new_game = true;
+                } else {
+                    self.start_new_game();
+                }
+            }
+
+            ui.add_space(6.0);
+            if ui
+                .add(
+                    egui::Button::new(egui::RichText::from(Msg::KidModeButton).size(28.0))
+                        .min_size(vec2(200.0, 60.0)),
+                )
+                .clicked()
+            {
+                // an unfinished game stays open in its tab
+                self.start_kid_game();
+            }
+
+            if self.has_unfinished_game() && ui.button(Msg::BackToGame).clicked() {
+                if let Some(index) = self.current_game {
+                    self.select_game(index);
+                }
+            }
+
+            if self.confirm_new_game {
+                self.new_game_dialog(ui.ctx());
+            }
+
+            self.accept_challenge_ui(ui);
+            self.favorites_ui(ui);
+            self.recent_ui(ui);
+            self.daily_ui(ui);
+            self.weekly_ui(ui);
+            self.open_games_ui(ui);
+
+            self.history_ui(ui, self.setup_config(), None);
+
+            ui.add_space(12.0);
+            ui.with_layout(i18n::horizontal_layout(), |ui| {
+                ui.toggle_value(&mut self.show_statistics, Msg::Statistics);
+                if self.leaderboard.is_active() {
+                    ui.toggle_value(&mut self.show_leaderboard, Msg::Leaderboard);
+                }
+                if ui
+                    .add_enabled(
+                        self.is_enabled(Action::ExportCsv),
+                        egui::Button::new(Msg::ExportCsv),
+                    )
+                    .clicked()
+                {
+                    self.apply(ui.ctx(), Action::ExportCsv);
+                }
+            });
+
+            ui.add_space(12.0);
+
+            ui.strong(Msg::UiScale);
+            let response = ui.add(
+                egui::Slider::new(&mut self.ui_scale, UI_SCALE_RANGE)
+                    .step_by(0.05)
+                    .custom_formatter(|scale, _| format!("{:.0}%", scale * 100.0))
+                    .custom_parser(|text| {
+                        text.trim()
+                            .trim_end_matches('%')
+                            .parse::<f64>()
+                            .ok()
+                            .map(|v| v / 100.0)
+                    }),
+            );
+            self.dirty |= response.changed();
+            // applying the zoom while dragging would move the slider under the pointer
+            if !response.dragged() {
+                ui.ctx().set_zoom_factor(self.ui_scale);
+            }
+            self.dirty |= ui
+                .checkbox(&mut self.show_color_summary, Msg::ColorSummary)
+                .on_hover_text(Msg::ColorSummaryNote)
+                .changed();
+            self.dirty |= ui
+                .checkbox(&mut self.circular_board, Msg::CircularBoard)
+                .on_hover_text(Msg::CircularBoardNote)
+                .changed();
+
+            ui.add_space(12.0);
+
+            ui.strong(Msg::Language);
+            let mut changed = false;
+            ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+                for &language in Language::ALL {
+                    changed |= ui
+                        .selectable_value(&mut self.language, language, language.name())
+                        .changed();
+                }
+            });
+
+            ui.add_space(12.0);
+
+            ui.strong(Msg::LayoutDirection);
+            ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+                for &direction in LayoutDirection::ALL {
+                    changed |= ui
+                        .selectable_value(&mut self.layout_direction, direction, direction.label())
+                        .changed();
+                }
+            });
+            changed |= ui
+                .checkbox(&mut self.mirror_board, Msg::MirrorBoard)
+                .changed();
+            changed |= ui
+                .checkbox(&mut self.left_handed, Msg::LeftHanded)
+                .on_hover_text(Msg::LeftHandedNote)
+                .changed();
+
+            if changed {
+                self.changes += 1;
+                self.dirty = true;
+                // strings and layouts above were built with the previous settings
+                ui.ctx().request_repaint();
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            self.save_file_ui(ui);
+            #[cfg(feature = "scripting")]
+            self.rules_script_ui(ui);
+            self.leaderboard_ui(ui);
+            self.sync_ui(ui);
+            #[cfg(not(target_arch = "wasm32"))]
+            self.broadcast_ui(ui);
+            #[cfg(not(target_arch = "wasm32"))]
+            self.remote_control_ui(ui);
+            self.telemetry_ui(ui);
+
+            ui.add_space(12.0);
+            ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+                for action in [
+                    Action::HowToPlay,
+                    Action::Shortcuts,
+                    Action::About,
+                    Action::LogPanel,
+                ] {
+                    if ui.small_button(action.label()).clicked() {
+                        self.apply(ui.ctx(), action);
+                    }
+                }
+            });
+
+            footer_ui(ui);
+        });
+    }
+
+    #[cfg(feature = "scripting")]
+    fn rules_script_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        ui.strong(Msg::RulesScript);
+        ui.label(Msg::RulesScriptNote);
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut self.rules_script)
+                .hint_text("rules.rhai")
+                .desired_width(f32::INFINITY),
+        );
+        if response.lost_focus() {
+            self.dirty = true;
+            self.load_rules_script();
+        }
+        match &self.rules_script_status {
+            None => {}
+            Some(Ok(())) => {
+                ui.label(Msg::RulesScriptLoaded);
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, Msg::RulesScriptFailed)
+                    .on_hover_text(err);
+            }
+        }
+    }
+
+    /// Play scripted rules by the script at `rules_script`, or the classic rules if there's none.
+    #[cfg(feature = "scripting")]
+    fn load_rules_script(&mut self) {
+        let path = self.rules_script.trim();
+        if path.is_empty() {
+            scripting::unload();
+            self.rules_script_status = None;
+        } else {
+            let status = std::fs::read_to_string(path)
+                .map_err(|err| err.to_string())
+                .and_then(|source| scripting::load(&source));
+            if let Err(err) = &status {
+                log::warn!("failed to load rules script {path}: {err}");
+            }
+            self.rules_script_status = Some(status);
+        }
+        // which columns are finished may have changed
+        self.games.iter_mut().for_each(State::refresh_cache);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_file_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        ui.strong(Msg::SaveFile);
+
+        let current = self.save_file_path();
+        let edit = self.save_path_edit.get_or_insert_with(|| {
+            current
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default()
+        });
+        let response = ui.add(egui::TextEdit::singleline(edit).desired_width(f32::INFINITY));
+        let mut new_path = None;
+        if response.lost_focus() {
+            let path = std::path::PathBuf::from(edit.trim());
+            if !edit.trim().is_empty() && Some(&path) != current.as_ref() {
+                new_path = Some(Some(path));
+            }
+            self.save_path_edit = None;
+        }
+        if self.save_path.is_some() && ui.small_button(Msg::DefaultSaveFile).clicked() {
+            new_path = Some(None);
+        }
+
+        if let Some(path) = new_path {
+            // an existing save file is switched to, otherwise the current data is written there
+            self.save_path = path;
+            self.save_path_edit = None;
+            self.backups_rotated = false;
+            self.load_save_file();
+            self.replay_journal();
+            self.dirty = true;
+        }
+    }
+
+    fn profile_window(&mut self, ctx: &egui::Context) {
+        let board = (self.column_count, self.column_capacity);
+        let mut changed = false;
+        egui::Window::new(Msg::Profile)
+            .open(&mut self.show_profile)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let profile = &mut self.profile;
+                ui.label(Msg::Nickname);
+                changed |= ui
+                    .add(egui::TextEdit::singleline(&mut profile.nickname).char_limit(32))
+                    .changed();
+
+                ui.add_space(6.0);
+                ui.label(Msg::AvatarColor);
+                ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+                    for color in 0..BALL_COLORS.len() {
+                        let avatar = Profile {
+                            avatar_color: color,
+                            ..profile.clone()
+                        };
+                        let response = avatar.avatar_ui(ui, 24.0);
+                        if color == profile.avatar_color {
+                            ui.painter().circle_stroke(
+                                response.rect.center(),
+                                14.0,
+                                ui.visuals().selection.stroke,
+                            );
+                        }
+                        if response.clicked() {
+                            profile.avatar_color = color;
+                            changed = true;
+                        }
+                    }
+                });
+
+                ui.add_space(6.0);
+                let mut use_default = profile.default_board.is_some();
+                let (column_count, column_capacity) = profile.default_board.unwrap_or(board);
+                let label = Msg::StartWithBoard {
+                    colors: column_count.saturating_sub(1),
+                    height: column_capacity,
+                };
+                if ui.checkbox(&mut use_default, label).changed() {
+                    profile.default_board = use_default.then_some(board);
+                    changed = true;
+                }
+
+                ui.add_space(12.0);
+                ui.strong(Msg::OtherProfiles);
+                for entry in &self.profiles.entries {
+                    if entry.id == self.profiles.active {
+                        continue;
+                    }
+                    ui.with_layout(i18n::horizontal_layout(), |ui| {
+                        avatar_ui(ui, &entry.nickname, entry.avatar_color, 18.0);
+                        if ui.button(profile_name(&entry.nickname)).clicked() {
+                            self.profile_change = Some(ProfileChange::Switch(entry.id));
+                        }
+                        if ui.small_button(Msg::RemoveProfile).clicked() {
+                            self.confirm_remove_profile = Some(entry.id);
+                        }
+                    });
+                }
+                if ui.button(Msg::NewProfile).clicked() {
+                    self.profile_change = Some(ProfileChange::Switch(self.profiles.add()));
+                }
+            });
+        if changed {
+            self.changes += 1;
+            self.dirty = true;
+        }
+
+        self.remove_profile_dialog(ctx);
+    }
+
+    fn remove_profile_dialog(&mut self, ctx: &egui::Context) {
+        let Some(entry) = self
+            .confirm_remove_profile
+            .and_then(|id| self.profiles.entries.iter().find(|entry| entry.id == id))
+        else {
+            return;
+        };
+
+        let (mut remove, mut cancel) = (false, false);
+        egui::Window::new(Msg::RemoveProfileTitle)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(profile_name(&entry.nickname));
+                ui.label(Msg::RemoveProfileDetails);
+                ui.add_space(6.0);
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    remove = ui.button(Msg::RemoveProfile).clicked();
+                    cancel = ui.button(Msg::Cancel).clicked();
+                });
+            });
+
+        if remove {
+            self.profile_change = Some(ProfileChange::Remove(entry.id));
+        }
+        if remove || cancel {
+            self.confirm_remove_profile = None;
+        }
+    }
+
+    /// Ask who's playing, at launch when there are several profiles.
+    fn choose_profile_ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            ui.strong(Msg::WhoIsPlaying);
+            ui.add_space(12.0);
+            for entry in &self.profiles.entries {
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    let avatar = avatar_ui(ui, &entry.nickname, entry.avatar_color, 24.0);
+                    let button = ui.button(profile_name(&entry.nickname));
+                    if avatar.clicked() || button.clicked() {
+                        if entry.id != self.profiles.active {
+                            self.profile_change = Some(ProfileChange::Switch(entry.id));
+                        }
+                        self.choose_profile = false;
+                    }
+                });
+            }
+            ui.add_space(12.0);
+            if ui.button(Msg::NewProfile).clicked() {
+                self.profile_change = Some(ProfileChange::Switch(self.profiles.add()));
+                self.choose_profile = false;
+            }
+        });
+    }
+
+    fn leaderboard_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        egui::CollapsingHeader::new(Msg::Leaderboard)
+            .id_source("leaderboard")
+            .show(ui, |ui| {
+                let mut changed = ui
+                    .checkbox(&mut self.leaderboard.enabled, Msg::LeaderboardEnabled)
+                    .changed();
+                ui.label(Msg::LeaderboardUrl);
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.leaderboard.url)
+                        .desired_width(f32::INFINITY),
+                );
+                if response.changed() {
+                    self.leaderboard_top = None;
+                    changed = true;
+                }
+                self.dirty |= changed;
+            });
+    }
+
+    /// Summary of the game just won, with what to do next: share it as a challenge, replay or
+    /// remix its board, or play again. Returns whether the favorite star was clicked.
+    fn win_summary_ui(&mut self, ui: &mut egui::Ui, starred: bool) -> bool {
+        let Some(index) = self.current_game else {
+            return false;
+        };
+        let Some(state) = self.games.get(index) else {
+            return false;
+        };
+        let Some(play_count) = state.is_winning() else {
+            return false;
+        };
+        let config = state.config();
+
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            egui::Grid::new("win_summary")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label(Msg::SummaryMoves);
+                    ui.strong(Msg::Moves(play_count));
+                    ui.end_row();
+                    if state.penalty > 0 {
+                        ui.label("");
+                        ui.small(Msg::PenaltyIncluded(state.penalty));
+                        ui.end_row();
+                    }
+
+                    ui.label(Msg::Par);
+                    match state.par {
+                        Some(par) => {
+                            ui.label(Msg::Moves(par));
+                        }
+                        None if self
+                            .par_searches
+                            .iter()
+                            .any(|(game, ..)| *game == Some(index)) =>
+                        {
+                            ui.spinner().on_hover_text(Msg::RatingWin);
+                        }
+                        None => {
+                            ui.label("—");
+                        }
+                    }
+                    ui.end_row();
+
+                    ui.label(Msg::Grade);
+                    match state.stars() {
+                        Some(stars) => stars_ui(ui, stars, 20.0),
+                        None => {
+                            ui.label("—");
+                        }
+                    }
+                    ui.end_row();
+
+                    ui.label(Msg::SummaryTime);
+                    ui.label(format_duration(state.elapsed));
+                    ui.end_row();
+
+                    ui.label(Msg::Undos);
+                    ui.label(state.undos.to_string());
+                    ui.end_row();
+
+                    ui.label(Msg::HintsUsed);
+                    ui.label(state.hints_used.to_string());
+                    ui.end_row();
+
+                    ui.label(Msg::TopTenRank);
+                    let rank = self
+                        .history
+                        .get(&config)
+                        .map(|history| rank(history, play_count))
+                        .filter(|&rank| rank < 10);
+                    match rank {
+                        Some(rank) => ui.strong(format!("#{}", rank + 1)),
+                        None => ui.label("—"),
+                    };
+                    ui.end_row();
+                });
+        });
+
+        ui.add_space(6.0);
+        let mut toggle_favorite = false;
+        let mut next = None;
+        let mut play_again = false;
+        ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+            toggle_favorite = favorite_button(ui, starred);
+            if self.challenge_copied == Some(index) {
+                ui.label(Msg::ChallengeCopied);
+            } else if ui.button(Msg::Challenge).clicked() {
+                if let Some(challenge) =
+                    challenge::Challenge::from_game(state, &self.profile.nickname)
+                {
+                    ui.ctx()
+                        .output_mut(|output| output.copied_text = challenge.encode());
+                    self.challenge_copied = Some(index);
+                }
+            }
+            if ui.button(Msg::Replay).clicked() {
+                next = state.replayed();
+            }
+            if let Some(seed) = state.seed {
+                if ui
+                    .button(Msg::Remix)
+                    .on_hover_text(Msg::RemixNote)
+                    .clicked()
+                {
+                    next = Some(State::remixed(config, seed, rand::random()));
+                }
+            }
+            play_again = ui.button(Msg::PlayAgain).clicked();
+        });
+
+        if play_again {
+            self.close_current_game();
+        } else if let Some(game) = next {
+            log::info!("playing the board just won again");
+            self.close_current_game();
+            self.games.push(game);
+            self.current_game = Some(self.games.len() - 1);
+            self.changes += 1;
+            self.dirty = true;
+        }
+        toggle_favorite
+    }
+
+    /// Compare the game just won to the challenge it was started from.
+    fn challenge_result_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(index) = self.current_game else {
+            return;
+        };
+        let Some(state) = self.games.get(index) else {
+            return;
+        };
+        let Some(moves) = state.is_winning() else {
+            return;
+        };
+
+        if let Some(challenge) = &state.challenge {
+            ui.add_space(12.0);
+            let seconds = state.elapsed.as_secs_f32();
+            // fewer moves wins, the clock breaks ties
+            let verdict = match moves
+                .cmp(&challenge.moves)
+                .then(seconds.total_cmp(&challenge.seconds))
+            {
+                std::cmp::Ordering::Less => Msg::ChallengeWon,
+                std::cmp::Ordering::Equal => Msg::ChallengeTied,
+                std::cmp::Ordering::Greater => Msg::ChallengeLost,
+            };
+            ui.strong(verdict);
+            egui::Grid::new("challenge")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(profile_name(&challenge.nickname));
+                    ui.label(Msg::Moves(challenge.moves));
+                    ui.label(challenge.duration().map_or("—".to_owned(), format_duration));
+                    ui.end_row();
+                    ui.label(Msg::You);
+                    ui.label(Msg::Moves(moves));
+                    ui.label(format_duration(state.elapsed));
+                    ui.end_row();
+                });
+        }
+    }
+
+    /// Start the puzzle of a challenge pasted from a friend.
+    fn accept_challenge_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        let (text, invalid) = &mut self.challenge_edit;
+        let mut accept = false;
+        ui.with_layout(i18n::horizontal_layout(), |ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(text)
+                    .hint_text(Msg::PasteChallenge)
+                    .desired_width(200.0),
+            );
+            if response.changed() {
+                *invalid = false;
+            }
+            accept = ui
+                .add_enabled(
+                    !text.trim().is_empty(),
+                    egui::Button::new(Msg::AcceptChallenge),
+                )
+                .clicked()
+                || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)));
+        });
+        if *invalid {
+            ui.colored_label(ui.visuals().error_fg_color, Msg::InvalidChallenge);
+        }
+        if ui.button(Msg::CreatePuzzle).clicked() {
+            self.editor = Some(editor::Editor::new(self.column_count, self.column_capacity));
+        }
+
+        if accept {
+            match shared_game(text) {
+                Some(game) => {
+                    log::info!("challenge accepted");
+                    self.challenge_edit = (String::new(), false);
+                    self.check_shared_game(game, ui.ctx());
+                }
+                None => *invalid = true,
+            }
+        }
+    }
+
+    /// Check that a shared or edited puzzle can be solved before playing it, under the rules it's
+    /// played with, which the packed board carries.
+    fn check_shared_game(&mut self, game: State, ctx: &egui::Context) {
+        let search = Search::start(PackedBoard::from(&game), ctx.clone());
+        self.puzzle_check = Some((Box::new(game), Ok(search)));
+    }
+
+    fn play_shared_game(&mut self, game: State) {
+        self.games.push(game);
+        self.current_game = Some(self.games.len() - 1);
+        self.show_setup = false;
+        self.editor = None;
+        self.changes += 1;
+        self.dirty = true;
+    }
+
+    /// Wait for the solver to vouch for the puzzle about to be played, and warn if it found no
+    /// solution, telling whether there is none or it gave up, in which case it's played only if
+    /// asked to.
+    fn puzzle_check_window(&mut self, ctx: &egui::Context) {
+        let Some((_, check)) = &mut self.puzzle_check else {
+            return;
+        };
+        if let Some(solution) = check.as_mut().ok().and_then(Search::poll) {
+            match solution {
+                Ok(moves) => {
+                    log::info!("puzzle checked, solution in {} moves", moves.len());
+                    if let Some((game, _)) = self.puzzle_check.take() {
+                        self.play_shared_game(*game);
+                    }
+                    return;
+                }
+                Err(unsolved) => {
+                    log::warn!("no solution found for the puzzle: {unsolved:?}");
+                    *check = Err(unsolved);
+                }
+            }
+        }
+        let checking = check.is_ok();
+        let unsolved = check.as_ref().err().copied();
+
+        let mut play = false;
+        let mut cancel = false;
+        egui::Window::new(Msg::OpenPuzzle)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                if checking {
+                    ui.with_layout(i18n::horizontal_layout(), |ui| {
+                        ui.spinner();
+                        ui.label(Msg::CheckingPuzzle);
+                    });
+                } else {
+                    let message = match unsolved {
+                        Some(Unsolved::GaveUp) => Msg::PuzzleTooHard,
+                        _ => Msg::UnsolvablePuzzle,
+                    };
+                    ui.colored_label(ui.visuals().warn_fg_color, message);
+                }
+                ui.add_space(6.0);
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    let label = if checking {
+                        Msg::SkipCheck
+                    } else {
+                        Msg::PlayAnyway
+                    };
+                    play = ui.button(label).clicked();
+                    cancel = ui.button(Msg::Cancel).clicked();
+                });
+            });
+
+        if play {
+            if let Some((game, _)) = self.puzzle_check.take() {
+                log::info!("playing the puzzle unchecked");
+                self.play_shared_game(*game);
+            }
+        } else if cancel {
+            self.puzzle_check = None;
+        }
+    }
+
+    /// Start the puzzle of a file dropped on the window, be it a puzzle file or a text file with
+    /// a challenge or puzzle string.
+    fn dropped_files(&mut self, ctx: &egui::Context) {
+        if self.choose_profile || self.resume_prompt {
+            return;
+        }
+
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let screen = ctx.screen_rect();
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("drop_target"),
+            ));
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                Msg::DropPuzzle,
+                egui::FontId::proportional(20.0),
+                egui::Color32::WHITE,
+            );
+        }
+
+        let Some(file) = ctx.input_mut(|i| i.raw.dropped_files.pop()) else {
+            return;
+        };
+        let contents = match (&file.bytes, &file.path) {
+            (Some(bytes), _) => Some(String::from_utf8_lossy(bytes).into_owned()),
+            #[cfg(not(target_arch = "wasm32"))]
+            (None, Some(path)) => std::fs::read_to_string(path)
+                .map_err(|err| log::warn!("failed to read {}: {err}", path.display()))
+                .ok(),
+            _ => None,
+        };
+        match contents.as_deref().and_then(shared_game) {
+            Some(game) => {
+                log::info!("puzzle dropped: {}", file.name);
+                self.invalid_drop = false;
+                self.check_shared_game(game, ctx);
+            }
+            None => {
+                log::warn!("dropped file is not a puzzle: {}", file.name);
+                self.invalid_drop = true;
+            }
+        }
+    }
+
+    fn invalid_drop_dialog(&mut self, ctx: &egui::Context) {
+        if !self.invalid_drop {
+            return;
+        }
+
+        egui::Window::new(Msg::OpenPuzzle)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(Msg::InvalidChallenge);
+                ui.add_space(6.0);
+                if ui.button(Msg::Ok).clicked() {
+                    self.invalid_drop = false;
+                }
+            });
+    }
+
+    /// Save a puzzle as a file, next to the save file on desktop, and as a download on the web.
+    fn export_puzzle(&mut self, puzzle: &puzzle::Puzzle) {
+        let contents = puzzle.to_file();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(path) = self
+                .save_file_path()
+                .and_then(|path| Some(path.parent()?.join(puzzle.file_name())))
+            else {
+                return;
+            };
+            let result = std::fs::write(&path, contents);
+            if let Err(err) = &result {
+                log::warn!("failed to write {}: {err}", path.display());
+            }
+            self.exported_puzzle = Some(result.map(|()| path).map_err(|err| err.to_string()));
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if let Err(err) = export::download(&puzzle.file_name(), "text/plain", &contents) {
+            log::warn!("failed to download the puzzle: {err:?}");
+        }
+    }
+
+    fn editor_window(&mut self, ctx: &egui::Context) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+
+        let mut open = true;
+        let outcome = egui::Window::new(Msg::PuzzleEditor)
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| editor.ui(ui))
+            .and_then(|response| response.inner)
+            .flatten();
+
+        match outcome {
+            Some(editor::Outcome::Play(game)) => {
+                log::info!("playing a puzzle from the editor");
+                self.check_shared_game(*game, ctx);
+            }
+            Some(editor::Outcome::Export(puzzle)) => self.export_puzzle(&puzzle),
+            Some(editor::Outcome::Close) => self.editor = None,
+            None if !open => self.editor = None,
+            None => {}
+        }
+    }
+
+    /// Today's puzzle, and a calendar of the past ones showing which were solved, in how many
+    /// moves.
+    fn daily_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        let today = daily::Day::of(unix_time());
+        let mut start = None;
+        egui::CollapsingHeader::new(Msg::DailyPuzzle)
+            .default_open(false)
+            .show(ui, |ui| {
+                let played_today = self.daily.get(&today).copied();
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    if ui.button(Msg::PlayToday).clicked() {
+                        start = Some(today);
+                    }
+                    if let Some(moves) = played_today {
+                        ui.label(Msg::Moves(moves));
+                    }
+                });
+
+                let month = self.daily_shown.unwrap_or(today.month());
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    if ui.small_button("⏴").clicked() {
+                        self.daily_shown = Some(month.previous());
+                    }
+                    ui.strong(month.label());
+                    if ui
+                        .add_enabled(month < today.month(), egui::Button::new("⏵").small())
+                        .clicked()
+                    {
+                        let next = month.next();
+                        self.daily_shown = (next < today.month()).then_some(next);
+                    }
+                });
+
+                egui::Grid::new("daily_calendar")
+                    .num_columns(7)
+                    .min_col_width(32.0)
+                    .show(ui, |ui| {
+                        for weekday in Msg::WeekdayInitials.to_string().split(' ') {
+                            ui.label(egui::RichText::new(weekday).weak());
+                        }
+                        ui.end_row();
+                        for _ in 0..month.first().weekday() {
+                            ui.label("");
+                        }
+                        for day in month.days() {
+                            let solved = self.daily.get(&day).copied();
+                            let mut text = egui::RichText::new(day.day_of_month().to_string());
+                            if day == today {
+                                text = text.strong();
+                            }
+                            let mut button = egui::Button::new(text).selected(solved.is_some());
+                            if day > today {
+                                button = button.frame(false);
+                            }
+                            let response = ui.add_enabled(day <= today, button);
+                            let response = match solved {
+                                Some(moves) => response.on_hover_text(Msg::Moves(moves)),
+                                None => response,
+                            };
+                            if response.clicked() {
+                                start = Some(day);
+                            }
+                            if day.weekday() == 6 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+            });
+
+        if let Some(day) = start {
+            log::info!("daily puzzle of {}", day.label());
+            let (colors, height) = daily::PUZZLE;
+            self.telemetry.game_started(colors, height);
+            self.games.push(day.game());
+            self.current_game = Some(self.games.len() - 1);
+            self.show_setup = false;
+            self.changes += 1;
+            self.dirty = true;
+        }
+    }
+
+    /// Star the board of the current game, or unstar it. A board shuffled from a seed starts with
+    /// the best of its earlier wins.
+    fn toggle_favorite(&mut self) {
+        let Some(state) = self.current() else {
+            return;
+        };
+        let Some(puzzle) = puzzle::Puzzle::of_game(state) else {
+            return;
+        };
+        if self
+            .favorites
+            .iter()
+            .any(|favorite| favorite.puzzle == puzzle)
+        {
+            log::info!("board unstarred");
+            self.favorites.retain(|favorite| favorite.puzzle != puzzle);
+        } else if let Some(mut favorite) = favorites::Favorite::of_game(state, unix_time()) {
+            log::info!("board starred");
+            let config = state.config();
+            if let Some(seed) = favorite.seed {
+                for record in &self.records {
+                    if record.seed == Some(seed) && record.config() == config {
+                        favorite.record(record.moves);
+                    }
+                }
+            }
+            self.favorites.push(favorite);
+        }
+        self.dirty = true;
+    }
+
+    /// Starred boards, with the best result on each, to be played again in one click.
+    fn favorites_ui(&mut self, ui: &mut egui::Ui) {
+        if self.favorites.is_empty() {
+            return;
+        }
+
+        ui.add_space(12.0);
+        let mut play = None;
+        let mut remove = None;
+        egui::CollapsingHeader::new(Msg::Favorites)
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::Grid::new("favorites")
+                    .striped(true)
+                    .num_columns(5)
+                    .show(ui, |ui| {
+                        for (index, favorite) in self.favorites.iter_mut().enumerate().rev() {
+                            let puzzle = &favorite.puzzle;
+                            let board = puzzle.board_label();
+                            if puzzle.name.is_empty() {
+                                ui.label(board);
+                            } else {
+                                ui.label(&puzzle.name).on_hover_text(board);
+                            }
+                            ui.label(export::format_date(favorite.added));
+                            match favorite.best {
+                                Some(best) => ui.label(Msg::Moves(best)).on_hover_text(Msg::Best),
+                                None => ui.label("—"),
+                            };
+                            self.dirty |= note_ui(ui, &mut favorite.note).changed();
+                            ui.with_layout(i18n::horizontal_layout(), |ui| {
+                                if ui.small_button(Msg::Play).clicked() {
+                                    play = Some(index);
+                                }
+                                if ui
+                                    .small_button("🗑")
+                                    .on_hover_text(Msg::RemoveFavorite)
+                                    .clicked()
+                                {
+                                    remove = Some(index);
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(game) = play.and_then(|index| self.favorites[index].game()) {
+            log::info!("replaying a starred board");
+            let config = game.config();
+            self.telemetry
+                .game_started(config.colors(), config.column_capacity);
+            self.games.push(game);
+            self.current_game = Some(self.games.len() - 1);
+            self.show_setup = false;
+            self.changes += 1;
+            self.dirty = true;
+        }
+        if let Some(index) = remove {
+            self.favorites.remove(index);
+            self.dirty = true;
+        }
+    }
+
+    /// Games closed lately, won or not, to be tried again in one click.
+    fn recent_ui(&mut self, ui: &mut egui::Ui) {
+        if self.recent.is_empty() {
+            return;
+        }
+
+        ui.add_space(12.0);
+        let mut retry = None;
+        egui::CollapsingHeader::new(Msg::RecentlyPlayed)
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::Grid::new("recent_games")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (index, game) in self.recent.iter().enumerate() {
+                            let board = ui.label(game.puzzle.board_label());
+                            if let Some(seed) = game.seed {
+                                board.on_hover_text(Msg::Seed(seed));
+                            }
+                            ui.label(match game.outcome {
+                                recent::Outcome::Won(moves) => Msg::WonIn(moves),
+                                recent::Outcome::Abandoned(moves) => Msg::AbandonedAfter(moves),
+                            });
+                            if ui.small_button(Msg::Retry).clicked() {
+                                retry = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(game) = retry.and_then(|index| self.recent[index].game()) {
+            log::info!("retrying a recent board");
+            let config = game.config();
+            self.telemetry
+                .game_started(config.colors(), config.column_capacity);
+            self.games.push(game);
+            self.current_game = Some(self.games.len() - 1);
+            self.show_setup = false;
+            self.changes += 1;
+            self.dirty = true;
+        }
+    }
+
+    /// Puzzles of the weekly challenge, of this week or an archived one, with their results.
+    fn weekly_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        let current = weekly::Week::of(unix_time());
+        let mut start = None;
+        egui::CollapsingHeader::new(Msg::WeeklyChallenge)
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.label(Msg::WeeklyChallengeNote);
+                let week = self.weekly_shown.unwrap_or(current);
+                let mut shown = week;
+                egui::ComboBox::from_id_source("weekly_week")
+                    .selected_text(week.label())
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut shown, current, current.label());
+                        for &past in self.weekly.keys().rev().filter(|past| **past < current) {
+                            ui.selectable_value(&mut shown, past, past.label());
+                        }
+                    });
+                self.weekly_shown = (shown != current).then_some(shown);
+
+                let results = self.weekly.get(&week).cloned().unwrap_or_default();
+                egui::Grid::new("weekly_puzzles")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (index, (colors, height)) in weekly::PUZZLES.into_iter().enumerate() {
+                            if ui.button(format!("{colors}x{height}")).clicked() {
+                                start = Some((week, index));
+                            }
+                            match results.moves(index) {
+                                Some(moves) => {
+                                    ui.label(Msg::Moves(moves));
+                                    ui.label(Msg::Points(weekly::points(index, moves)));
+                                }
+                                None => {
+                                    ui.label("—");
+                                    ui.label("");
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.strong(Msg::WeeklyScore {
+                    completed: results.completed(),
+                    total: weekly::PUZZLES.len(),
+                    score: results.score(),
+                });
+            });
+
+        if let Some((week, index)) = start {
+            log::info!("weekly challenge {} puzzle {index}", week.label());
+            let (colors, height) = weekly::PUZZLES[index];
+            self.telemetry.game_started(colors, height);
+            self.games.push(week.game(index));
+            self.current_game = Some(self.games.len() - 1);
+            self.show_setup = false;
+            self.changes += 1;
+            self.dirty = true;
+        }
+    }
+
+    /// Compare the game just won to the average of all scores submitted for its puzzle.
+    fn global_stats_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(index) = self.current_game.filter(|_| self.leaderboard.is_active()) else {
+            return;
+        };
+        let Some(state) = self.games.get(index).filter(|state| state.is_ranked()) else {
+            return;
+        };
+        let Some(moves) = state.is_winning() else {
+            return;
+        };
+
+        if self
+            .global_stats
+            .as_ref()
+            .is_none_or(|(key, _)| *key != (index, moves))
+        {
+            let key = (state.column_count, state.column_capacity);
+            let hash = state
+                .initial_codes()
+                .map(|start| leaderboard::puzzle_hash(&start));
+            let request = leaderboard::fetch_stats(
+                &self.leaderboard,
+                key,
+                hash.as_deref(),
+                moves,
+                ui.ctx().clone(),
+            );
+            self.global_stats = Some(((index, moves), request));
+        }
+        let Some((_, request)) = &mut self.global_stats else {
+            return;
+        };
+        // only shown once known, the comparison is a bonus
+        if let Some(Ok(stats)) = request.poll() {
+            if stats.count > 0 {
+                ui.add_space(12.0);
+                ui.label(Msg::GlobalAverage {
+                    average: stats.average_moves,
+                    count: stats.count,
+                });
+                ui.label(Msg::GlobalPercentile(
+                    (stats.percentile.clamp(0.0, 1.0) * 100.0).round() as usize,
+                ));
+            }
+        }
+    }
+
+    /// Offer to submit the game just won to the leaderboard.
+    fn submit_score_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(index) = self.current_game.filter(|_| self.leaderboard.is_active()) else {
+            return;
+        };
+        if self
+            .games
+            .get(index)
+            .filter(|state| state.is_ranked())
+            .is_none()
+        {
+            return;
+        }
+
+        ui.add_space(12.0);
+        let mut failed = None;
+        if let Some((submitted, request)) = &mut self.score_submission {
+            if *submitted == index {
+                match request.poll() {
+                    None => {
+                        ui.spinner();
+                        return;
+                    }
+                    Some(Ok(())) => {
+                        self.games[index].score_submitted = true;
+                        self.score_submission = None;
+                        // the new score may have made it to the top
+                        self.leaderboard_top = None;
+                        self.changes += 1;
+                        self.dirty = true;
+                    }
+                    Some(Err(err)) => failed = Some(err.clone()),
+                }
+            }
+        }
+
+        let Some(state) = self.games.get(index) else {
+            return;
+        };
+        if state.score_submitted {
+            ui.label(Msg::ScoreSubmitted);
+            return;
+        }
+        if let Some(err) = failed {
+            ui.colored_label(ui.visuals().error_fg_color, Msg::SubmitFailed)
+                .on_hover_text(err);
+        }
+        let nickname = self.profile.nickname.trim();
+        let clicked = ui
+            .add_enabled(!nickname.is_empty(), egui::Button::new(Msg::SubmitScore))
+            .on_disabled_hover_text(Msg::NicknameNeeded)
+            .clicked();
+        if clicked {
+            let start = state.initial_codes().unwrap_or_default();
+            let submission = leaderboard::Submission {
+                nickname: nickname.to_owned(),
+                colors: state.color_count(),
+                height: state.column_capacity,
+                moves: state.play_count,
+                duration_seconds: state.elapsed.as_secs_f32(),
+                seed: state.seed,
+                puzzle_hash: leaderboard::puzzle_hash(&start),
+                start,
+                move_list: state.moves.iter().map(|mv| [mv.from, mv.to]).collect(),
+            };
+            let request = leaderboard::submit(&self.leaderboard, &submission, ui.ctx().clone());
+            self.score_submission = Some((index, request));
+        }
+        ui.toggle_value(&mut self.show_leaderboard, Msg::Leaderboard);
+    }
+
+    /// Best scores for the board being played, or the one selected on the setup screen.
+    fn leaderboard_window(&mut self, ctx: &egui::Context) {
+        let key = self
+            .current()
+            .filter(|_| !self.show_setup)
+            .map_or((self.column_count, self.column_capacity), |state| {
+                (state.column_count, state.column_capacity)
+            });
+        if self
+            .leaderboard_top
+            .as_ref()
+            .is_none_or(|(top_key, _)| *top_key != key)
+        {
+            let request = leaderboard::fetch_top(&self.leaderboard, key, ctx.clone());
+            self.leaderboard_top = Some((key, request));
+        }
+
+        let mut open = self.show_leaderboard;
+        let mut refresh = false;
+        egui::Window::new(Msg::Leaderboard)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    ui.strong(format!("{}x{}", key.0.saturating_sub(1), key.1));
+                    refresh = ui.small_button(Msg::Refresh).clicked();
+                });
+                ui.add_space(6.0);
+
+                let Some((_, request)) = &mut self.leaderboard_top else {
+                    return;
+                };
+                match request.poll() {
+                    None => {
+                        ui.spinner();
+                    }
+                    Some(Err(err)) => {
+                        ui.label(Msg::LeaderboardUnavailable).on_hover_text(err);
+                    }
+                    Some(Ok(entries)) if entries.is_empty() => {
+                        ui.label(Msg::NoScores);
+                    }
+                    Some(Ok(entries)) => {
+                        egui::ScrollArea::vertical()
+                            .max_height(400.0)
+                            .show(ui, |ui| {
+                                egui::Grid::new("leaderboard")
+                                    .striped(true)
+                                    .num_columns(4)
+                                    .show(ui, |ui| {
+                                        for (rank, entry) in entries.iter().enumerate() {
+                                            ui.label(format!("{}.", rank + 1));
+                                            ui.with_layout(i18n::horizontal_layout(), |ui| {
+                                                ui.label(&entry.nickname);
+                                                if entry.verified {
+                                                    ui.label("✔")
+                                                        .on_hover_text(Msg::ReplayVerified);
+                                                }
+                                            });
+                                            ui.label(Msg::Moves(entry.moves));
+                                            if let Some(seconds) = entry.duration_seconds {
+                                                // scores come from the server, as sent by anyone
+                                                let duration = Duration::try_from_secs_f32(seconds)
+                                                    .map_or("—".to_owned(), format_duration);
+                                                ui.label(duration);
+                                            }
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                    }
+                }
+            });
+
+        self.show_leaderboard = open;
+        if refresh {
+            self.leaderboard_top = None;
+        }
+    }
+
+    fn telemetry_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        egui::CollapsingHeader::new(Msg::UsageStatistics)
+            .id_source("telemetry")
+            .show(ui, |ui| {
+                ui.label(Msg::UsageStatisticsText);
+                let mut enabled = self.telemetry.enabled;
+                if ui
+                    .checkbox(&mut enabled, Msg::SendUsageStatistics)
+                    .changed()
+                {
+                    self.telemetry.set_enabled(enabled, unix_time());
+                    self.dirty = true;
+                }
+                egui::CollapsingHeader::new(Msg::UsageStatisticsPreview)
+                    .id_source("telemetry_preview")
+                    .show(ui, |ui| {
+                        ui.monospace(self.telemetry.report(&self.records));
+                    });
+            });
+    }
+
+    fn sync_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        egui::CollapsingHeader::new(Msg::Sync)
+            .id_source("sync")
+            .show(ui, |ui| {
+                let mut changed = ui
+                    .checkbox(&mut self.sync.enabled, Msg::SyncEnabled)
+                    .changed();
+
+                ui.label(Msg::SyncUrl);
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.sync.url)
+                        .hint_text("https://example.com/dav/boule.ron")
+                        .desired_width(f32::INFINITY),
+                );
+                if response.changed() {
+                    // the last sync says nothing about another server's copy
+                    self.synced = 0;
+                    changed = true;
+                }
+                ui.label(Msg::Username);
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.sync.username)
+                            .desired_width(f32::INFINITY),
+                    )
+                    .changed();
+                ui.label(Msg::Password);
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.sync.password)
+                            .password(true)
+                            .desired_width(f32::INFINITY),
+                    )
+                    .changed();
+                self.dirty |= changed;
+
+                ui.add_space(6.0);
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    let can_sync = self.sync.is_active()
+                        && self.sync_request.is_none()
+                        && self.sync_conflict.is_none();
+                    if ui
+                        .add_enabled(can_sync, egui::Button::new(Msg::SyncNow))
+                        .clicked()
+                    {
+                        self.sync_requested = true;
+                    }
+                    match &self.sync_status {
+                        SyncStatus::Idle => {}
+                        SyncStatus::Syncing => {
+                            ui.spinner();
+                        }
+                        SyncStatus::UpToDate => {
+                            ui.label(Msg::SyncUpToDate);
+                        }
+                        SyncStatus::Failed(err) => {
+                            ui.colored_label(ui.visuals().error_fg_color, Msg::SyncFailed)
+                                .on_hover_text(err);
+                        }
+                    }
+                });
+            });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn broadcast_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        egui::CollapsingHeader::new(Msg::Broadcast)
+            .id_source("broadcast")
+            .show(ui, |ui| {
+                ui.label(Msg::BroadcastText);
+                self.dirty |= ui
+                    .checkbox(&mut self.broadcast.enabled, Msg::BroadcastEnabled)
+                    .changed();
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    ui.label(Msg::Port);
+                    self.dirty |= ui
+                        .add(
+                            egui::DragValue::new(&mut self.broadcast.port)
+                                .clamp_range(1024..=65535),
+                        )
+                        .changed();
+                });
+                match &self.broadcaster {
+                    None => {}
+                    Some(Ok(broadcaster)) => {
+                        ui.label(Msg::Broadcasting(broadcaster.port()));
+                    }
+                    Some(Err((_, err))) => {
+                        ui.colored_label(ui.visuals().error_fg_color, Msg::ListenFailed)
+                            .on_hover_text(err);
+                    }
+                }
+            });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn remote_control_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        egui::CollapsingHeader::new(Msg::RemoteControl)
+            .id_source("remote_control")
+            .show(ui, |ui| {
+                ui.label(Msg::RemoteControlText);
+                self.dirty |= ui
+                    .checkbox(&mut self.remote.enabled, Msg::RemoteControlEnabled)
+                    .changed();
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    ui.label(Msg::Port);
+                    self.dirty |= ui
+                        .add(egui::DragValue::new(&mut self.remote.port).clamp_range(1024..=65535))
+                        .changed();
+                });
+                match &self.remote_control {
+                    None => {}
+                    Some(Ok(remote_control)) => {
+                        ui.label(Msg::RemoteControlListening(remote_control.port()));
+                    }
+                    Some(Err((_, err))) => {
+                        ui.colored_label(ui.visuals().error_fg_color, Msg::ListenFailed)
+                            .on_hover_text(err);
+                    }
+                }
+            });
+    }
+
+    /// Start or stop the spectator broadcast as set, and send it the game being played.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_broadcast(&mut self) {
+        listen_on(
+            &mut self.broadcaster,
+            self.broadcast.enabled.then_some(self.broadcast.port),
+            broadcast::Broadcaster::port,
+            broadcast::Broadcaster::start,
+        );
+
+        if let (Some(Ok(broadcaster)), Some(index)) = (&mut self.broadcaster, self.current_game) {
+            if let Some(state) = self.games.get(index) {
+                broadcaster.send_game(index, state);
+            }
+        }
+    }
+
+    /// Start or stop the remote control as set, and answer its requests.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_remote_control(&mut self, ctx: &egui::Context) {
+        listen_on(
+            &mut self.remote_control,
+            self.remote.enabled.then_some(self.remote.port),
+            remote::RemoteControl::port,
+            |port| remote::RemoteControl::start(port, ctx.clone()),
+        );
+
+        let Some(Ok(remote_control)) = &self.remote_control else {
+            return;
+        };
+        let requests: Vec<_> = std::iter::from_fn(|| remote_control.poll()).collect();
+        for request in requests {
+            let reply = self.remote_command(&request.command);
+            request.reply(reply);
+            ctx.request_repaint();
+        }
+    }
+
+    /// Answer a remote control command about the game being played, playing moves as if they
+    /// were dragged on the board.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn remote_command(&mut self, command: &remote::Command) -> remote::Reply {
+        let journal = self.journal();
+        let Some((index, state)) = self
+            .current_game
+            .and_then(|index| Some((index, self.games.get_mut(index)?)))
+        else {
+            return remote::Reply::error(404, "no game is being played");
+        };
+
+        match *command {
+            remote::Command::GetState => {
+                remote::Reply::json(&broadcast::Snapshot::new(index, state, None))
+            }
+            remote::Command::LegalMoves => {
+                let moves: Vec<_> = state
+                    .legal_moves()
+                    .into_iter()
+                    .map(|mv| [mv.from, mv.to])
+                    .collect();
+                remote::Reply::json(&moves)
+            }
+            remote::Command::ApplyMove(mv) => {
+                if state.paused || state.is_winning().is_some() {
+                    return remote::Reply::error(409, "the game is paused or over");
+                }
+                if !state.move_ball(mv.from, mv.to) {
+                    return remote::Reply::error(409, "illegal move");
+                }
+                self.changes += 1;
+                // moves are forgotten once the extra column goes, which the save has to catch
+                let journaled = state.moves.len().checked_sub(1).is_some_and(|moves| {
+                    let entry = journal::Entry::Moved {
+                        game: index,
+                        moves,
+                        from: mv.from,
+                        to: mv.to,
+                    };
+                    journal.is_some_and(|journal| journal.append(entry))
+                });
+                self.dirty |= !journaled;
+                remote::Reply::json(&broadcast::Snapshot::new(index, state, Some(mv)))
+            }
+        }
+    }
+
+    fn game_ui(&mut self, ui: &mut egui::Ui) {
+        let journal = self.journal();
+        ui.vertical_centered(|ui| {
+            let mut jump_to = None;
+            let Some(state) = self
+                .current_game
+                .and_then(|index| self.games.get_mut(index))
+            else {
+                return;
+            };
+
+            let played = state.tick(ui.input(|i| i.unstable_dt));
+            if !played.is_zero() {
+                *self.play_time.entry(state.config()).or_default() += played;
+            }
+
+            // coach marks, for players who haven't won a game yet
+            if self.history.is_empty() && !state.paused {
+                let tip = Tip::ALL
+                    .iter()
+                    .find(|tip| !self.seen_tips.contains(tip) && tip.applies_to(state));
+                if let Some(&tip) = tip {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label(tip.text());
+                        if ui.small_button(Msg::GotIt).clicked() {
+                            self.seen_tips.insert(tip);
+                            self.dirty = true;
+                        }
+                    });
+                    ui.add_space(6.0);
+                }
+            }
+
+            if self.show_color_summary
+                && state.mode != Mode::Kid
+                && state.mode.goal() == Goal::Colors
+                && !state.paused
+                && state.is_winning().is_none()
+            {
+                color_summary_ui(ui, state);
+            }
+
+            let view = BoardView {
+                mirrored: self.mirror_board && i18n::is_right_to_left(),
+                circular: self.circular_board,
+            };
+            if let (Some(position), false) = (self.scrub, state.paused) {
+                // only a preview until the scrubber is released
+                let mut preview = state.at_position(position);
+                ui.add_enabled_ui(false, |ui| preview.ui(ui, view));
+            } else {
+                let (index, paused) = (self.current_game, state.paused);
+                let mut journaled = true;
+                let board = widget::BouleBoard::new(state)
+                    .view(view)
+                    .on_move(|state, mv| {
+                        // moves are forgotten once the extra column goes, which the save has to
+                        // catch
+                        let moves = state.moves.len().checked_sub(1);
+                        if let Some(index) = index {
+                            journaled = moves.is_some_and(|moves| {
+                                let entry = journal::Entry::Moved {
+                                    game: index,
+                                    moves,
+                                    from: mv.from,
+                                    to: mv.to,
+                                };
+                                journal
+                                    .as_ref()
+                                    .is_some_and(|journal| journal.append(entry))
+                            });
+                        }
+                    })
+                    .on_win(|state, _| {
+                        if state.mode == Mode::Kid {
+                            sound::celebrate();
+                        }
+                    });
+                let response = ui
+                    .add_enabled_ui(paused || !self.confirm_abort, |ui| ui.add(board))
+                    .inner;
+                if response.changed() {
+                    self.changes += 1;
+                }
+                // resuming is saved right away, moves go to the journal when there's one
+                self.dirty |= (paused && response.changed()) || !journaled;
+            }
+
+            let scrubbed = state.moves.len() + state.undone_moves.len();
+            if state.mode != Mode::Kid
+                && !state.paused
+                && scrubbed > 0
+                && state.is_winning().is_none()
+            {
+                let mut position = self.scrub.unwrap_or(state.moves.len());
+                let response = ui
+                    .add(egui::Slider::new(&mut position, 0..=scrubbed).show_value(false))
+                    .on_hover_text(Msg::ScrubberNote);
+                if response.dragged() {
+                    self.scrub = Some(position);
+                } else if response.drag_stopped() || response.changed() {
+                    self.scrub = None;
+                    jump_to = Some(position);
+                }
+            } else {
+                self.scrub = None;
+            }
+
+            ui.add_space(6.0);
+            let kid = state.mode == Mode::Kid;
+            if !kid {
+                ui.label(egui::RichText::new(format_duration(state.elapsed)).monospace());
+                if !state.paused && state.is_winning().is_none() {
+                    ui.small(Msg::LegalMoves(state.distinct_moves()))
+                        .on_hover_text(Msg::LegalMovesNote);
+                }
+            }
+            match state.extra_column {
+                Some(0) => {
+                    ui.label(Msg::EmptyExtraColumn);
+                }
+                Some(moves_left) => {
+                    ui.label(Msg::ExtraColumnMovesLeft(moves_left));
+                }
+                None => {}
+            }
+            match state.hint {
+                Some(Hint::Searching) => {
+                    ui.label(Msg::SearchingHint);
+                    ui.spinner();
+                }
+                Some(Hint::NotFound) => {
+                    ui.label(Msg::HintNotFound);
+                }
+                Some(Hint::Move(mv)) => {
+                    ui.label(Msg::HintMove {
+                        from: mv.from,
+                        to: mv.to,
+                    });
+                }
+                None => {}
+            }
+            // The app is otherwise only repainted on input, so the clock is the one thing that
+            // needs a timer. It's accumulated from frame deltas, so without repaints while the
+            // window is in the background, that time isn't counted.
+            if !state.paused && state.is_winning().is_none() && ui.input(|i| i.focused) {
+                let until_next_second = Duration::from_secs(1)
+                    - Duration::from_nanos(state.elapsed.subsec_nanos().into());
+                ui.ctx().request_repaint_after(until_next_second);
+            }
+
+            ui.add_space(6.0);
+
+            let mut action = None;
+            let starred = favorites::is_starred(&self.favorites, state);
+            let mut toggle_favorite = false;
+            if !state.paused && state.shows_stuck_warning() {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(Msg::MaybeStuck);
+                    ui.with_layout(i18n::horizontal_layout(), |ui| {
+                        if ui.small_button(Msg::UndoButton).clicked() {
+                            action = Some(Action::Undo);
+                        }
+                        if ui
+                            .add_enabled(
+                                state.can_restart(),
+                                egui::Button::new(Msg::Restart).small(),
+                            )
+                            .on_hover_text(Action::Restart.shortcut_hint(ui.ctx()))
+                            .clicked()
+                        {
+                            action = Some(Action::Restart);
+                        }
+                        if ui.small_button(Msg::GotIt).clicked() {
+                            state.stuck_dismissed = Some(state.play_count);
+                        }
+                    });
+                });
+                ui.add_space(6.0);
+            }
+            if kid && state.is_winning().is_some() {
+                ui.label(
+                    egui::RichText::from(Msg::WellDone)
+                        .color(egui::Color32::RED)
+                        .size(48.0)
+                        .strong(),
+                );
+                ui.add_space(12.0);
+                if ui
+                    .button(egui::RichText::from(Msg::PlayAgain).size(28.0))
+                    .clicked()
+                {
+                    self.close_current_game();
+                    self.start_kid_game();
+                }
+            } else if let Some(play_count) = state.is_winning() {
+                let config = state.config();
+                ui.label(
+                    egui::RichText::from(Msg::YouWon(play_count))
+                        .color(egui::Color32::RED)
+                        .size(24.0)
+                        .strong(),
+                );
+                ui.add_space(6.0);
+                toggle_favorite = self.win_summary_ui(ui, starred);
+
+                self.challenge_result_ui(ui);
+                self.global_stats_ui(ui);
+                self.history_ui(ui, config, Some(play_count));
+                self.submit_score_ui(ui);
+            } else {
+                let (can_undo, can_redo, paused) =
+                    (state.can_undo(), state.can_redo(), state.paused);
+                if !kid
+                    && !paused
+                    && ui
+                        .button(Msg::Pause)
+                        .on_hover_text(Action::Pause.shortcut_hint(ui.ctx()))
+                        .clicked()
+                {
+                    action = Some(Action::Pause);
+                }
+                if ui
+                    .add_enabled(can_undo, egui::Button::new(Msg::UndoButton))
+                    .on_hover_text(Action::Undo.shortcut_hint(ui.ctx()))
+                    .clicked()
+                {
+                    action = Some(Action::Undo);
+                }
+                if ui
+                    .add_enabled(can_redo, egui::Button::new(Msg::RedoButton))
+                    .on_hover_text(Action::Redo.shortcut_hint(ui.ctx()))
+                    .clicked()
+                {
+                    action = Some(Action::Redo);
+                }
+                if !kid
+                    && ui
+                        .add_enabled(
+                            state.hints_left() > 0,
+                            egui::Button::new(Msg::HintButton(state.hints_left())),
+                        )
+                        .on_hover_text(Action::Hint.shortcut_hint(ui.ctx()))
+                        .clicked()
+                {
+                    action = Some(Action::Hint);
+                }
+                if !kid
+                    && self.powerups
+                    && ui
+                        .add_enabled(
+                            state.can_reshuffle(),
+                            egui::Button::new(Msg::ReshuffleButton(RESHUFFLE_PENALTY)),
+                        )
+                        .on_hover_text(Msg::Reshuffle)
+                        .clicked()
+                {
+                    action = Some(Action::Reshuffle);
+                }
+                if !kid
+                    && self.powerups
+                    && ui
+                        .add_enabled(
+                            state.can_add_extra_column(),
+                            egui::Button::new(Msg::ExtraColumnButton(EXTRA_COLUMN_PENALTY)),
+                        )
+                        .on_hover_text(Msg::ExtraColumnNote(EXTRA_COLUMN_MOVES))
+                        .clicked()
+                {
+                    action = Some(Action::ExtraColumn);
+                }
+                if !kid {
+                    toggle_favorite = favorite_button(ui, starred);
+                }
+                if ui
+                    .button(Msg::NewGame)
+                    .on_hover_text(Action::NewGame.shortcut_hint(ui.ctx()))
+                    .clicked()
+                {
+                    action = Some(Action::NewGame);
+                }
+                if !kid && ui.button(Msg::Abort).clicked() {
+                    action = Some(Action::Abort);
+                }
+                // there is no menu bar on the web
+                #[cfg(target_arch = "wasm32")]
+                if !kid
+                    && ui
+                        .selectable_label(self.show_move_log, Msg::MoveLog)
+                        .clicked()
+                {
+                    action = Some(Action::MoveLog);
+                }
+            }
+
+            if let Some(action) = action {
+                self.apply(ui.ctx(), action);
+            }
+            if toggle_favorite {
+                self.toggle_favorite();
+            }
+            if let Some(position) = jump_to {
+                self.jump_to(ui.ctx(), position);
+            }
+
+            if self.confirm_abort {
+                self.abort_dialog(ui.ctx());
+            }
+
+            footer_ui(ui);
+        });
+    }
+
+    fn is_enabled(&self, action: Action) -> bool {
+        let playing = self
+            .current()
+            .filter(|_| !self.show_setup && !self.resume_prompt);
+        match action {
+            Action::Restart => playing.is_some_and(State::can_restart),
+            Action::Abort | Action::SaveForLater | Action::Pause => {
+                playing.is_some_and(|state| state.is_winning().is_none())
+            }
+            Action::Undo => playing.is_some_and(|state| !state.paused && state.can_undo()),
+            Action::Redo => playing.is_some_and(|state| !state.paused && state.can_redo()),
+            Action::Reshuffle => {
+                self.powerups
+                    && playing.is_some_and(|state| {
+                        !state.paused && state.mode != Mode::Kid && state.can_reshuffle()
+                    })
+            }
+            Action::ExtraColumn => {
+                self.powerups
+                    && playing.is_some_and(|state| {
+                        !state.paused && state.mode != Mode::Kid && state.can_add_extra_column()
+                    })
+            }
+            // the solver doesn't know about the temporary extra column
+            Action::Hint => playing.is_some_and(|state| {
+                !state.paused
+                    && state.hints_left() > 0
+                    && state.is_winning().is_none()
+                    && state.extra_column.is_none()
+                    && state.hint != Some(Hint::Searching)
+            }),
+            Action::NewGame
+            | Action::ZoomIn
+            | Action::ZoomOut
+            | Action::ResetZoom
+            | Action::HowToPlay
+            | Action::Shortcuts
+            | Action::About
+            | Action::LogPanel
+            | Action::MoveLog
+            | Action::DebugOverlay => true,
+            Action::ExportCsv => !self.records.is_empty(),
+            #[cfg(feature = "puffin")]
+            Action::Profiler => true,
+        }
+    }
+
+    fn apply(&mut self, ctx: &egui::Context, action: Action) {
+        if !self.is_enabled(action) {
+            return;
+        }
+        // moves are journaled instead
+        self.dirty |= !matches!(action, Action::Undo | Action::Redo);
+        if matches!(
+            action,
+            Action::Restart
+                | Action::Pause
+                | Action::Undo
+                | Action::Redo
+                | Action::Hint
+                | Action::Reshuffle
+                | Action::ExtraColumn
+        ) {
+            self.changes += 1;
+        }
+
+        let journal = self.journal();
+        let state = self
+            .current_game
+            .and_then(|index| self.games.get_mut(index));
+        match action {
+            Action::NewGame => {
+                self.resume_prompt = false;
+                if self.current_game.is_some() {
+                    self.show_setup = true;
+                }
+            }
+            Action::Restart => state.into_iter().for_each(State::restart),
+            Action::Abort => self.confirm_abort = true,
+            Action::SaveForLater => self.current_game = None,
+            Action::Pause => {
+                if let Some(state) = state {
+                    state.paused = !state.paused;
+                }
+            }
+            Action::Undo | Action::Redo => {
+                if let (Some(index), Some(state)) = (self.current_game, state) {
+                    let moves = state.moves.len();
+                    let had_extra_column = state.extra_column.is_some();
+                    let entry = if action == Action::Undo {
+                        state.undo();
+                        journal::Entry::Undone { game: index, moves }
+                    } else {
+                        state.redo();
+                        journal::Entry::Redone { game: index, moves }
+                    };
+                    // the moves are forgotten along with the extra column, so save right away
+                    let removed_extra_column = had_extra_column && state.extra_column.is_none();
+                    self.dirty |= !journal.is_some_and(|journal| journal.append(entry))
+                        || removed_extra_column;
+                }
+            }
+            Action::Reshuffle => {
+                if let Some(state) = state {
+                    state.reshuffle(&mut rand::thread_rng());
+                }
+            }
+            Action::ExtraColumn => state.into_iter().for_each(State::add_extra_column),
+            Action::Hint => {
+                if let (Some(index), Some(state)) = (self.current_game, state) {
+                    state.hint = Some(Hint::Searching);
+                    state.hints_used += 1;
+                    let search = Search::start(PackedBoard::from(&*state), ctx.clone());
+                    // only one search runs at a time: another game waiting for its hint gets it
+                    // back
+                    if let Some((other, _)) = self.hint_search.replace((index, search)) {
+                        if let Some(other) = self.games.get_mut(other).filter(|_| other != index) {
+                            if other.hint == Some(Hint::Searching) {
+                                other.hint = None;
+                                other.hints_used = other.hints_used.saturating_sub(1);
+                            }
+                        }
+                    }
+                }
+            }
+            Action::ZoomIn | Action::ZoomOut | Action::ResetZoom => {
+                self.ui_scale = match action {
+                    Action::ZoomIn => self.ui_scale + 0.1,
+                    Action::ZoomOut => self.ui_scale - 0.1,
+                    _ => 1.0,
+                }
+                .clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end());
+                ctx.set_zoom_factor(self.ui_scale);
+            }
+            Action::HowToPlay => self.show_how_to_play = true,
+            Action::Shortcuts => self.show_shortcuts = !self.show_shortcuts,
+            Action::About => self.show_about = true,
+            Action::LogPanel => self.show_log_panel = !self.show_log_panel,
+            Action::MoveLog => self.show_move_log = !self.show_move_log,
+            Action::DebugOverlay => self.show_debug_overlay = !self.show_debug_overlay,
+            Action::ExportCsv => self.export_csv(),
+            #[cfg(feature = "puffin")]
+            Action::Profiler => {
+                self.show_profiler = !self.show_profiler;
+                puffin::set_scopes_on(self.show_profiler);
+            }
+        }
+    }
+
+    /// Write the history as CSV next to the save file on desktop, or download it on the web.
+    fn export_csv(&mut self) {
+        let csv = export::history_csv(&self.records);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(path) = self
+                .save_file_path()
+                .and_then(|path| Some(path.parent()?.join(export::FILE_NAME)))
+            else {
+                return;
+            };
+            let result = std::fs::write(&path, csv);
+            if let Err(err) = &result {
+                log::warn!("failed to write {}: {err}", path.display());
+            }
+            self.exported_csv = Some(result.map(|()| path).map_err(|err| err.to_string()));
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if let Err(err) = export::download(export::FILE_NAME, "text/csv", &csv) {
+            log::warn!("failed to download the history: {err:?}");
+        }
+    }
+
+    /// Deliver the hint once found. The search is dropped, which stops it, as soon as its game no
+    /// longer waits for it, e.g. because a move was played meanwhile.
+    fn poll_hint_search(&mut self) {
+        let Some((index, search)) = &mut self.hint_search else {
+            return;
+        };
+        let Some(state) = self
+            .games
+            .get_mut(*index)
+            .filter(|state| state.hint == Some(Hint::Searching))
+        else {
+            self.hint_search = None;
+            return;
+        };
+
+        search.set_paused(state.paused);
+        if let Some(solution) = search.poll() {
+            let message = match &solution {
+                Ok(moves) => match moves.first() {
+                    Some(mv) => format!("hint {mv}, solution in {} moves", moves.len()),
+                    None => "hint found, already solved".to_owned(),
+                },
+                Err(Unsolved::Impossible) => "no solution for hint".to_owned(),
+                Err(Unsolved::GaveUp) => "no solution found for hint".to_owned(),
+            };
+            log::info!("{message}");
+            #[cfg(feature = "rerun")]
+            if let Some(recording) = &self.recording {
+                recording.log_text(*index, &message);
+            }
+            // the solver doesn't know about a closed extra column
+            let legal_moves = state.legal_moves();
+            state.hint = Some(
+                solution
+                    .ok()
+                    .and_then(|moves| moves.first().copied())
+                    .filter(|mv| legal_moves.contains(mv))
+                    .map_or(Hint::NotFound, Hint::Move),
+            );
+            self.hint_search = None;
+        }
+    }
+
+    /// Rate the wins one at a time, the first won first.
+    fn poll_par_search(&mut self) {
+        let Some((index, finished, search)) = self.par_searches.front_mut() else {
+            return;
+        };
+        let Some(solution) = search.poll() else {
+            return;
+        };
+
+        match solution {
+            Ok(moves) => {
+                log::info!(
+                    "win rated against a shortest solution in {} moves",
+                    moves.len()
+                );
+                let par = Some(moves.len());
+                if let Some(state) = index.and_then(|index| self.games.get_mut(index)) {
+                    state.par = par;
+                }
+                if let Some(record) = self
+                    .records
+                    .iter_mut()
+                    .rev()
+                    .find(|record| record.finished == Some(*finished))
+                {
+                    record.par = par;
+                }
+                self.changes += 1;
+                self.dirty = true;
+            }
+            Err(_) => log::info!("no shortest solution found to rate the win"),
+        }
+        self.par_searches.pop_front();
+    }
+
+    /// Handle the outcome of the sync request in flight, or pull from the sync server when asked
+    /// to or when local changes are waiting to be pushed.
+    fn update_sync(&mut self, ctx: &egui::Context) {
+        if let Some((step, pending)) = &self.sync_request {
+            let Some(outcome) = pending.poll() else {
+                return;
+            };
+            let step = *step;
+            self.sync_request = None;
+            match (step, outcome) {
+                (_, Err(err)) => {
+                    log::warn!("failed to sync: {err}");
+                    self.sync_status = SyncStatus::Failed(err);
+                }
+                (SyncStep::Pull, Ok(remote)) => self.pulled(ctx, remote),
+                (SyncStep::Push { modified }, Ok(_)) => {
+                    self.synced = modified;
+                    self.sync_status = SyncStatus::UpToDate;
+                    self.dirty = true;
+                    log::info!("pushed the synced data");
+                }
+            }
+            return;
+        }
+
+        if !self.sync.is_active() || self.sync_conflict.is_some() {
+            return;
+        }
+        let now = ctx.input(|i| i.time);
+        let next_sync = self.last_sync.map_or(now, |last| last + SYNC_INTERVAL);
+        let pending_changes = self.modified != self.synced;
+        if self.sync_requested || (pending_changes && now >= next_sync) {
+            self.sync_requested = false;
+            self.last_sync = Some(now);
+            self.sync_status = SyncStatus::Syncing;
+            self.sync_request = Some((SyncStep::Pull, sync::pull(&self.sync, ctx.clone())));
+        } else if pending_changes {
+            ctx.request_repaint_after(Duration::from_secs_f64(next_sync - now));
+        }
+    }
+
+    /// Last write wins when only one side changed since the last sync, otherwise the player is
+    /// asked which copy to keep.
+    fn pulled(&mut self, ctx: &egui::Context, remote: Option<String>) {
+        let remote = match remote.map(|text| ron::from_str::<SyncDocument>(&text)) {
+            Some(Ok(document)) => Some(document),
+            Some(Err(err)) => {
+                log::warn!("failed to read the synced data: {err}");
+                self.sync_status = SyncStatus::Failed(err.to_string());
+                return;
+            }
+            None => None,
+        };
+
+        self.track_changes();
+        let local_changed = self.modified != self.synced;
+        match remote {
+            Some(document) if document.modified != self.synced => {
+                if local_changed {
+                    self.sync_conflict = Some(document);
+                    self.sync_status = SyncStatus::Idle;
+                } else {
+                    self.apply_sync_document(document);
+                }
+            }
+            Some(_) if !local_changed => self.sync_status = SyncStatus::UpToDate,
+            _ => self.push(ctx),
+        }
+    }
+
+    fn push(&mut self, ctx: &egui::Context) {
+        self.track_changes();
+        if let Some(contents) = self.sync_document() {
+            self.sync_status = SyncStatus::Syncing;
+            self.sync_request = Some((
+                SyncStep::Push {
+                    modified: self.modified,
+                },
+                sync::push(&self.sync, contents, ctx.clone()),
+            ));
+        }
+    }
+
+    /// Add the current game to the history the first time it's found won.
+    fn record_win(&mut self, ctx: &egui::Context) {
+        let Some(state) = self
+            .current_game
+            .and_then(|index| self.games.get_mut(index))
+        else {
+            return;
+        };
+
+        if state.win_recorded {
+            return;
+        }
+        if let Some(play_count) = state.is_winning() {
+            log::info!("game won in {play_count} moves");
+            #[cfg(target_arch = "wasm32")]
+            embed::notify_win(play_count);
+            let config = state.config();
+            let finished = unix_time();
+            if let Some(day) = state.daily {
+                let best = self.daily.entry(day).or_insert(play_count);
+                *best = (*best).min(play_count);
+            }
+            if let Some((week, index)) = state.weekly {
+                self.weekly
+                    .entry(week)
+                    .or_default()
+                    .record(index, play_count);
+            }
+            if let Some(puzzle) = puzzle::Puzzle::of_game(state) {
+                self.favorites
+                    .iter_mut()
+                    .filter(|favorite| favorite.puzzle == puzzle)
+                    .for_each(|favorite| favorite.record(play_count));
+            }
+            insert_play_count(&mut self.history, config, play_count);
+            self.records.push(GameRecord {
+                column_count: config.column_count,
+                column_capacity: config.column_capacity,
+                empty_columns: config.empty_columns,
+                rules: config.rules,
+                mode: config.mode,
+                moves: play_count,
+                penalty: state.penalty,
+                hints: state.hints_used,
+                undos: state.undos,
+                par: state.par,
+                duration: Some(state.elapsed),
+                finished: Some(finished),
+                seed: state.seed,
+                note: String::new(),
+                move_list: state.moves.clone(),
+                verification: None,
+            });
+            state.win_recorded = true;
+            // already known when the game was restarted
+            if state.mode != Mode::Kid && state.par.is_none() && state.can_restart() {
+                let search = Search::start_optimal(state.initial_board(), ctx.clone());
+                self.par_searches
+                    .push_back((self.current_game, finished, search));
+            }
+            self.changes += 1;
+            self.dirty = true;
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn menu_bar_ui(&mut self, ctx: &egui::Context) {
+        let mut action = None;
+        let mut dark_mode = self.dark_mode;
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                let mut item = |ui: &mut egui::Ui, item: Action| {
+                    let mut button = egui::Button::new(item.label());
+                    if let Some(shortcut) = item.shortcut() {
+                        button = button.shortcut_text(ui.ctx().format_shortcut(shortcut));
+                    }
+                    if ui.add_enabled(self.is_enabled(item), button).clicked() {
+                        action = Some(item);
+                        ui.close_menu();
+                    }
+                };
+
+                ui.menu_button(Msg::GameMenu, |ui| {
+                    for action in [
+                        Action::NewGame,
+                        Action::Restart,
+                        Action::Pause,
+                        Action::Hint,
+                        Action::Reshuffle,
+                        Action::ExtraColumn,
+                        Action::SaveForLater,
+                        Action::Abort,
+                    ] {
+                        item(ui, action);
+                    }
+                    ui.separator();
+                    item(ui, Action::ExportCsv);
+                });
+                ui.menu_button(Msg::EditMenu, |ui| {
+                    item(ui, Action::Undo);
+                    item(ui, Action::Redo);
+                });
+                ui.menu_button(Msg::ViewMenu, |ui| {
+                    item(ui, Action::ZoomIn);
+                    item(ui, Action::ZoomOut);
+                    item(ui, Action::ResetZoom);
+                    item(ui, Action::MoveLog);
+                    #[cfg(feature = "puffin")]
+                    item(ui, Action::Profiler);
+                    ui.separator();
+                    ui.label(Msg::Theme);
+                    for (value, label) in [
+                        (None, Msg::ThemeSystem),
+                        (Some(false), Msg::ThemeLight),
+                        (Some(true), Msg::ThemeDark),
+                    ] {
+                        if ui.radio_value(&mut dark_mode, value, label).clicked() {
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.menu_button(Msg::HelpMenu, |ui| {
+                    item(ui, Action::HowToPlay);
+                    item(ui, Action::Shortcuts);
+                    item(ui, Action::About);
+                    item(ui, Action::LogPanel);
+                });
+            });
+        });
+
+        if dark_mode != self.dark_mode {
+            self.dark_mode = dark_mode;
+            self.changes += 1;
+            self.dirty = true;
+        }
+        if let Some(action) = action {
+            self.apply(ctx, action);
+        }
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        for (action, shortcut) in KEYBINDINGS {
+            if ctx.input_mut(|i| i.consume_shortcut(shortcut)) {
+                self.apply(ctx, *action);
+            }
+        }
+    }
+
+    fn help_windows(&mut self, ctx: &egui::Context) {
+        egui::Window::new(Msg::KeyboardShortcuts)
+            .open(&mut self.show_shortcuts)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                egui::Grid::new("shortcuts")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (action, shortcut) in KEYBINDINGS {
+                            ui.label(
+                                egui::RichText::new(ctx.format_shortcut(shortcut)).monospace(),
+                            );
+                            ui.label(action.label());
+                            ui.end_row();
+                        }
+                        for (gesture, action) in GESTURES {
+                            ui.label(*gesture);
+                            ui.label(*action);
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        egui::Window::new(Msg::HowToPlay)
+            .open(&mut self.show_how_to_play)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.set_max_width(300.0);
+                ui.label(Msg::HowToPlayText);
+            });
+
+        egui::Window::new(Msg::About)
+            .open(&mut self.show_about)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.strong(format!("boule {}", env!("CARGO_PKG_VERSION")));
+                ui.label(Msg::AboutText);
+                footer_ui(ui);
+            });
+    }
+
+    fn log_panel(ctx: &egui::Context) {
+        egui::TopBottomPanel::bottom("log_panel").show(ctx, |ui| {
+            egui::CollapsingHeader::new(Msg::LogPanel)
+                .default_open(true)
+                .show(ui, |ui| {
+                    let lines = logs::lines();
+                    if ui.small_button(Msg::CopyLog).clicked() {
+                        ui.output_mut(|output| output.copied_text = lines.join("\n"));
+                    }
+                    egui::ScrollArea::vertical()
+                        .max_height(150.0)
+                        .auto_shrink([false, true])
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            for line in &lines {
+                                ui.monospace(line);
+                            }
+                        });
+                });
+        });
+    }
+
+    /// Moves of the game being played, the undone ones last. Clicking one brings the board back
+    /// (or forward) to right after it, through undo and redo.
+    fn move_log_panel(&mut self, ctx: &egui::Context) {
+        let Some(state) = self.current() else {
+            return;
+        };
+        let played = state.moves.len();
+        let moves: Vec<_> = state
+            .moves
+            .iter()
+            .chain(state.undone_moves.iter().rev())
+            .copied()
+            .collect();
+        let enabled = !state.paused && state.is_winning().is_none();
+
+        let panel = if i18n::is_right_to_left() {
+            egui::SidePanel::left("move_log")
+        } else {
+            egui::SidePanel::right("move_log")
+        };
+        let mut target = None;
+        panel.resizable(false).show(ctx, |ui| {
+            egui::CollapsingHeader::new(Msg::MoveLog)
+                .default_open(true)
+                .show(ui, |ui| {
+                    ui.add_enabled_ui(enabled, |ui| {
+                        egui::ScrollArea::vertical()
+                            .auto_shrink([true, false])
+                            .show(ui, |ui| {
+                                if ui
+                                    .selectable_label(played == 0, Msg::StartPosition)
+                                    .clicked()
+                                {
+                                    target = Some(0);
+                                }
+                                for (index, mv) in moves.iter().enumerate() {
+                                    let mut text =
+                                        egui::RichText::new(format!("{}. {mv}", index + 1))
+                                            .monospace();
+                                    if index >= played {
+                                        text = text.weak();
+                                    }
+                                    if ui.selectable_label(index + 1 == played, text).clicked() {
+                                        target = Some(index + 1);
+                                    }
+                                }
+                            });
+                    });
+                });
+        });
+
+        if let Some(target) = target {
+            self.jump_to(ctx, target);
+        }
+    }
+
+    /// Bring the current game back (or forward) to `position` moves, through undo and redo so
+    /// that each step is journaled.
+    fn jump_to(&mut self, ctx: &egui::Context, position: usize) {
+        let Some(played) = self.current().map(|state| state.moves.len()) else {
+            return;
+        };
+        let action = if position < played {
+            Action::Undo
+        } else {
+            Action::Redo
+        };
+        for _ in 0..played.abs_diff(position) {
+            self.apply(ctx, action);
+        }
+    }
+
+    /// Figures worth asking for when a performance problem is reported. They aren't translated,
+    /// so that reports read the same whatever the language.
+    fn debug_overlay(&self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let frame_time = ctx.input(|i| i.stable_dt);
+        let repaint_causes = ctx.repaint_causes();
+        let slot_count = self
+            .current()
+            .map(|state| state.column_count * state.column_capacity);
+        let solver = match &self.hint_search {
+            Some((index, _)) => format!("searching (game {})", index + 1),
+            None => "idle".to_owned(),
+        };
+
+        egui::Area::new(egui::Id::new("debug_overlay"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, vec2(-8.0, -8.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    egui::Grid::new("debug_overlay_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            let mut row = |name: &str, value: String| {
+                                ui.label(name);
+                                ui.monospace(value);
+                                ui.end_row();
+                            };
+                            row(
+                                "FPS",
+                                format!("{:.0} ({:.1} ms)", 1.0 / frame_time, frame_time * 1e3),
+                            );
+                            if let Some(cpu_usage) = frame.info().cpu_usage {
+                                row("CPU", format!("{:.1} ms", cpu_usage * 1e3));
+                            }
+                            row(
+                                "Repaint",
+                                if repaint_causes.is_empty() {
+                                    "input".to_owned()
+                                } else {
+                                    format!("{repaint_causes:?}")
+                                },
+                            );
+                            row(
+                                "Slots",
+                                slot_count.map_or("-".to_owned(), |count| count.to_string()),
+                            );
+                            row("Solver", solver);
+                            row(
+                                "Storage",
+                                self.storage_size
+                                    .map_or("-".to_owned(), |size| format!("{size} B")),
+                            );
+                        });
+                });
+            });
+    }
+
+    fn has_unfinished_game(&self) -> bool {
+        self.current()
+            .is_some_and(|state| state.is_winning().is_none())
+    }
+
+    /// Open a new kid mode game and switch to it.
+    fn start_kid_game(&mut self) {
+        let color_count = if rand::random() { 3 } else { 4 };
+        log::info!("new kid game with {color_count} colors");
+        self.telemetry
+            .game_started(color_count, KID_COLUMN_CAPACITY);
+        let mut state = State::new(color_count + 1, KID_COLUMN_CAPACITY);
+        state.mode = Mode::Kid;
+        self.games.push(state);
+        self.current_game = Some(self.games.len() - 1);
+        self.show_setup = false;
+        self.changes += 1;
+        self.dirty = true;
+    }
+
+    fn handicap_allowed(&self) -> bool {
+        self.column_count.saturating_sub(1) * self.column_capacity >= HANDICAP_MIN_BALLS
+    }
+
+    /// Preset to play next, with why: a harder one when the last few games on the preset played
+    /// last were nearly all won, and efficiently if rated, or an easier one when most of them
+    /// were given up.
+    fn recommended_preset(&self) -> Option<(Preset, Msg)> {
+        let preset_of = |board| Preset::ALL.iter().copied().find(|p| p.board() == board);
+        let level = self
+            .recent
+            .iter()
+            .find_map(|game| preset_of(game.puzzle.board()))?;
+        let (colors, height) = level.board();
+
+        let outcomes: Vec<_> = self
+            .recent
+            .iter()
+            .filter(|game| game.puzzle.board() == level.board())
+            .map(|game| game.outcome)
+            .collect();
+        if outcomes.len() < RECOMMENDATION_GAMES {
+            return None;
+        }
+        let wins = outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome, recent::Outcome::Won(_)))
+            .count();
+
+        // stars of the last rated wins, unknown efficiency counting as good enough
+        let stars: Vec<_> = (self.records.iter().rev())
+            .filter(|record| record.board() == level.board())
+            .filter_map(GameRecord::stars)
+            .take(RECOMMENDATION_GAMES)
+            .collect();
+        let efficient =
+            stars.is_empty() || 3 * stars.iter().sum::<usize>() >= 2 * MAX_STARS * stars.len();
+
+        if 5 * wins >= 4 * outcomes.len() && efficient {
+            let harder = level.harder()?;
+            let (next_colors, next_height) = harder.board();
+            Some((
+                harder,
+                Msg::TryHarder {
+                    colors,
+                    height,
+                    next_colors,
+                    next_height,
+                },
+            ))
+        } else if 5 * wins < 2 * outcomes.len() {
+            let easier = level.easier()?;
+            let (next_colors, next_height) = easier.board();
+            Some((
+                easier,
+                Msg::TryEasier {
+                    colors,
+                    height,
+                    next_colors,
+                    next_height,
+                },
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Average solve times on the leaderboard of the presets and of `config`, along with their
+    /// number of scores, for those with enough scores to go by.
+    fn crowd_solve_times(
+        &mut self,
+        config: GameConfig,
+        ctx: &egui::Context,
+    ) -> HashMap<GameConfig, (f32, usize)> {
+        let mut solve_times = HashMap::new();
+        if !self.leaderboard.is_active() {
+            return solve_times;
+        }
+        let configs = Preset::ALL.iter().map(|preset| preset.config());
+        for config in configs.chain([config]) {
+            self.solve_stats.entry(config).or_insert_with(|| {
+                leaderboard::fetch_solves(&self.leaderboard, &config, ctx.clone())
+            });
+        }
+        for (&other, request) in &mut self.solve_stats {
+            if let Some(Ok(stats)) = request.poll() {
+                if stats.count >= CROWD_RATING_SCORES {
+                    if let Some(seconds) = stats.average_seconds.filter(|&s| s > 0.0) {
+                        solve_times.insert(other, (seconds, stats.count));
+                    }
+                }
+            }
+        }
+        solve_times
+    }
+
+    /// Presets in order of difficulty, each with the one whose label it goes by. Once players
+    /// solved all of them enough on the leaderboard, they are ordered and labeled by how long
+    /// that took, so that a preset playing harder than the next one is labeled as such.
+    fn calibrated_presets(&mut self, ctx: &egui::Context) -> Vec<(Preset, Preset)> {
+        let solve_times = self.crowd_solve_times(self.setup_config(), ctx);
+        let mut presets: Vec<_> = Preset::ALL
+            .iter()
+            .filter_map(|&preset| Some((preset, solve_times.get(&preset.config())?.0)))
+            .collect();
+        if presets.len() < Preset::ALL.len() {
+            return Preset::ALL.iter().map(|&preset| (preset, preset)).collect();
+        }
+        presets.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        presets
+            .into_iter()
+            .zip(Preset::ALL)
+            .map(|((preset, _), &label)| (preset, label))
+            .collect()
+    }
+
+    /// Preset games of `config` are as hard as, along with the leaderboard scores this was judged
+    /// by. Games are compared by how long players took to solve them when the leaderboard has
+    /// enough scores for them, and by their number of balls otherwise.
+    fn rated_difficulty(
+        &mut self,
+        config: GameConfig,
+        ctx: &egui::Context,
+    ) -> (Preset, Option<usize>) {
+        let closest = |measure: &dyn Fn(GameConfig) -> Option<f32>| {
+            let target = measure(config)?.ln();
+            Preset::ALL
+                .iter()
+                .filter_map(|&preset| {
+                    Some((preset, (measure(preset.config())?.ln() - target).abs()))
+                })
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(preset, _)| preset)
+        };
+
+        let solve_times = self.crowd_solve_times(config, ctx);
+        // the board is only compared to presets the crowd rated as well
+        let rated_presets = Preset::ALL
+            .iter()
+            .filter(|preset| solve_times.contains_key(&preset.config()))
+            .count();
+        if rated_presets >= 2 {
+            if let Some(preset) = closest(&|other| solve_times.get(&other).map(|&(s, _)| s)) {
+                let scores = solve_times.get(&config).map_or(0, |&(_, count)| count);
+                return (preset, Some(scores));
+            }
+        }
+
+        let preset = closest(&|config| Some((config.colors() * config.column_capacity) as f32));
+        (preset.unwrap_or(Preset::Easy), None)
+    }
+
+    /// Preset the setup screen is set to, if any.
+    fn preset(&self) -> Option<Preset> {
+        Preset::ALL.iter().copied().find(|preset| {
+            let (colors, height, handicap, rules) = preset.settings();
+            self.column_count == colors + 1
+                && self.column_capacity == height
+                && self.setup_config().is_handicapped() == handicap
+                && self.rules == rules
+        })
+    }
+
+    /// Configuration of the games started from the setup screen.
+    fn setup_config(&self) -> GameConfig {
+        let mut config = GameConfig::new(self.column_count, self.column_capacity);
+        config.rules = self.rules;
+        config.mode = self.variant;
+        if self.handicap && self.handicap_allowed() {
+            config = config.with_extra_empty_column();
+        }
+        config
+    }
+
+    /// Open a new game with the configured settings and switch to it.
+    fn start_new_game(&mut self) {
+        self.start_game(self.setup_config(), rand::random());
+    }
+
+    /// Open a new game set up by `config`, shuffled from `seed`, and switch to it.
+    fn start_game(&mut self, config: GameConfig, seed: u64) {
+        log::info!(
+            "new game with {} colors of height {}",
+            config.colors(),
+            config.column_capacity
+        );
+        self.telemetry
+            .game_started(config.colors(), config.column_capacity);
+        let state = State::from_config(config, seed);
+        log::debug!("initial board:\n{state}");
+        self.games.push(state);
+        self.current_game = Some(self.games.len() - 1);
+        self.show_setup = false;
+        self.changes += 1;
+        self.dirty = true;
+    }
+
+    fn select_game(&mut self, index: usize) {
+        let Some(game) = self.games.get(index) else {
+            return;
+        };
+
+        // default the setup screen to the configuration being played
+        self.column_count = game.color_count() + 1;
+        self.column_capacity = game.column_capacity;
+        self.handicap = game.config().is_handicapped();
+        if game.mode != Mode::Kid {
+            self.rules = game.rules;
+            self.variant = game.mode;
+        }
+        self.current_game = Some(index);
+        self.show_setup = false;
+        self.dirty = true;
+    }
+
+    fn remove_game(&mut self, index: usize) {
+        if index >= self.games.len() {
+            return;
+        }
+
+        let game = self.games.remove(index);
+        if game.is_winning().is_none() {
+            *self.abandoned.entry(game.config()).or_default() += 1;
+        }
+        if game.mode != Mode::Kid {
+            if let Some(game) = recent::RecentGame::of_game(&game) {
+                recent::push(&mut self.recent, game);
+            }
+        }
+        self.current_game = match self.current_game {
+            Some(current) if current == index => None,
+            Some(current) if current > index => Some(current - 1),
+            current => current,
+        };
+        // the hint of a closed game is of no use anymore
+        self.hint_search = self
+            .hint_search
+            .take()
+            .filter(|(game, _)| *game != index)
+            .map(|(game, search)| (if game > index { game - 1 } else { game }, search));
+        // the win is still rated for the record alone once its game is closed
+        for (game, ..) in &mut self.par_searches {
+            *game = match *game {
+                Some(game) if game == index => None,
+                Some(game) if game > index => Some(game - 1),
+                game => game,
+            };
+        }
+        // indices past the removed game are off by one now
+        self.score_submission = None;
+        self.global_stats = None;
+        self.challenge_copied = None;
+        self.changes += 1;
+        self.dirty = true;
+    }
+
+    fn close_current_game(&mut self) {
+        if let Some(index) = self.current_game {
+            self.remove_game(index);
+        }
+    }
+
+    fn resume_prompt_ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            let Some(state) = self.current() else {
+                self.resume_prompt = false;
+                return;
+            };
+
+            ui.add_space(24.0);
+            ui.strong(Msg::ResumePrompt {
+                colors: state.color_count(),
+                height: state.column_capacity,
+                moves: state.play_count,
+            });
+            ui.add_space(12.0);
+
+            if ui
+                .button(egui::RichText::from(Msg::Resume).strong())
+                .clicked()
+            {
+                self.resume_prompt = false;
+            }
+            if ui.button(Msg::NewGame).clicked() {
+                self.resume_prompt = false;
+                self.show_setup = true;
+            }
+            if ui.button(Msg::SaveForLater).clicked() {
+                self.resume_prompt = false;
+                self.apply(ui.ctx(), Action::SaveForLater);
+            }
+
+            footer_ui(ui);
+        });
+    }
+
+    /// Offer to export the stored data that couldn't be read, without getting in the way.
+    fn corrupt_data_banner(&mut self, ctx: &egui::Context) {
+        if self.corrupt_data.is_empty() {
+            return;
+        }
+
+        let (mut export, mut dismiss) = (false, false);
+        egui::TopBottomPanel::top("corrupt_data").show(ctx, |ui| {
+            ui.with_layout(i18n::horizontal_layout(), |ui| {
+                let errors: Vec<_> = self
+                    .corrupt_data
+                    .iter()
+                    .map(|data| format!("{}: {}", data.key, data.error))
+                    .collect();
+                ui.colored_label(ui.visuals().warn_fg_color, Msg::CorruptData)
+                    .on_hover_text(errors.join("\n"));
+                export = ui
+                    .button(Msg::ExportCorruptData)
+                    .on_hover_text(Msg::CorruptDataNote)
+                    .clicked();
+                dismiss = ui.button(Msg::Dismiss).clicked();
+            });
+        });
+
+        if export {
+            self.export_corrupt_data();
+        }
+        if export || dismiss {
+            self.corrupt_data.clear();
+        }
+    }
+
+    /// Write the stored data that couldn't be read next to the save file on desktop, or download
+    /// it on the web.
+    fn export_corrupt_data(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(dir) = self
+                .save_file_path()
+                .and_then(|path| Some(path.parent()?.to_owned()))
+            else {
+                return;
+            };
+            let result = self.corrupt_data.iter().try_for_each(|data| {
+                let path = dir.join(data.file_name());
+                std::fs::write(&path, &data.contents).inspect_err(|err| {
+                    log::warn!("failed to write {}: {err}", path.display());
+                })
+            });
+            self.exported_corrupt_data = Some(result.map(|()| dir).map_err(|err| err.to_string()));
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        for data in &self.corrupt_data {
+            if let Err(err) = export::download(&data.file_name(), "text/plain", &data.contents) {
+                log::warn!("failed to download {}: {err:?}", data.key);
+            }
+        }
+    }
+
+    /// Keep a copy of the stored data that couldn't be read next to it, before it's saved over.
+    fn stash_corrupt_data(&mut self, storage: &mut dyn Storage) {
+        for data in self.corrupt_data.iter_mut().filter(|data| !data.stashed) {
+            match data.store {
+                DataStore::Storage => storage.set_string(&data.stash_key(), data.contents.clone()),
+                #[cfg(target_arch = "wasm32")]
+                DataStore::IndexedDb => bulk_storage::save(data.stash_key(), data.contents.clone()),
+                #[cfg(not(target_arch = "wasm32"))]
+                DataStore::SaveFile => {}
+            }
+            data.stashed = true;
+        }
+    }
+
+    fn crash_dialog(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.crash_report else {
+            return;
+        };
+
+        let (mut copy, mut close) = (false, false);
+        egui::Window::new(Msg::Crashed)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                if self.game_recovered {
+                    ui.label(Msg::GameRecovered);
+                    ui.add_space(6.0);
+                }
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    copy = ui.button(Msg::CopyReport).clicked();
+                    close = ui.button(Msg::Ok).clicked();
+                });
+            });
+
+        if copy {
+            ctx.output_mut(|output| output.copied_text = report.clone());
+        }
+        if close {
+            self.crash_report = None;
+        }
+    }
+
+    /// Tell where the history was exported, or why it failed.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn exported_csv_dialog(&mut self, ctx: &egui::Context) {
+        exported_file_dialog(
+            ctx,
+            &mut self.exported_csv,
+            Msg::ExportCsv,
+            Msg::HistoryExported,
+        );
+    }
+
+    /// Ask which copy to keep when games changed both here and on the sync server.
+    fn sync_conflict_dialog(&mut self, ctx: &egui::Context) {
+        let Some(remote) = &self.sync_conflict else {
+            return;
+        };
+        let server_newer = remote.modified > self.modified;
+
+        let (mut keep_local, mut use_server) = (false, false);
+        egui::Window::new(Msg::SyncConflict)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(Msg::SyncConflictDetails { server_newer });
+                ui.add_space(6.0);
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    // the newer copy is the one that would win without a conflict
+                    let strong = |msg: Msg, newer: bool| {
+                        let text = egui::RichText::from(msg);
+                        if newer {
+                            text.strong()
+                        } else {
+                            text
+                        }
+                    };
+                    keep_local = ui.button(strong(Msg::KeepLocal, !server_newer)).clicked();
+                    use_server = ui.button(strong(Msg::UseServer, server_newer)).clicked();
+                });
+            });
+
+        if keep_local {
+            self.sync_conflict = None;
+            self.push(ctx);
+        } else if use_server {
+            if let Some(document) = self.sync_conflict.take() {
+                self.apply_sync_document(document);
+            }
+        }
+    }
+
+    /// Ask what to do with the unfinished game when starting a new one.
+    fn new_game_dialog(&mut self, ctx: &egui::Context) {
+        let Some(state) = self.current() else {
+            self.confirm_new_game = false;
+            return;
+        };
+        let details = Msg::UnfinishedGameDetails {
+            colors: state.color_count(),
+            height: state.column_capacity,
+            moves: state.play_count,
+        };
+
+        let (mut resume, mut overwrite, mut stash, mut cancel) = (false, false, false, false);
+        egui::Window::new(Msg::UnfinishedGame)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(details);
+                ui.add_space(6.0);
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    resume = ui.button(Msg::Resume).clicked();
+                    overwrite = ui.button(Msg::Overwrite).clicked();
+                    stash = ui.button(Msg::SaveForLater).clicked();
+                    cancel = ui.button(Msg::Cancel).clicked();
+                });
+            });
+
+        if resume {
+            if let Some(index) = self.current_game {
+                self.select_game(index);
+            }
+        } else if overwrite {
+            self.close_current_game();
+            self.start_new_game();
+        } else if stash {
+            // the unfinished game stays open in its tab
+            self.start_new_game();
+        }
+
+        if resume || overwrite || stash || cancel {
+            self.confirm_new_game = false;
+        }
+    }
+
+    /// Ask for confirmation before abandoning the current game.
+    fn abort_dialog(&mut self, ctx: &egui::Context) {
+        let Some(state) = self.current() else {
+            self.confirm_abort = false;
+            return;
+        };
+        let moves_played = Msg::MovesPlayed(state.play_count);
+
+        let (mut abandon, mut stash, mut cancel) = (false, false, false);
+        egui::Window::new(Msg::AbandonGame)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(moves_played);
+                ui.add_space(6.0);
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    abandon = ui.button(Msg::Abandon).clicked();
+                    stash = ui.button(Msg::SaveForLater).clicked();
+                    cancel = ui.button(Msg::Cancel).clicked();
+                });
+            });
+
+        if abandon {
+            self.close_current_game();
+        } else if stash {
+            self.apply(ctx, Action::SaveForLater);
+        }
+
+        if abandon || stash || cancel {
+            self.confirm_abort = false;
+        }
+    }
+
+    /// List the open games other than the current one.
+    fn open_games_ui(&mut self, ui: &mut egui::Ui) {
+        if self.games.len() == usize::from(self.current_game.is_some()) {
+            return;
+        }
+
+        ui.add_space(12.0);
+        ui.strong(Msg::OtherGames);
+
+        let mut resume = None;
+        let mut delete = None;
+        for (index, game) in self.games.iter().enumerate() {
+            if Some(index) == self.current_game {
+                continue;
+            }
+
+            ui.with_layout(i18n::horizontal_layout(), |ui| {
+                ui.label(game.summary());
+                if ui.small_button(Msg::Resume).clicked() {
+                    resume = Some(index);
+                }
+                if ui.small_button(Msg::Delete).clicked() {
+                    delete = Some(index);
+                }
+            });
+        }
+
+        if let Some(index) = resume {
+            self.select_game(index);
+        } else if let Some(index) = delete {
+            self.remove_game(index);
+        }
+    }
+
+    /// Show the statistics in their own OS window where supported (embedded otherwise).
+    fn statistics_viewport(&mut self, ctx: &egui::Context) {
+        let title = format!("boule — {}", Msg::Statistics);
+        let mut remix = None;
+        let mut noted = false;
+        let outcomes = self.outcomes();
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("statistics"),
+            egui::ViewportBuilder::default()
+                .with_title(&title)
+                .with_inner_size([400.0, 560.0]),
+            |ctx, class| {
+                if class == egui::ViewportClass::Embedded {
+                    egui::Window::new(title)
+                        .open(&mut self.show_statistics)
+                        .show(ctx, |ui| {
+                            remix = Self::statistics_ui(
+                                &self.history,
+                                &outcomes,
+                                &self.play_time,
+                                &mut self.records,
+                                &mut self.history_filter,
+                                &mut noted,
+                                ui,
+                            );
+                        });
+                } else {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        egui::ScrollArea::vertical()
+                            .auto_shrink(false)
+                            .show(ui, |ui| {
+                                remix = Self::statistics_ui(
+                                    &self.history,
+                                    &outcomes,
+                                    &self.play_time,
+                                    &mut self.records,
+                                    &mut self.history_filter,
+                                    &mut noted,
+                                    ui,
+                                );
+                            });
+                    });
+
+                    if ctx.input(|i| i.viewport().close_requested()) {
+                        self.show_statistics = false;
+                    }
+                }
+            },
+        );
+
+        if noted {
+            self.changes += 1;
+            self.dirty = true;
+        }
+        if let Some(game) = remix {
+            log::info!("remixed a past game");
+            self.games.push(game);
+            self.current_game = Some(self.games.len() - 1);
+            self.show_setup = false;
+            self.changes += 1;
+            self.dirty = true;
+        }
+    }
+
+    /// How the games of `config` went.
+    fn outcome(&self, config: GameConfig) -> Outcomes {
+        Outcomes {
+            won: self.history.get(&config).map_or(0, Vec::len),
+            abandoned: self.abandoned.get(&config).copied().unwrap_or_default(),
+            open: self
+                .games
+                .iter()
+                .filter(|game| game.config() == config && game.is_winning().is_none())
+                .count(),
+        }
+    }
+
+    /// How the games went for each game configuration.
+    fn outcomes(&self) -> HashMap<GameConfig, Outcomes> {
+        let mut outcomes: HashMap<_, Outcomes> = HashMap::new();
+        for (&config, play_counts) in &self.history {
+            outcomes.entry(config).or_default().won = play_counts.len();
+        }
+        for (&config, &abandoned) in &self.abandoned {
+            outcomes.entry(config).or_default().abandoned = abandoned;
+        }
+        for game in self.games.iter().filter(|game| game.is_winning().is_none()) {
+            outcomes.entry(game.config()).or_default().open += 1;
+        }
+        outcomes
+    }
+
+    fn statistics_ui(
+        history: &History,
+        outcomes: &HashMap<GameConfig, Outcomes>,
+        play_time: &HashMap<GameConfig, Duration>,
+        records: &mut [GameRecord],
+        filter: &mut history::HistoryFilter,
+        dirty: &mut bool,
+        ui: &mut egui::Ui,
+    ) -> Option<State> {
+        if outcomes.is_empty() && play_time.is_empty() {
+            ui.label(Msg::NoStatistics);
+            return None;
+        }
+
+        ui.label(Msg::TimeSpent(play_time.values().sum()));
+        ui.add_space(6.0);
+
+        let mut configs: Vec<_> = outcomes.keys().chain(play_time.keys()).copied().collect();
+        configs.sort();
+        configs.dedup();
+
+        egui::Grid::new("statistics")
+            .striped(true)
+            .num_columns(6)
+            .show(ui, |ui| {
+                ui.strong(Msg::Board);
+                ui.strong(Msg::Started);
+                ui.strong(Msg::Wins);
+                ui.strong(Msg::Abandoned);
+                ui.strong(Msg::Best);
+                ui.strong(Msg::PlayTime);
+                ui.end_row();
+
+                for config in configs {
+                    let play_counts = history.get(&config);
+                    let outcome = outcomes.get(&config).copied().unwrap_or_default();
+                    let share = |count: usize| match outcome.percent_of_over(count) {
+                        Some(percent) => format!("{count} ({})", Msg::Percent(percent)),
+                        None => count.to_string(),
+                    };
+                    ui.label(config.label());
+                    ui.label(outcome.started().to_string());
+                    ui.label(share(outcome.won));
+                    ui.label(share(outcome.abandoned));
+                    match play_counts.and_then(|play_counts| play_counts.first()) {
+                        Some(best) => ui.label(Msg::Moves(*best)),
+                        None => ui.label("—"),
+                    };
+                    ui.label(format_duration(
+                        play_time.get(&config).copied().unwrap_or_default(),
+                    ));
+                    ui.end_row();
+                }
+            });
+
+        ui.add_space(12.0);
+        Self::won_games_ui(records, filter, dirty, ui)
+    }
+
+    /// Won games, filtered and sorted as the player chooses, with their notes, and the remix of
+    /// one of them if asked for.
+    fn won_games_ui(
+        records: &mut [GameRecord],
+        filter: &mut history::HistoryFilter,
+        dirty: &mut bool,
+        ui: &mut egui::Ui,
+    ) -> Option<State> {
+        ui.strong(Msg::WonGames);
+        ui.with_layout(i18n::horizontal_layout(), |ui| {
+            egui::ComboBox::from_id_source("history_period")
+                .selected_text(filter.period.label().to_string())
+                .show_ui(ui, |ui| {
+                    for &period in history::Period::ALL {
+                        ui.selectable_value(&mut filter.period, period, period.label());
+                    }
+                });
+
+            egui::ComboBox::from_id_source("history_mode")
+                .selected_text(filter.mode.map_or(Msg::AllModes, Mode::label).to_string())
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut filter.mode, None, Msg::AllModes);
+                    for &mode in Mode::ALL {
+                        ui.selectable_value(&mut filter.mode, Some(mode), mode.label());
+                    }
+                });
+
+            let mut boards: Vec<_> = records.iter().map(GameRecord::board).collect();
+            boards.sort();
+            boards.dedup();
+            let board_label = |(colors, height): (usize, usize)| format!("{colors}x{height}");
+            egui::ComboBox::from_id_source("history_board")
+                .selected_text(filter.board.map_or(Msg::AllBoards.to_string(), board_label))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut filter.board, None, Msg::AllBoards);
+                    for board in boards {
+                        ui.selectable_value(&mut filter.board, Some(board), board_label(board));
+                    }
+                });
+        });
+        ui.with_layout(i18n::horizontal_layout(), |ui| {
+            for &sort in history::SortOrder::ALL {
+                ui.selectable_value(&mut filter.sort, sort, sort.label());
+            }
+        });
+        ui.add_space(6.0);
+
+        let listed = filter.apply(records, unix_time());
+        if listed.is_empty() {
+            ui.label(Msg::NoMatchingGames);
+            return None;
+        }
+        let mut remix = None;
+        let row_height = ui.spacing().interact_size.y;
+        ui.push_id("won_games", |ui| {
+            egui_extras::TableBuilder::new(ui)
+                .striped(true)
+                .resizable(true)
+                .max_scroll_height(300.0)
+                .cell_layout(i18n::horizontal_layout().with_cross_align(egui::Align::Center))
+                .column(Column::auto().at_least(60.0))
+                .column(Column::auto().at_least(60.0))
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::initial(130.0).at_least(60.0).clip(true))
+                .column(Column::auto())
+                .header(row_height, |mut header| {
+                    header.col(|ui| {
+                        ui.strong(Msg::Board);
+                    });
+                    header.col(|ui| {
+                        ui.strong(Msg::SummaryMoves);
+                    });
+                    header.col(|ui| {
+                        ui.strong("⭐");
+                    });
+                    header.col(|ui| {
+                        ui.strong(Msg::SummaryTime);
+                    });
+                    header.col(|ui| {
+                        ui.strong(Msg::WonOn);
+                    });
+                    header.col(|ui| {
+                        ui.strong(Msg::Note);
+                    });
+                    header.col(|_| {});
+                })
+                .body(|body| {
+                    body.rows(row_height, listed.len(), |mut row| {
+                        let record = &mut records[listed[row.index()]];
+                        row.col(|ui| {
+                            ui.label(record.config().label());
+                        });
+                        row.col(|ui| {
+                            ui.with_layout(i18n::horizontal_layout(), |ui| {
+                                ui.label(Msg::Moves(record.moves));
+                                assists_ui(ui, record);
+                            });
+                        });
+                        row.col(|ui| {
+                            if let Some(stars) = record.stars() {
+                                stars_ui(ui, stars, 14.0);
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(record.duration.map_or("—".to_owned(), format_duration));
+                        });
+                        row.col(|ui| {
+                            ui.label(record.finished.map_or("—".to_owned(), export::format_date));
+                        });
+                        row.col(|ui| {
+                            *dirty |= note_ui(ui, &mut record.note).changed();
+                        });
+                        row.col(|ui| {
+                            verification_ui(ui, record);
+                            let Some(seed) = record.seed else {
+                                return;
+                            };
+                            if ui
+                                .small_button(Msg::Remix)
+                                .on_hover_text(Msg::RemixNote)
+                                .clicked()
+                            {
+                                remix = Some(State::remixed(record.config(), seed, rand::random()));
+                            }
+                        });
+                    });
+                });
+        });
+        remix
+    }
+
+    /// Fewest hints among the wins of `config` in `play_count` moves, none for wins that predate
+    /// records.
+    fn fewest_hints(&self, config: GameConfig, play_count: usize) -> usize {
+        let mut hints = self
+            .records
+            .iter()
+            .filter(|record| record.config() == config && record.moves == play_count)
+            .map(|record| record.hints)
+            .peekable();
+        if hints.peek().is_none() {
+            return 0;
+        }
+        hints.min().unwrap_or_default()
+    }
+
+    fn history_ui(&self, ui: &mut egui::Ui, config: GameConfig, this_play_count: Option<usize>) {
+        // rules and modes played on the same board, any of which can be listed instead
+        let mut variants: Vec<_> = self
+            .history
+            .keys()
+            .filter(|other| other.is_same_board(&config))
+            .copied()
+            .collect();
+        if variants.is_empty() {
+            return;
+        }
+        variants.push(config);
+        variants.sort();
+        variants.dedup();
+
+        let id = ui.id().with(("top_ten_variant", config));
+        let mut selected = ui.data_mut(|data| data.get_temp(id)).unwrap_or(config);
+        let ranking_id = ui.id().with("top_ten_ranking");
+        let mut ranking = ui.data_mut(|data| data.get_temp(ranking_id).unwrap_or_default());
+        let this_play_count = this_play_count.filter(|_| selected == config);
+        let history = self.history.get(&selected).map_or(&[][..], Vec::as_slice);
+
+        let width = 100.0.at_most(ui.available_width());
+        ui.allocate_ui(vec2(width, 0.0), |ui| {
+            ui.add_space(12.0);
+            egui::Frame {
+                stroke: ui.visuals().widgets.noninteractive.bg_stroke,
+                ..Default::default()
+            }
+            .show(ui, |ui| {
+                ui.add_space(6.0);
+                let top_ten = ui.strong(Msg::TopTen {
+                    colors: config.colors(),
+                    height: config.column_capacity,
+                    handicap: config.is_handicapped(),
+                });
+                if config.is_handicapped() {
+                    top_ten.on_hover_text(Msg::HandicapNote);
+                }
+                if variants.len() > 1 {
+                    ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+                        for &variant in &variants {
+                            ui.selectable_value(&mut selected, variant, variant.variant_label());
+                        }
+                    });
+                }
+                let outcome = self.outcome(selected);
+                ui.small(Msg::Attempts {
+                    attempts: outcome.started(),
+                    won_percent: outcome.percent_of_over(outcome.won).unwrap_or_default(),
+                });
+
+                ui.with_layout(i18n::horizontal_layout(), |ui| {
+                    ui.selectable_value(&mut ranking, Ranking::Moves, Msg::SummaryMoves);
+                    ui.selectable_value(&mut ranking, Ranking::Time, Msg::SummaryTime);
+                });
+                if ranking == Ranking::Time {
+                    self.fastest_wins_ui(ui, selected, this_play_count.is_some());
+                    return;
+                }
+
+                // where the game just won stands, tied with the wins in as many moves
+                let this_rank = this_play_count
+                    .map(|this| rank(history, this))
+                    .filter(|&rank| rank < 10);
+                if let (Some(rank), Some(this)) = (this_rank, this_play_count) {
+                    let place = match rank.checked_sub(1).and_then(|r| history.get(r)) {
+                        Some(&next) => Msg::TopTenPlace {
+                            rank: rank + 1,
+                            behind: this - next,
+                            behind_best: this - history.first().copied().unwrap_or(this),
+                        },
+                        None => Msg::TopTenBest,
+                    };
+                    ui.label(place);
+                }
+
+                ui.separator();
+
+                // every win tied for tenth place is in
+                let top_ten = history
+                    .iter()
+                    .map(|&play_count| (rank(history, play_count), play_count))
+                    .take_while(|&(rank, _)| rank < 10);
+                for (rank, play_count) in top_ten {
+                    let mut text = egui::RichText::from(Msg::Moves(play_count));
+                    if Some(play_count) == this_play_count {
+                        text = text.strong();
+                    }
+                    ui.with_layout(i18n::horizontal_layout(), |ui| {
+                        ui.label(egui::RichText::new(format!("#{}", rank + 1)).weak());
+                        ui.label(text);
+                        hinted_ui(ui, self.fewest_hints(selected, play_count));
+                    });
+                }
+
+                ui.add_space(6.0);
+            });
+        });
+        ui.data_mut(|data| {
+            data.insert_temp(id, selected);
+            data.insert_temp(ranking_id, ranking);
+        });
+    }
+
+    /// The ten fastest wins of `config`, the last one being the game just won if `just_won`.
+    fn fastest_wins_ui(&self, ui: &mut egui::Ui, config: GameConfig, just_won: bool) {
+        let this = self.records.last().filter(|_| just_won);
+        let mut wins: Vec<_> = self
+            .records
+            .iter()
+            .filter(|record| record.config() == config)
+            .filter_map(|record| Some((record.duration?, record)))
+            .collect();
+        wins.sort_by_key(|&(duration, _)| duration);
+
+        ui.separator();
+        for (index, (duration, record)) in wins.into_iter().take(10).enumerate() {
+            let mut text = egui::RichText::new(format_duration(duration));
+            if this.is_some_and(|this| std::ptr::eq(this, record)) {
+                text = text.strong();
+            }
+            ui.with_layout(i18n::horizontal_layout(), |ui| {
+                ui.label(egui::RichText::new(format!("#{}", index + 1)).weak());
+                ui.label(text);
+                hinted_ui(ui, record.hints);
+            });
+        }
+        ui.add_space(6.0);
+    }
+}
+
+/// What the top ten ranks wins by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Ranking {
+    #[default]
+    Moves,
+
+    /// Only wins whose time is known, as it wasn't kept by older versions.
+    Time,
+}
+
+/// Start `server` listening on `port`, or stop it if `None`, unless it already listens there or
+/// failed to.
+#[cfg(not(target_arch = "wasm32"))]
+fn listen_on<T>(
+    server: &mut Option<Result<T, (u16, String)>>,
+    port: Option<u16>,
+    port_of: impl Fn(&T) -> u16,
+    start: impl FnOnce(u16) -> std::io::Result<T>,
+) {
+    let current_port = server.as_ref().map(|server| match server {
+        Ok(server) => port_of(server),
+        Err((port, _)) => *port,
+    });
+    if current_port == port {
+        return;
+    }
+    // stopped first, should the port stay the same
+    *server = None;
+    *server = port.map(|port| {
+        start(port).map_err(|err| {
+            log::warn!("failed to listen on port {port}: {err}");
+            (port, err.to_string())
+        })
+    });
+}
+
+/// Game of a pasted challenge or puzzle, which can also be given as the contents of a puzzle
+/// file, or on desktop as its path.
+fn shared_game(text: &str) -> Option<State> {
+    if let Some(challenge) = challenge::Challenge::decode(text) {
+        return challenge.game();
+    }
+    let puzzle = puzzle::Puzzle::decode(text).or_else(|| puzzle::Puzzle::from_file(text));
+    #[cfg(not(target_arch = "wasm32"))]
+    let puzzle = puzzle.or_else(|| {
+        let path = std::path::Path::new(text.trim());
+        if path.extension()? != puzzle::FILE_EXTENSION {
+            return None;
+        }
+        std::fs::read_to_string(path)
+            .map_err(|err| log::warn!("failed to read {}: {err}", path.display()))
+            .ok()
+            .and_then(|contents| puzzle::Puzzle::from_file(&contents))
+    });
+    let puzzle = puzzle?;
+    log::info!(
+        "puzzle {:?} by {:?}, difficulty {:?}",
+        puzzle.name,
+        puzzle.author,
+        puzzle.difficulty
+    );
+    puzzle.game()
+}
+
+/// Each color with the number of its balls left to sort, the sorted colors being left out.
+fn color_summary_ui(ui: &mut egui::Ui, state: &State) {
+    ui.with_layout(i18n::horizontal_layout().with_main_wrap(true), |ui| {
+        for (color, unsorted) in state.unsorted_balls().into_iter().enumerate() {
+            if unsorted == 0 {
+                continue;
+            }
+            let (rect, _) = ui.allocate_exact_size(vec2(14.0, 14.0), Sense::hover());
+            Slot::Ball(color)
+                .color(ui.ctx())
+                .paint(ui.painter(), rect.center(), 0.5);
+            ui.small(unsorted.to_string());
+        }
+    });
+    ui.add_space(6.0);
+}
+
+/// Longest note on a board, in characters.
+const NOTE_LENGTH: usize = 80;
+
+/// Field for a short note on a board.
+fn note_ui(ui: &mut egui::Ui, note: &mut String) -> egui::Response {
+    let response = ui.add(
+        egui::TextEdit::singleline(note)
+            .hint_text(Msg::Note)
+            .char_limit(NOTE_LENGTH)
+            .desired_width(120.0),
+    );
+    // the field is too narrow for longer notes
+    if note.is_empty() {
+        response
+    } else {
+        response.on_hover_text(note.as_str())
+    }
+}
+
+/// Star toggling the board being played as a favorite. Returns whether it was clicked.
+fn favorite_button(ui: &mut egui::Ui, starred: bool) -> bool {
+    let hover = if starred {
+        Msg::RemoveFavorite
+    } else {
+        Msg::AddFavorite
+    };
+    ui.selectable_label(starred, if starred { "★" } else { "☆" })
+        .on_hover_text(hover)
+        .clicked()
+}
+
+/// Tell where a file was exported, or why it failed, until dismissed.
+#[cfg(not(target_arch = "wasm32"))]
+fn exported_file_dialog(
+    ctx: &egui::Context,
+    exported: &mut Option<Result<std::path::PathBuf, String>>,
+    title: Msg,
+    done: Msg,
+) {
+    let Some(result) = exported else {
+        return;
+    };
+
+    let mut close = false;
+    egui::Window::new(title)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            match result {
+                Ok(path) => {
+                    ui.label(done);
+                    ui.monospace(path.display().to_string());
+                }
+                Err(err) => {
+                    ui.label(Msg::ExportFailed);
+                    ui.monospace(err.as_str());
+                }
+            }
+            ui.add_space(6.0);
+            close = ui.button(Msg::Ok).clicked();
+        });
+
+    if close {
+        *exported = None;
+    }
+}
+
+/// Stars earned by a win, out of [`MAX_STARS`].
+fn stars_ui(ui: &mut egui::Ui, stars: usize, size: f32) {
+    ui.with_layout(i18n::horizontal_layout(), |ui| {
+        ui.spacing_mut().item_spacing.x = 0.0;
+        for star in 0..MAX_STARS {
+            let color = if star < stars {
+                egui::Color32::GOLD
+            } else {
+                ui.visuals().weak_text_color()
+            };
+            ui.label(egui::RichText::new("⭐").size(size).color(color));
+        }
+    })
+    .response
+    .on_hover_text(Msg::Stars(stars));
+}
+
+/// Icons for the help a win took, or a sparkle if it took none.
+fn assists_ui(ui: &mut egui::Ui, record: &GameRecord) {
+    hinted_ui(ui, record.hints);
+    if record.undos > 0 {
+        ui.label("↩").on_hover_text(Msg::UndoneWin(record.undos));
+    }
+    if record.penalty > 0 {
+        ui.label("➕")
+            .on_hover_text(Msg::PowerupWin(record.penalty));
+    }
+    if record.is_clean() {
+        ui.label("✨").on_hover_text(Msg::CleanWin);
+    }
+}
+
+/// Button replaying a win, then how that went.
+fn verification_ui(ui: &mut egui::Ui, record: &mut GameRecord) {
+    match record.verification {
+        None => {
+            if ui
+                .small_button(Msg::Verify)
+                .on_hover_text(Msg::VerifyNote)
+                .clicked()
+            {
+                record.verification = Some(record.verify());
+            }
+        }
+        Some(verification) => {
+            let icon = match verification {
+                Verification::Verified => egui::RichText::new("✔"),
+                Verification::Failed => egui::RichText::new("✖").color(ui.visuals().error_fg_color),
+                Verification::Unverifiable => egui::RichText::new("—").weak(),
+            };
+            ui.label(icon).on_hover_text(verification.label());
+        }
+    }
+}
+
+fn hinted_ui(ui: &mut egui::Ui, hints: usize) {
+    if hints > 0 {
+        ui.label("💡").on_hover_text(Msg::HintedWin(hints));
+    }
+}
+
+/// Returns whether the value changed.
+fn selectable_label_range(
+    ui: &mut egui::Ui,
+    range: std::ops::RangeInclusive<usize>,
+    value: &mut usize,
+) -> bool {
+    let mut changed = false;
+    let width = 400.0.at_most(ui.available_width());
+    ui.allocate_ui_with_layout(
+        vec2(width, 0.0),
+        i18n::horizontal_layout().with_main_wrap(true),
+        |ui| {
+            for i in range.into_iter() {
+                if ui.selectable_label(*value == i, format!("{}", i)).clicked() {
+                    changed |= *value != i;
+                    *value = i;
+                }
+            }
+        },
+    );
+    changed
+}
+
+fn footer_ui(ui: &mut egui::Ui) {
+    ui.add_space(20.0);
+    ui.hyperlink_to(
+        egui::RichText::from(Msg::MadeBy).weak(),
+        "https://x.com/abey79/",
+    );
+
+    ui.hyperlink_to(
+        egui::RichText::from(Msg::Source).weak(),
+        "https://github.com/abey79/boule/",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_history(text: &str) -> History {
+        deserialize_history(&mut ron::Deserializer::from_str(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn history_reads_legacy_board_sizes() {
+        let history = read_history("{(4, 3): [9, 5, 7], (7, 4): [20]}");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[&GameConfig::new(4, 3)], [5, 7, 9]);
+        assert_eq!(history[&GameConfig::new(7, 4)], [20]);
+    }
+
+    #[test]
+    fn history_reads_game_configs() {
+        let config = GameConfig {
+            rules: Rules::CountedUndo,
+            mode: Mode::Hex,
+            ..GameConfig::new(6, 4).with_extra_empty_column()
+        };
+        let mut written = History::new();
+        written.insert(config, vec![30, 12]);
+        written.insert(GameConfig::new(5, 3), vec![8]);
+
+        let history = read_history(&ron::to_string(&written).unwrap());
+        assert_eq!(history[&config], [12, 30]);
+        assert_eq!(history[&GameConfig::new(5, 3)], [8]);
+
+        // fields added since then take their defaults
+        let history = read_history("{(column_count: 5, column_capacity: 3): [8]}");
+        assert_eq!(history[&GameConfig::new(5, 3)], [8]);
+    }
+
+    #[test]
+    fn undo_and_redo_restore_the_board_under_every_rule_set() {
+        for rules in [Rules::Classic, Rules::CountedUndo, Rules::Scripted] {
+            let mut state = State::from_seed(7, 4, 11);
+            state.rules = rules;
+            state.refresh_cache();
+
+            let mut boards = vec![state.slots.clone()];
+            for step in 0..20 {
+                let moves = state.legal_moves();
+                if moves.is_empty() || state.is_winning().is_some() {
+                    break;
+                }
+                let mv = moves[step * 7 % moves.len()];
+                assert!(state.move_ball(mv.from, mv.to));
+                boards.push(state.slots.clone());
+            }
+            let played = state.moves.clone();
+            assert!(played.len() > 1, "{rules:?}");
+
+            for board in boards.iter().rev().skip(1) {
+                state.undo();
+                assert_eq!(&state.slots, board, "{rules:?}");
+            }
+            assert!(state.moves.is_empty());
+            assert!(!state.can_undo());
+
+            for board in boards.iter().skip(1) {
+                state.redo();
+                assert_eq!(&state.slots, board, "{rules:?}");
+            }
+            assert_eq!(state.moves, played, "{rules:?}");
+            assert!(!state.can_redo());
+        }
+    }
+}
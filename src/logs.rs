@@ -0,0 +1,64 @@
+//! Recent log records, kept in memory to be shown in the app where the console is out of reach
+//! (e.g. on the web).
+
+use std::{collections::VecDeque, sync::Mutex};
+
+/// Records kept, the oldest being dropped first.
+const CAPACITY: usize = 500;
+
+static RECORDS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Forwards records to another logger, keeping a copy of those of `level` or above.
+struct RingLogger {
+    inner: Box<dyn log::Log>,
+    level: log::LevelFilter,
+}
+
+impl log::Log for RingLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.level() <= self.level || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if record.level() <= self.level {
+            if let Ok(mut records) = RECORDS.lock() {
+                if records.len() == CAPACITY {
+                    records.pop_front();
+                }
+                records.push_back(format!(
+                    "[{} {}] {}",
+                    record.level(),
+                    record.target(),
+                    record.args()
+                ));
+            }
+        }
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Install `inner`, which is interested in records up to `inner_level`, as the logger, and keep
+/// records up to `level` for [`lines`].
+pub fn init(
+    inner: Box<dyn log::Log>,
+    inner_level: log::LevelFilter,
+    level: log::LevelFilter,
+) -> Result<(), log::SetLoggerError> {
+    log::set_boxed_logger(Box::new(RingLogger { inner, level }))?;
+    log::set_max_level(inner_level.max(level));
+    Ok(())
+}
+
+/// Kept records, oldest first.
+pub fn lines() -> Vec<String> {
+    RECORDS
+        .lock()
+        .map(|records| records.iter().cloned().collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,97 @@
+//! Local profiles, for players sharing a device. Each profile has its own games, history and
+//! settings, persisted under keys of its own.
+
+use eframe::Storage;
+
+/// Key of [`Profiles`] in eframe's storage.
+const STORAGE_KEY: &str = "__profiles__";
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct ProfileEntry {
+    pub id: u32,
+
+    /// Copied from the profile's [`crate::Profile`] when it's saved, to list profiles without
+    /// loading them.
+    pub nickname: String,
+    pub avatar_color: usize,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Profiles {
+    pub entries: Vec<ProfileEntry>,
+
+    /// Profile last played, loaded at launch.
+    pub active: u32,
+
+    /// Ids aren't reused, so that a new profile never finds the data of a removed one.
+    next_id: u32,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self {
+            entries: vec![ProfileEntry {
+                id: 0,
+                nickname: String::new(),
+                avatar_color: 0,
+            }],
+            active: 0,
+            next_id: 1,
+        }
+    }
+}
+
+impl Profiles {
+    pub fn load(storage: Option<&dyn Storage>) -> Self {
+        storage
+            .and_then(|storage| eframe::get_value(storage, STORAGE_KEY))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage) {
+        eframe::set_value(storage, STORAGE_KEY, self);
+    }
+
+    /// Add an empty profile, returning its id.
+    pub fn add(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(ProfileEntry {
+            id,
+            nickname: String::new(),
+            avatar_color: id as usize,
+        });
+        id
+    }
+
+    pub fn entry_mut(&mut self, id: u32) -> Option<&mut ProfileEntry> {
+        self.entries.iter_mut().find(|entry| entry.id == id)
+    }
+}
+
+/// Suffix of the keys and files of profile `id`. The first profile uses none, so that it keeps
+/// the data of versions without profiles.
+pub fn suffix(id: u32) -> String {
+    if id == 0 {
+        String::new()
+    } else {
+        format!("-{id}")
+    }
+}
+
+/// Key of the settings of profile `id` in eframe's storage.
+pub fn app_key(id: u32) -> String {
+    format!("__app__{}", suffix(id))
+}
+
+/// Key of the open games of profile `id`, in eframe's storage on desktop and in IndexedDB on the
+/// web.
+pub fn games_key(id: u32) -> String {
+    format!("__app_games__{}", suffix(id))
+}
+
+/// Key of the history of profile `id`, stored like its games.
+pub fn history_key(id: u32) -> String {
+    format!("__app_history__{}", suffix(id))
+}
@@ -0,0 +1,194 @@
+//! Puzzles to share: a starting arrangement along with its rules and a few optional details,
+//! packed into a string to paste, or saved as a `.boule` file. Unlike a challenge, there's no
+//! result to beat.
+//!
+//! The file is the puzzle written as RON:
+//!
+//! ```text
+//! (
+//!     column_count: 4,
+//!     column_capacity: 3,
+//!     slots: [1, 2, 0, 3, 3, 1, 0, 0, 2, 0, 1, 3],
+//!     rules: Classic,
+//!     name: "Warm-up",
+//!     author: "Ada",
+//!     difficulty: Some(1),
+//! )
+//! ```
+
+use base64::Engine as _;
+
+use crate::{Mode, Rules, State};
+
+/// Start of every puzzle string, so that it's recognizable once pasted.
+const PREFIX: &str = "boule-puzzle:";
+
+pub const FILE_EXTENSION: &str = "boule";
+
+/// Hardest difficulty, the easiest being 1.
+pub const MAX_DIFFICULTY: u8 = 5;
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Puzzle {
+    pub column_count: usize,
+    pub column_capacity: usize,
+
+    /// Starting arrangement as slot codes, column by column.
+    slots: Vec<u8>,
+
+    #[serde(default)]
+    rules: Rules,
+
+    #[serde(default)]
+    mode: Mode,
+
+    #[serde(default)]
+    pub name: String,
+
+    #[serde(default)]
+    pub author: String,
+
+    /// From 1 to [`MAX_DIFFICULTY`], as rated by the author.
+    #[serde(default)]
+    pub difficulty: Option<u8>,
+}
+
+impl Puzzle {
+    pub fn new(column_count: usize, column_capacity: usize, slots: Vec<u8>) -> Self {
+        Self {
+            column_count,
+            column_capacity,
+            slots,
+            rules: Rules::default(),
+            mode: Mode::default(),
+            name: String::new(),
+            author: String::new(),
+            difficulty: None,
+        }
+    }
+
+    /// Puzzle of the board `state` started from, under its rules and mode.
+    pub fn of_game(state: &State) -> Option<Self> {
+        let mut puzzle = Self::new(
+            state.base_column_count(),
+            state.column_capacity,
+            state.initial_codes()?,
+        );
+        puzzle.rules = state.rules;
+        puzzle.mode = state.mode;
+        Some(puzzle)
+    }
+
+    /// Colors and height.
+    pub fn board(&self) -> (usize, usize) {
+        let colors = self.slots.iter().copied().max().unwrap_or_default();
+        (usize::from(colors), self.column_capacity)
+    }
+
+    /// Colors and height, e.g. `6x4`.
+    pub fn board_label(&self) -> String {
+        let (colors, height) = self.board();
+        format!("{colors}x{height}")
+    }
+
+    /// The puzzle, ready to be played, if it holds together.
+    pub fn game(&self) -> Option<State> {
+        let mut state = State::from_codes(
+            self.column_count,
+            self.column_capacity,
+            self.mode.goal(),
+            &self.slots,
+        )?;
+        state.rules = self.rules;
+        state.mode = self.mode;
+        Some(state)
+    }
+
+    pub fn encode(&self) -> String {
+        let text = ron::to_string(self).unwrap_or_default();
+        let code = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(text);
+        format!("{PREFIX}{code}")
+    }
+
+    /// Puzzle from a pasted string, tolerating surrounding text and whitespace.
+    pub fn decode(text: &str) -> Option<Self> {
+        let code = text.split(PREFIX).nth(1)?.split_whitespace().next()?;
+        let text = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(code)
+            .ok()?;
+        ron::de::from_bytes(&text).ok()
+    }
+
+    /// Contents of the puzzle's file.
+    pub fn to_file(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).unwrap_or_default()
+    }
+
+    pub fn from_file(text: &str) -> Option<Self> {
+        ron::from_str(text)
+            .map_err(|err| log::debug!("not a puzzle file: {err}"))
+            .ok()
+    }
+
+    /// Name of the puzzle's file, from its name if it has one.
+    pub fn file_name(&self) -> String {
+        let name: String = self
+            .name
+            .trim()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect();
+        let name = if name.is_empty() { "puzzle" } else { &name };
+        format!("{name}.{FILE_EXTENSION}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle() -> Puzzle {
+        let mut puzzle = Puzzle::new(4, 3, vec![2, 1, 3, 3, 2, 1, 1, 3, 2, 0, 0, 0]);
+        puzzle.rules = Rules::CountedUndo;
+        puzzle.mode = Mode::Hex;
+        puzzle.name = "Warm-up".to_owned();
+        puzzle.author = "Ada".to_owned();
+        puzzle.difficulty = Some(2);
+        puzzle
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let puzzle = puzzle();
+        assert_eq!(Puzzle::decode(&puzzle.encode()), Some(puzzle.clone()));
+        let text = format!("Try this one: {} :)", puzzle.encode());
+        assert_eq!(Puzzle::decode(&text), Some(puzzle));
+        assert_eq!(Puzzle::decode("boule-puzzle:"), None);
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let puzzle = puzzle();
+        assert_eq!(Puzzle::from_file(&puzzle.to_file()), Some(puzzle));
+    }
+
+    #[test]
+    fn file_fields_default() {
+        let puzzle = Puzzle::from_file(
+            "(column_count: 4, column_capacity: 3, slots: [2, 1, 3, 3, 2, 1, 1, 3, 2, 0, 0, 0])",
+        )
+        .unwrap();
+        assert_eq!(puzzle, Puzzle::new(4, 3, puzzle.slots.clone()));
+    }
+
+    #[test]
+    fn game_keeps_rules_and_mode() {
+        let puzzle = puzzle();
+        let state = puzzle.game().unwrap();
+        assert_eq!((state.rules, state.mode), (puzzle.rules, puzzle.mode));
+
+        let of_game = Puzzle::of_game(&state).unwrap();
+        assert_eq!(of_game.slots, puzzle.slots);
+        assert_eq!((of_game.rules, of_game.mode), (puzzle.rules, puzzle.mode));
+    }
+}
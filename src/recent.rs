@@ -0,0 +1,56 @@
+//! Games played lately, won or given up, so that their boards can be tried again.
+
+use std::collections::VecDeque;
+
+use crate::{puzzle::Puzzle, State};
+
+/// Games kept, the oldest being dropped first.
+const MAX_RECENT: usize = 10;
+
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+pub enum Outcome {
+    /// Won in this many moves.
+    Won(usize),
+
+    /// Closed unfinished after this many moves.
+    Abandoned(usize),
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct RecentGame {
+    /// Starting arrangement and rules.
+    pub puzzle: Puzzle,
+
+    /// Seed the board was shuffled from, if it was, kept by retries.
+    pub seed: Option<u64>,
+
+    pub outcome: Outcome,
+}
+
+impl RecentGame {
+    pub fn of_game(state: &State) -> Option<Self> {
+        let outcome = match state.is_winning() {
+            Some(moves) => Outcome::Won(moves),
+            None => Outcome::Abandoned(state.play_count),
+        };
+        Some(Self {
+            puzzle: Puzzle::of_game(state)?,
+            seed: state.seed,
+            outcome,
+        })
+    }
+
+    /// The board, ready to be tried again.
+    pub fn game(&self) -> Option<State> {
+        let mut state = self.puzzle.game()?;
+        state.seed = self.seed;
+        Some(state)
+    }
+}
+
+/// Add a game to the front of `recent`, replacing any earlier game on the same board.
+pub fn push(recent: &mut VecDeque<RecentGame>, game: RecentGame) {
+    recent.retain(|other| other.puzzle != game.puzzle);
+    recent.push_front(game);
+    recent.truncate(MAX_RECENT);
+}
@@ -0,0 +1,79 @@
+//! Boards and moves streamed to a Rerun viewer, to inspect puzzle generation, hints, or a bug
+//! report over time.
+
+use std::collections::HashMap;
+
+use crate::{Slot, State, BALL_COLORS};
+
+pub struct Recording {
+    stream: rerun::RecordingStream,
+
+    /// Move count and board last logged for each game, by tab index.
+    logged: HashMap<usize, (usize, Vec<Slot>)>,
+}
+
+impl Recording {
+    /// Connect to a Rerun viewer, starting one if needed.
+    pub fn spawn() -> Option<Self> {
+        rerun::RecordingStreamBuilder::new(crate::APP_ID)
+            .spawn()
+            .map_err(|err| log::warn!("failed to start the Rerun viewer: {err}"))
+            .ok()
+            .map(|stream| Self {
+                stream,
+                logged: HashMap::new(),
+            })
+    }
+
+    /// Log the board of game `index` if it changed since last time, and the move that led to it.
+    pub fn log_game(&mut self, index: usize, state: &State) {
+        let previous_play_count = match self.logged.get(&index) {
+            Some((_, slots)) if *slots == state.slots => return,
+            Some((play_count, _)) => Some(*play_count),
+            None => None,
+        };
+        self.logged
+            .insert(index, (state.play_count, state.slots.clone()));
+
+        self.stream
+            .set_time_sequence("move", state.play_count as i64);
+
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+        let mut radii = Vec::new();
+        for col in 0..state.column_count {
+            for row in 0..state.column_capacity {
+                if let Slot::Ball(color_idx) = state.slot(row, col) {
+                    let color = BALL_COLORS[color_idx % BALL_COLORS.len()];
+                    positions.push((col as f32, -(row as f32)));
+                    colors.push(rerun::Color::from_rgb(color.r(), color.g(), color.b()));
+                    // second theme balls are drawn as rings in the app
+                    radii.push(if color_idx < BALL_COLORS.len() {
+                        0.4
+                    } else {
+                        0.25
+                    });
+                }
+            }
+        }
+        self.stream
+            .log(
+                format!("game/{index}/board"),
+                &rerun::Points2D::new(positions)
+                    .with_colors(colors)
+                    .with_radii(radii),
+            )
+            .ok();
+
+        let played = previous_play_count.is_some_and(|count| state.play_count > count);
+        if let Some(mv) = state.moves.last().filter(|_| played) {
+            self.log_text(index, &format!("move {mv}"));
+        }
+    }
+
+    pub fn log_text(&self, index: usize, text: &str) {
+        self.stream
+            .log(format!("game/{index}/log"), &rerun::TextLog::new(text))
+            .ok();
+    }
+}
@@ -0,0 +1,273 @@
+//! Opt-in remote control over HTTP, on this computer only, so that bots, accessibility tools or
+//! chat integrations can play while the app shows the game. Requests are answered with JSON:
+//!
+//! - `GET /get_state`: the board of the game being played (see
+//!   [`crate::broadcast::Snapshot`]),
+//! - `GET /legal_moves`: the moves that can be played, as pairs of source and destination columns,
+//! - `POST /apply_move`: play the move given as `{"from": 0, "to": 2}`, then the new board, the move
+//!   included.
+//!
+//! Requests are handed to the app, which answers them between frames. Those sent by web pages,
+//! which carry an `Origin` header, are turned down, and moves have to be posted as
+//! `application/json`, which pages can't send elsewhere without the browser asking first.
+
+use std::io::{BufRead as _, BufReader, Read as _, Write as _};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::Move;
+
+/// Port listened on unless set otherwise.
+pub const DEFAULT_PORT: u16 = 4718;
+
+/// How often the listener checks whether it should stop.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Clients have this long to send their request, and the app to answer it.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Larger request bodies are turned down.
+const MAX_BODY_SIZE: usize = 4096;
+
+/// Requests whose request line and headers are longer are turned down.
+const MAX_HEAD_SIZE: u64 = 8192;
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct RemoteSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+pub enum Command {
+    GetState,
+    LegalMoves,
+    ApplyMove(Move),
+}
+
+/// A command, to be answered with [`Request::reply`].
+pub struct Request {
+    pub command: Command,
+    reply: mpsc::Sender<Reply>,
+}
+
+impl Request {
+    pub fn reply(self, reply: Reply) {
+        // the client may have given up waiting
+        self.reply.send(reply).ok();
+    }
+}
+
+/// HTTP status and JSON body of a response.
+pub struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    pub fn json(value: &impl serde::Serialize) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self { status: 200, body },
+            Err(err) => Self::error(500, &err.to_string()),
+        }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "error": message }).to_string(),
+        }
+    }
+}
+
+/// Listening for requests, with the thread taking them.
+pub struct RemoteControl {
+    port: u16,
+    requests: mpsc::Receiver<Request>,
+
+    /// Dropped along with this, for the thread to stop.
+    _stop: mpsc::Sender<()>,
+}
+
+impl RemoteControl {
+    /// Listen on `port` on the loopback interface, waking `ctx` up for each request.
+    pub fn start(port: u16, ctx: egui::Context) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        listener.set_nonblocking(true)?;
+        let (sender, requests) = mpsc::channel();
+        let (stop, stopped) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("remote control".to_owned())
+            .spawn(move || serve(&listener, &sender, &stopped, &ctx))?;
+        log::info!("remote control listening on port {port}");
+        Ok(Self {
+            port,
+            requests,
+            _stop: stop,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// A request waiting to be answered, if any.
+    pub fn poll(&self) -> Option<Request> {
+        self.requests.try_recv().ok()
+    }
+}
+
+/// Answer connections one at a time, until `stopped` is disconnected.
+fn serve(
+    listener: &TcpListener,
+    requests: &mpsc::Sender<Request>,
+    stopped: &mpsc::Receiver<()>,
+    ctx: &egui::Context,
+) {
+    loop {
+        if let Err(mpsc::TryRecvError::Disconnected) = stopped.try_recv() {
+            return;
+        }
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(err) = answer(stream, requests, ctx) {
+                    log::warn!("failed to answer a remote control request: {err}");
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_INTERVAL);
+            }
+            Err(err) => log::warn!("failed to accept a remote control connection: {err}"),
+        }
+    }
+}
+
+/// Read a request from `stream`, have the app answer it, and write back the response.
+fn answer(
+    stream: TcpStream,
+    requests: &mpsc::Sender<Request>,
+    ctx: &egui::Context,
+) -> std::io::Result<()> {
+    // accepted sockets may inherit the listener's non-blocking mode
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+
+    let mut head = (&mut reader).take(MAX_HEAD_SIZE);
+    let mut request_line = String::new();
+    head.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default().to_owned();
+
+    let mut content_length = 0;
+    let mut content_type = String::new();
+    let mut has_origin = false;
+    let mut complete = request_line.ends_with('\n');
+    while complete {
+        let mut header = String::new();
+        head.read_line(&mut header)?;
+        // cut short by the size limit, or by the client
+        complete = header.ends_with('\n');
+        if header.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let (name, value) = (name.trim(), value.trim());
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().unwrap_or_default();
+        } else if name.eq_ignore_ascii_case("content-type") {
+            value.clone_into(&mut content_type);
+        } else if name.eq_ignore_ascii_case("origin") {
+            has_origin = true;
+        }
+    }
+
+    let reply = if !complete {
+        Reply::error(431, "request head too large")
+    } else if has_origin {
+        Reply::error(403, "requests from web pages aren't allowed")
+    } else if content_length > MAX_BODY_SIZE {
+        Reply::error(413, "request body too large")
+    } else {
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        match command(&method, &path, &content_type, &body) {
+            Ok(command) => {
+                let (reply, replied) = mpsc::channel();
+                requests
+                    .send(Request { command, reply })
+                    .map_err(|_| std::io::ErrorKind::BrokenPipe)?;
+                ctx.request_repaint();
+                replied
+                    .recv_timeout(TIMEOUT)
+                    .unwrap_or_else(|_| Reply::error(503, "the app didn't answer in time"))
+            }
+            Err(reply) => reply,
+        }
+    };
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        reply.status,
+        reason(reply.status),
+        reply.body.len(),
+        reply.body,
+    )?;
+    stream.flush()
+}
+
+/// Command asked for by a request, or the error to answer it with.
+fn command(method: &str, path: &str, content_type: &str, body: &[u8]) -> Result<Command, Reply> {
+    let is_json = content_type
+        .split(';')
+        .next()
+        .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case("application/json"));
+    match (method, path) {
+        ("GET", "/get_state") => Ok(Command::GetState),
+        ("GET", "/legal_moves") => Ok(Command::LegalMoves),
+        ("POST", "/apply_move") if !is_json => {
+            Err(Reply::error(415, "moves are posted as application/json"))
+        }
+        ("POST", "/apply_move") => serde_json::from_slice(body)
+            .map(Command::ApplyMove)
+            .map_err(|err| Reply::error(400, &err.to_string())),
+        (_, "/get_state" | "/legal_moves" | "/apply_move") => {
+            Err(Reply::error(405, "method not allowed"))
+        }
+        _ => Err(Reply::error(404, "no such endpoint")),
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
@@ -0,0 +1,115 @@
+//! Rules a game is played by: which moves are legal, when a column is finished and how moves are
+//! counted. Each of them is a [`RuleSet`], picked by the [`Rules`] saved with the game.
+//!
+//! The rules apply to any board, whatever its [`Topology`](crate::board::Topology) and
+//! [`Goal`](crate::board::Goal), which say where balls can go and what a finished column looks
+//! like.
+
+use crate::{board::PackedBoard, Msg};
+
+/// How moves are judged and counted under a set of rules. The defaults are the classic rules.
+pub trait RuleSet: Sync {
+    /// Whether a ball may go from `from_column` to `to_column` of `board`, the first having a
+    /// ball and the second room for it.
+    fn allows_move(&self, board: &PackedBoard, from_column: usize, to_column: usize) -> bool {
+        board.topology().connects(from_column, to_column)
+    }
+
+    /// Whether `column` of `board` is done with: empty, or full with every ball in place. The
+    /// board is won once every column is.
+    fn is_column_finished(&self, board: &PackedBoard, column: usize) -> bool {
+        let height = board.height(column);
+        height == 0 || (height == board.column_capacity() && board.base_run(column) == height)
+    }
+
+    /// Moves counted once a move is undone, `play_count` being counted before.
+    fn play_count_after_undo(&self, play_count: usize) -> usize {
+        play_count.saturating_sub(1)
+    }
+
+    /// Whether wins may go on the leaderboard, which replays them under the classic rules.
+    fn is_ranked(&self) -> bool {
+        true
+    }
+}
+
+/// A ball moves onto any column with room, and undoing a move takes it back.
+struct Classic;
+
+impl RuleSet for Classic {}
+
+/// As classic, except that undoing a move counts as one more move rather than one less.
+struct CountedUndo;
+
+impl RuleSet for CountedUndo {
+    fn play_count_after_undo(&self, play_count: usize) -> usize {
+        play_count + 1
+    }
+
+    fn is_ranked(&self) -> bool {
+        false
+    }
+}
+
+/// Rules of a script loaded at runtime (see [`crate::scripting`]), the classic rules standing in
+/// for whatever the script doesn't say, and for all of it in builds without scripting.
+struct Scripted;
+
+impl RuleSet for Scripted {
+    #[cfg(feature = "scripting")]
+    fn allows_move(&self, board: &PackedBoard, from_column: usize, to_column: usize) -> bool {
+        Classic.allows_move(board, from_column, to_column)
+            && crate::scripting::allows_move(board, from_column, to_column).unwrap_or(true)
+    }
+
+    #[cfg(feature = "scripting")]
+    fn is_column_finished(&self, board: &PackedBoard, column: usize) -> bool {
+        crate::scripting::is_column_finished(board, column)
+            .unwrap_or_else(|| Classic.is_column_finished(board, column))
+    }
+
+    /// Which script a game was played by isn't kept.
+    fn is_ranked(&self) -> bool {
+        false
+    }
+}
+
+/// Rules the balls move by, as saved with games and records. See [`RuleSet`] for what each of
+/// them does.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+pub enum Rules {
+    #[default]
+    Classic,
+    CountedUndo,
+    Scripted,
+}
+
+impl Rules {
+    pub fn label(self) -> Msg {
+        match self {
+            Rules::Classic => Msg::ClassicRules,
+            Rules::CountedUndo => Msg::CountedUndoRules,
+            Rules::Scripted => Msg::ScriptedRules,
+        }
+    }
+
+    pub fn rule_set(self) -> &'static dyn RuleSet {
+        match self {
+            Rules::Classic => &Classic,
+            Rules::CountedUndo => &CountedUndo,
+            Rules::Scripted => &Scripted,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Save file on desktop, where games and history are kept in plain sight rather than in eframe's
+//! storage, along with rotating backups.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Backups kept next to the save file, the oldest being dropped first.
+const BACKUP_COUNT: usize = 5;
+
+/// Save file of profile `id`, used unless another one is configured.
+pub fn default_path(id: u32) -> Option<PathBuf> {
+    let name = format!("boule-save{}.ron", crate::profiles::suffix(id));
+    eframe::storage_dir(crate::APP_ID).map(|dir| dir.join(name))
+}
+
+pub fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// `boule-save.ron.1` for the most recent backup, and so on.
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+    with_suffix(path, &format!(".{index}"))
+}
+
+/// Contents of the save file, or `None` if there is none yet.
+pub fn read(path: &Path) -> io::Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Write the save file through a temporary file, so that it's never left half written. With
+/// `rotate`, the previous save is kept as the most recent backup first.
+pub fn write(path: &Path, contents: &str, rotate: bool) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    if rotate && path.exists() {
+        for index in (1..BACKUP_COUNT).rev() {
+            let backup = backup_path(path, index);
+            if backup.exists() {
+                std::fs::rename(&backup, backup_path(path, index + 1))?;
+            }
+        }
+        std::fs::copy(path, backup_path(path, 1))?;
+    }
+
+    let temporary = with_suffix(path, ".tmp");
+    std::fs::write(&temporary, contents)?;
+    std::fs::rename(&temporary, path)
+}
@@ -0,0 +1,149 @@
+//! Custom rules written as [Rhai](https://rhai.rs) scripts, loaded at runtime and played as
+//! [`Rules::Scripted`](crate::rules::Rules::Scripted). A script may define either of these
+//! functions, the classic rules standing in for the one it doesn't:
+//!
+//! - `allows_move(board, from, to)`: whether a ball may go from column `from` to column `to`, the
+//!   first having a ball and the second room for it. Moves the layout of the board doesn't allow
+//!   are never legal.
+//! - `is_column_finished(board, column)`: whether the column is done with, the game being won
+//!   once every column is.
+//!
+//! The board has `column_count()`, `column_capacity()`, `height(column)`, `ball(column, level)`,
+//! the color of the ball at `level` counting from the bottom or -1 if there's none, and
+//! `base_run(column)`, the number of balls at the bottom of the column already in place. On a
+//! grid, the solver takes boards differing only by the order of their columns as the same, so
+//! rules shouldn't depend on it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use rhai::{Dynamic, Engine, FuncArgs, Scope, AST, INT};
+
+use crate::board::PackedBoard;
+
+/// Script calls give up after this many operations, so that a runaway script can't hang the app.
+const MAX_OPERATIONS: u64 = 100_000;
+
+struct Script {
+    engine: Engine,
+    ast: AST,
+    allows_move: bool,
+    is_column_finished: bool,
+}
+
+static SCRIPT: RwLock<Option<Script>> = RwLock::new(None);
+
+/// Whether a failing script was reported already, so that the log isn't flooded with it.
+static FAILURE_REPORTED: AtomicBool = AtomicBool::new(false);
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let column = |board: &PackedBoard, column: INT| {
+        usize::try_from(column)
+            .ok()
+            .filter(|&column| column < board.column_count())
+    };
+    engine
+        .register_type_with_name::<PackedBoard>("Board")
+        .register_fn("column_count", |board: &mut PackedBoard| {
+            board.column_count() as INT
+        })
+        .register_fn("column_capacity", |board: &mut PackedBoard| {
+            board.column_capacity() as INT
+        })
+        .register_fn("height", move |board: &mut PackedBoard, index: INT| {
+            column(board, index).map_or(0, |column| board.height(column) as INT)
+        })
+        .register_fn(
+            "ball",
+            move |board: &mut PackedBoard, index: INT, level: INT| {
+                let Some(column) = column(board, index) else {
+                    return -1;
+                };
+                usize::try_from(level)
+                    .ok()
+                    .filter(|&level| level < board.height(column))
+                    .map_or(-1, |level| board.ball(column, level) as INT)
+            },
+        )
+        .register_fn("base_run", move |board: &mut PackedBoard, index: INT| {
+            column(board, index).map_or(0, |column| board.base_run(column) as INT)
+        });
+    engine
+}
+
+/// Compile `source` and play scripted rules by it from now on, or tell what's wrong with it.
+pub fn load(source: &str) -> Result<(), String> {
+    let engine = engine();
+    let ast = engine.compile(source).map_err(|err| err.to_string())?;
+    let defines = |name: &str, params: usize| {
+        ast.iter_functions()
+            .any(|function| function.name == name && function.params.len() == params)
+    };
+    let script = Script {
+        allows_move: defines("allows_move", 3),
+        is_column_finished: defines("is_column_finished", 2),
+        engine,
+        ast,
+    };
+    if let Ok(mut loaded) = SCRIPT.write() {
+        *loaded = Some(script);
+    }
+    FAILURE_REPORTED.store(false, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Go back to the classic rules for scripted games.
+pub fn unload() {
+    if let Ok(mut loaded) = SCRIPT.write() {
+        *loaded = None;
+    }
+}
+
+pub fn is_loaded() -> bool {
+    SCRIPT.read().is_ok_and(|loaded| loaded.is_some())
+}
+
+/// What the script says of the move, if it has a say.
+pub fn allows_move(board: &PackedBoard, from_column: usize, to_column: usize) -> Option<bool> {
+    call(
+        |script| script.allows_move,
+        "allows_move",
+        (board.clone(), from_column as INT, to_column as INT),
+    )
+}
+
+/// What the script says of the column, if it has a say.
+pub fn is_column_finished(board: &PackedBoard, column: usize) -> Option<bool> {
+    call(
+        |script| script.is_column_finished,
+        "is_column_finished",
+        (board.clone(), column as INT),
+    )
+}
+
+/// Result of script function `name`, if the script defines it and it returns a boolean.
+fn call(defined: fn(&Script) -> bool, name: &str, args: impl FuncArgs) -> Option<bool> {
+    let loaded = SCRIPT.read().ok()?;
+    let script = loaded.as_ref().filter(|&script| defined(script))?;
+    let result = script
+        .engine
+        .call_fn::<Dynamic>(&mut Scope::new(), &script.ast, name, args)
+        .map_err(|err| err.to_string())
+        .and_then(|value| {
+            value
+                .as_bool()
+                .map_err(|type_name| format!("{name} returned {type_name}, not a bool"))
+        });
+    match result {
+        Ok(value) => Some(value),
+        Err(err) => {
+            if !FAILURE_REPORTED.swap(true, Ordering::Relaxed) {
+                log::warn!("rules script failed, falling back to the classic rules: {err}");
+            }
+            None
+        }
+    }
+}
@@ -0,0 +1,495 @@
+//! Search for a sequence of moves solving a board, used for hints, and for the fewest moves
+//! solving it, used for par.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc,
+};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{
+    board::{Goal, PackedBoard},
+    Move,
+};
+
+/// Positions explored before giving up.
+const MAX_NODES: usize = 2_000_000;
+
+/// How much the hint search favors positions that look close to solved over short move
+/// sequences. Larger is faster, but finds longer solutions. The optimal search doesn't weigh the
+/// estimate, so that the first solution it finds is a shortest one.
+const HEURISTIC_WEIGHT: usize = 2;
+
+/// Positions expanded together. On native, they are spread over all cores. The optimal search only
+/// takes positions of the same priority in a batch, so it gets less of the speedup.
+const BATCH_SIZE: usize = 64;
+
+/// Positions explored between checks for cancellation.
+#[cfg(not(target_arch = "wasm32"))]
+const NODES_PER_STEP: usize = 10_000;
+
+/// Positions explored per frame on the web, small enough to keep the page responsive.
+#[cfg(target_arch = "wasm32")]
+const NODES_PER_FRAME: usize = 2_000;
+
+pub enum Progress {
+    Searching,
+    Solved(Vec<Move>),
+    Failed(Unsolved),
+}
+
+/// Why a search ended without a solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unsolved {
+    /// Every reachable position was explored: there is no solution.
+    Impossible,
+    /// The search explored [`MAX_NODES`] positions without finding one.
+    GaveUp,
+}
+
+/// A position about to be added to the search, with what's computed about it ahead of time.
+struct Successor {
+    board: PackedBoard,
+    canonical: PackedBoard,
+    estimate: usize,
+    mv: Option<Move>,
+}
+
+impl Successor {
+    fn new(board: PackedBoard, mv: Option<Move>, optimal: bool) -> Self {
+        Self {
+            canonical: board.canonical(),
+            estimate: Solver::estimate(&board, optimal),
+            board,
+            mv,
+        }
+    }
+}
+
+struct Node {
+    board: PackedBoard,
+    parent: usize,
+    mv: Option<Move>,
+    depth: usize,
+}
+
+/// Best-first search, which can be run a few nodes at a time: weighted A* for a quick solution,
+/// or plain A* for a shortest one.
+pub struct Solver {
+    /// Whether the solution found has to be a shortest one.
+    optimal: bool,
+    nodes: Vec<Node>,
+    open: BinaryHeap<Reverse<(usize, usize)>>,
+    /// Fewest moves found to reach each position, keyed by canonical form so that positions
+    /// differing only by column order are explored once.
+    transpositions: HashMap<PackedBoard, usize>,
+}
+
+impl Solver {
+    /// Search for any solution, favoring a quick search over a short solution.
+    pub fn new(board: PackedBoard) -> Self {
+        Self::with_mode(board, false)
+    }
+
+    /// Search for a solution with the fewest moves, which can take much longer.
+    pub fn optimal(board: PackedBoard) -> Self {
+        Self::with_mode(board, true)
+    }
+
+    fn with_mode(board: PackedBoard, optimal: bool) -> Self {
+        let mut solver = Self {
+            optimal,
+            nodes: Vec::new(),
+            open: BinaryHeap::new(),
+            transpositions: HashMap::new(),
+        };
+        solver.push(Successor::new(board, None, optimal), 0, 0);
+        solver
+    }
+
+    /// Rough estimate of the remaining moves: every ball not resting on a run of its own color at
+    /// the bottom of a column has to move at least once. Only the longest run of each color counts,
+    /// since the others have to be taken apart. Unless `optimal`, color changes among the
+    /// misplaced balls are counted too, as they usually cost extra moves to untangle, though not
+    /// always: the estimate then overshoots, which the optimal search can't afford. Numbered balls
+    /// all count where they are once in place, and break wherever they aren't in sequence.
+    fn estimate(board: &PackedBoard, optimal: bool) -> usize {
+        if board.goal() == Goal::Numbers {
+            return (0..board.column_count())
+                .map(|column| {
+                    let (height, kept) = (board.height(column), board.base_run(column));
+                    let breaks = (kept.max(1)..height)
+                        .filter(|&level| {
+                            !optimal
+                                && board.ball(column, level) != board.ball(column, level - 1) + 1
+                        })
+                        .count();
+                    height - kept + breaks
+                })
+                .sum();
+        }
+
+        let mut longest_runs: HashMap<usize, (usize, usize)> = HashMap::new();
+        for column in 0..board.column_count() {
+            let run = board.base_run(column);
+            if run > 0 {
+                let longest = longest_runs
+                    .entry(board.ball(column, 0))
+                    .or_insert((run, column));
+                if run > longest.0 {
+                    *longest = (run, column);
+                }
+            }
+        }
+
+        (0..board.column_count())
+            .map(|column| {
+                let height = board.height(column);
+                let kept = if height > 0 && longest_runs[&board.ball(column, 0)].1 == column {
+                    board.base_run(column)
+                } else {
+                    0
+                };
+                let breaks = (kept.max(1)..height)
+                    .filter(|&level| {
+                        !optimal && board.ball(column, level) != board.ball(column, level - 1)
+                    })
+                    .count();
+                height - kept + breaks
+            })
+            .sum()
+    }
+
+    /// Whether the search already reached `canonical` in at most `depth` moves.
+    fn is_known(&self, canonical: &PackedBoard, depth: usize) -> bool {
+        self.transpositions
+            .get(canonical)
+            .is_some_and(|&known_depth| known_depth <= depth)
+    }
+
+    fn push(&mut self, successor: Successor, parent: usize, depth: usize) {
+        if self.is_known(&successor.canonical, depth) {
+            return;
+        }
+        self.transpositions.insert(successor.canonical, depth);
+
+        let weight = if self.optimal { 1 } else { HEURISTIC_WEIGHT };
+        let priority = depth + weight * successor.estimate;
+        self.open.push(Reverse((priority, self.nodes.len())));
+        self.nodes.push(Node {
+            board: successor.board,
+            parent,
+            mv: successor.mv,
+            depth,
+        });
+    }
+
+    /// Positions reachable in one move from the given node that weren't reached as quickly yet.
+    fn successors(&self, index: usize) -> Vec<Successor> {
+        let Node { board, depth, .. } = &self.nodes[index];
+        let mut successors = Vec::new();
+        let column_count = board.column_count();
+        for from in 0..column_count {
+            let uniform = board.base_run(from) == board.height(from);
+            // a completed column is never taken apart
+            if uniform && board.height(from) == board.column_capacity() {
+                continue;
+            }
+            for to in 0..column_count {
+                if !board.can_move(from, to) {
+                    continue;
+                }
+                // moving a ball off a single-color column into an empty one gains nothing
+                if uniform && board.height(to) == 0 {
+                    continue;
+                }
+                let mut next = board.clone();
+                next.apply_move(from, to);
+                let successor = Successor::new(next, Some(Move { from, to }), self.optimal);
+                if !self.is_known(&successor.canonical, depth + 1) {
+                    successors.push(successor);
+                }
+            }
+        }
+        successors
+    }
+
+    fn moves_to(&self, mut index: usize) -> Vec<Move> {
+        let mut moves = Vec::new();
+        while let Some(mv) = self.nodes[index].mv {
+            moves.push(mv);
+            index = self.nodes[index].parent;
+        }
+        moves.reverse();
+        moves
+    }
+
+    /// Expand up to `budget` positions.
+    ///
+    /// The most promising positions are taken in batches and expanded in parallel on native,
+    /// checking against the transposition table, which is only added to afterwards. The optimal
+    /// search stops a batch at the first position of a higher priority than the batch started
+    /// with, so that it never expands a position before one that could lead to a shorter solution.
+    pub fn step(&mut self, budget: usize) -> Progress {
+        profile_scope!("solver step");
+        let mut expanded = 0;
+        while expanded < budget {
+            let mut batch = Vec::new();
+            let mut lowest = None;
+            while batch.len() < BATCH_SIZE.min(budget - expanded) {
+                let Some(&Reverse((priority, index))) = self.open.peek() else {
+                    break;
+                };
+                if self.optimal && lowest.is_some_and(|lowest| priority > lowest) {
+                    break;
+                }
+                self.open.pop();
+                lowest.get_or_insert(priority);
+                if self.nodes[index].board.is_solved() {
+                    return Progress::Solved(self.moves_to(index));
+                }
+                batch.push(index);
+            }
+            if batch.is_empty() {
+                return Progress::Failed(Unsolved::Impossible);
+            }
+            if self.nodes.len() >= MAX_NODES {
+                return Progress::Failed(Unsolved::GaveUp);
+            }
+            expanded += batch.len();
+
+            let expand = |&index: &usize| {
+                profile_scope!("expand position");
+                self.successors(index)
+            };
+            #[cfg(not(target_arch = "wasm32"))]
+            let successors: Vec<_> = {
+                use rayon::prelude::*;
+                batch.par_iter().map(expand).collect()
+            };
+            #[cfg(target_arch = "wasm32")]
+            let successors: Vec<_> = batch.iter().map(expand).collect();
+
+            profile_scope!("merge batch");
+            for (index, successors) in batch.into_iter().zip(successors) {
+                let depth = self.nodes[index].depth + 1;
+                for successor in successors {
+                    self.push(successor, index, depth);
+                }
+            }
+        }
+
+        Progress::Searching
+    }
+}
+
+/// A solver run that doesn't block the UI. On native, it runs on a worker thread which stops
+/// when the search is dropped. On the web, it advances a little each time it's polled, and keeps
+/// requesting repaints until done.
+pub struct Search {
+    #[cfg(not(target_arch = "wasm32"))]
+    receiver: mpsc::Receiver<Result<Vec<Move>, Unsolved>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    cancelled: Arc<AtomicBool>,
+    #[cfg(not(target_arch = "wasm32"))]
+    paused: Arc<AtomicBool>,
+    #[cfg(not(target_arch = "wasm32"))]
+    worker: std::thread::Thread,
+
+    #[cfg(target_arch = "wasm32")]
+    solver: Solver,
+    #[cfg(target_arch = "wasm32")]
+    paused: bool,
+    #[cfg(target_arch = "wasm32")]
+    ctx: egui::Context,
+}
+
+impl Search {
+    /// Start solving `board`, repainting `ctx` once done.
+    pub fn start(board: PackedBoard, ctx: egui::Context) -> Self {
+        Self::run(Solver::new(board), ctx)
+    }
+
+    /// Start looking for the fewest moves solving `board`, repainting `ctx` once done.
+    pub fn start_optimal(board: PackedBoard, ctx: egui::Context) -> Self {
+        Self::run(Solver::optimal(board), ctx)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run(mut solver: Solver, ctx: egui::Context) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let paused = Arc::new(AtomicBool::new(false));
+
+        let worker = {
+            let cancelled = cancelled.clone();
+            let paused = paused.clone();
+            std::thread::spawn(move || {
+                let solution = loop {
+                    if cancelled.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    if paused.load(Ordering::Relaxed) {
+                        std::thread::park();
+                        continue;
+                    }
+                    match solver.step(NODES_PER_STEP) {
+                        Progress::Searching => {}
+                        Progress::Solved(moves) => break Ok(moves),
+                        Progress::Failed(unsolved) => break Err(unsolved),
+                    }
+                };
+                sender.send(solution).ok();
+                ctx.request_repaint();
+            })
+        };
+
+        Self {
+            receiver,
+            cancelled,
+            paused,
+            worker: worker.thread().clone(),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn run(solver: Solver, ctx: egui::Context) -> Self {
+        ctx.request_repaint();
+        Self {
+            solver,
+            paused: false,
+            ctx,
+        }
+    }
+
+    /// Stop (or resume) exploring positions, e.g. while the game is paused.
+    pub fn set_paused(&mut self, paused: bool) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.paused.swap(paused, Ordering::Relaxed) && !paused {
+            self.worker.unpark();
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.paused = paused;
+        }
+    }
+
+    /// The solution, once the search is over, or why none was found.
+    pub fn poll(&mut self) -> Option<Result<Vec<Move>, Unsolved>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.receiver.try_recv().ok();
+        #[cfg(target_arch = "wasm32")]
+        {
+            if self.paused {
+                return None;
+            }
+            match self.solver.step(NODES_PER_FRAME) {
+                Progress::Searching => {
+                    self.ctx.request_repaint();
+                    None
+                }
+                Progress::Solved(moves) => Some(Ok(moves)),
+                Progress::Failed(unsolved) => Some(Err(unsolved)),
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for Search {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.worker.unpark();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use super::*;
+    use crate::{Slot, State};
+
+    /// Three colors in columns of three balls, each column listed from the top, the last one
+    /// empty.
+    const CODES: [u8; 12] = [2, 1, 3, 3, 2, 1, 1, 3, 2, 0, 0, 0];
+
+    fn board() -> PackedBoard {
+        let state = State::from_codes(4, 3, Goal::Colors, &CODES).unwrap();
+        PackedBoard::from(&state)
+    }
+
+    fn solve(mut solver: Solver) -> Result<Vec<Move>, Unsolved> {
+        loop {
+            match solver.step(1_000) {
+                Progress::Searching => {}
+                Progress::Solved(moves) => return Ok(moves),
+                Progress::Failed(unsolved) => return Err(unsolved),
+            }
+        }
+    }
+
+    fn play(mut board: PackedBoard, moves: &[Move]) -> PackedBoard {
+        for mv in moves {
+            assert!(board.can_move(mv.from, mv.to), "illegal move {mv}");
+            board.apply_move(mv.from, mv.to);
+        }
+        board
+    }
+
+    /// Fewest moves solving `board`, by trying every move.
+    fn shortest(board: PackedBoard) -> Option<usize> {
+        let mut seen = HashSet::from([board.clone()]);
+        let mut queue = VecDeque::from([(board, 0)]);
+        while let Some((board, depth)) = queue.pop_front() {
+            if board.is_solved() {
+                return Some(depth);
+            }
+            for from in 0..board.column_count() {
+                for to in 0..board.column_count() {
+                    if board.can_move(from, to) {
+                        let mut next = board.clone();
+                        next.apply_move(from, to);
+                        if seen.insert(next.clone()) {
+                            queue.push_back((next, depth + 1));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn solves_a_small_board() {
+        let moves = solve(Solver::new(board())).unwrap();
+        assert!(play(board(), &moves).is_solved());
+    }
+
+    #[test]
+    fn optimal_search_finds_a_shortest_solution() {
+        let moves = solve(Solver::optimal(board())).unwrap();
+        assert!(play(board(), &moves).is_solved());
+        assert_eq!(Some(moves.len()), shortest(board()));
+    }
+
+    #[test]
+    fn solved_board_needs_no_moves() {
+        let codes = [1, 1, 1, 2, 2, 2, 3, 3, 3, 0, 0, 0];
+        let state = State::from_codes(4, 3, Goal::Colors, &codes).unwrap();
+        let moves = solve(Solver::optimal(PackedBoard::from(&state))).unwrap();
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    fn board_without_moves_is_impossible() {
+        let slots = vec![Slot::Ball(1), Slot::Ball(0), Slot::Ball(0), Slot::Ball(1)];
+        let state = State::with_slots(2, 2, slots);
+        let solution = solve(Solver::new(PackedBoard::from(&state)));
+        assert_eq!(solution, Err(Unsolved::Impossible));
+    }
+}
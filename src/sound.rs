@@ -0,0 +1,53 @@
+//! Sound effects, synthesized with the Web Audio API. There is no audio backend on desktop, where
+//! they are silent.
+
+/// Short rising arpeggio, for a won game.
+#[cfg(target_arch = "wasm32")]
+pub fn celebrate() {
+    // C5, E5, G5, C6
+    const NOTES: [f32; 4] = [523.25, 659.25, 783.99, 1046.5];
+    const NOTE_LENGTH: f64 = 0.12;
+
+    let played = AUDIO.with(|audio| -> Result<(), eframe::wasm_bindgen::JsValue> {
+        let mut audio = audio.borrow_mut();
+        if audio.is_none() {
+            *audio = Some(web_sys::AudioContext::new()?);
+        }
+        let Some(audio) = audio.as_ref() else {
+            return Ok(());
+        };
+
+        let start = audio.current_time();
+        for (index, frequency) in NOTES.into_iter().enumerate() {
+            let time = start + NOTE_LENGTH * index as f64;
+            let oscillator = audio.create_oscillator()?;
+            oscillator.set_type(web_sys::OscillatorType::Triangle);
+            oscillator.frequency().set_value(frequency);
+
+            // fade out each note, so they don't click
+            let gain = audio.create_gain()?;
+            gain.gain().set_value_at_time(0.2, time)?;
+            gain.gain()
+                .exponential_ramp_to_value_at_time(0.001, time + 2.0 * NOTE_LENGTH)?;
+
+            oscillator.connect_with_audio_node(&gain)?;
+            gain.connect_with_audio_node(&audio.destination())?;
+            oscillator.start_with_when(time)?;
+            oscillator.stop_with_when(time + 2.0 * NOTE_LENGTH)?;
+        }
+        Ok(())
+    });
+    if let Err(err) = played {
+        log::warn!("failed to play a sound: {err:?}");
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn celebrate() {}
+
+// browsers limit how many audio contexts a page may create
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static AUDIO: std::cell::RefCell<Option<web_sys::AudioContext>> =
+        const { std::cell::RefCell::new(None) };
+}
@@ -0,0 +1,85 @@
+//! Opt-in sync with a user-supplied endpoint, such as a file on a WebDAV server, so desktop and web
+//! sessions share progress. The synced document is read with `GET` and written with `PUT`.
+
+use std::sync::{Arc, Mutex};
+
+use base64::Engine as _;
+
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    pub enabled: bool,
+
+    /// URL of the synced document.
+    pub url: String,
+
+    /// Basic authentication is used if set. The password is stored as is, like the other
+    /// settings.
+    pub username: String,
+    pub password: String,
+}
+
+impl SyncSettings {
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.url.trim().is_empty()
+    }
+
+    fn request(&self, method: &str, body: Vec<u8>) -> ehttp::Request {
+        let mut request = ehttp::Request::get(self.url.trim());
+        request.method = method.to_owned();
+        request.body = body;
+        if !self.username.is_empty() {
+            let credentials = base64::engine::general_purpose::STANDARD
+                .encode(format!("{}:{}", self.username, self.password));
+            request
+                .headers
+                .insert("Authorization", format!("Basic {credentials}"));
+        }
+        request
+    }
+}
+
+/// Outcome of a request: the synced document, `None` inside if there is none yet, or what went
+/// wrong.
+pub type Outcome = Result<Option<String>, String>;
+
+/// Request in flight.
+pub struct PendingRequest(Arc<Mutex<Option<Outcome>>>);
+
+impl PendingRequest {
+    /// The outcome once the request is done.
+    pub fn poll(&self) -> Option<Outcome> {
+        self.0.lock().ok()?.take()
+    }
+}
+
+fn send(request: ehttp::Request, ctx: egui::Context) -> PendingRequest {
+    let result = Arc::new(Mutex::new(None));
+    let pending = PendingRequest(result.clone());
+    ehttp::fetch(request, move |response| {
+        let outcome = match response {
+            Ok(response) if response.status == 404 => Ok(None),
+            Ok(response) if response.ok => response
+                .text()
+                .map(|text| Some(text.to_owned()))
+                .ok_or_else(|| "the synced document is not text".to_owned()),
+            Ok(response) => Err(format!("{} {}", response.status, response.status_text)),
+            Err(err) => Err(err),
+        };
+        if let Ok(mut result) = result.lock() {
+            *result = Some(outcome);
+        }
+        ctx.request_repaint();
+    });
+    pending
+}
+
+/// Start reading the synced document, repainting `ctx` once done.
+pub fn pull(settings: &SyncSettings, ctx: egui::Context) -> PendingRequest {
+    send(settings.request("GET", Vec::new()), ctx)
+}
+
+/// Start replacing the synced document with `contents`, repainting `ctx` once done.
+pub fn push(settings: &SyncSettings, contents: String, ctx: egui::Context) -> PendingRequest {
+    send(settings.request("PUT", contents.into_bytes()), ctx)
+}
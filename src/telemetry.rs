@@ -0,0 +1,121 @@
+//! Opt-in anonymous usage statistics. Only aggregate counts are reported, namely how often each
+//! board size is played and won, to help decide which features matter.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::GameRecord;
+
+/// Endpoint reports are posted to, set at build time. Builds without one never send anything.
+const ENDPOINT: Option<&str> = option_env!("BOULE_TELEMETRY_URL");
+
+/// Reports are sent at most this often, in seconds.
+const REPORT_INTERVAL: u64 = 24 * 60 * 60;
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Telemetry {
+    pub enabled: bool,
+
+    /// Unix timestamp of when the player opted in, wins before it being left out of reports.
+    enabled_since: u64,
+
+    /// Games started since the player opted in, for each number of colors and height.
+    started: HashMap<(usize, usize), usize>,
+
+    /// Unix timestamp of the last report sent.
+    last_report: u64,
+}
+
+/// Everything a report contains.
+#[derive(serde::Serialize)]
+struct Report {
+    version: &'static str,
+    platform: &'static str,
+    boards: Vec<BoardReport>,
+}
+
+#[derive(serde::Serialize)]
+struct BoardReport {
+    colors: usize,
+    height: usize,
+    started: usize,
+    won: usize,
+}
+
+impl Telemetry {
+    /// Turn reports on or off, as the player opted at `now`.
+    pub fn set_enabled(&mut self, enabled: bool, now: u64) {
+        if enabled && !self.enabled {
+            self.enabled_since = now;
+            self.started.clear();
+        }
+        self.enabled = enabled;
+    }
+
+    /// Count a game started with `colors` of the given `height`, if the player opted in.
+    pub fn game_started(&mut self, colors: usize, height: usize) {
+        if self.enabled {
+            *self.started.entry((colors, height)).or_default() += 1;
+        }
+    }
+
+    /// The report exactly as it would be sent.
+    pub fn report(&self, records: &[GameRecord]) -> String {
+        let mut boards = BTreeMap::<(usize, usize), (usize, usize)>::new();
+        for (&config, &started) in &self.started {
+            boards.entry(config).or_default().0 = started;
+        }
+        let since_opt_in = records
+            .iter()
+            .filter(|record| record.finished.unwrap_or_default() >= self.enabled_since);
+        for record in since_opt_in {
+            let config = record.config();
+            boards
+                .entry((config.colors(), config.column_capacity))
+                .or_default()
+                .1 += 1;
+        }
+
+        let report = Report {
+            version: env!("CARGO_PKG_VERSION"),
+            platform: if cfg!(target_arch = "wasm32") {
+                "web"
+            } else {
+                std::env::consts::OS
+            },
+            boards: boards
+                .into_iter()
+                .map(|((colors, height), (started, won))| BoardReport {
+                    colors,
+                    height,
+                    started,
+                    won,
+                })
+                .collect(),
+        };
+        ron::ser::to_string_pretty(&report, ron::ser::PrettyConfig::default()).unwrap_or_default()
+    }
+
+    /// Send a report if enabled and the last one is old enough, returning whether one was sent.
+    pub fn report_if_due(&mut self, records: &[GameRecord], now: u64) -> bool {
+        let Some(endpoint) = ENDPOINT.filter(|_| self.enabled) else {
+            return false;
+        };
+        if now < self.last_report + REPORT_INTERVAL {
+            return false;
+        }
+        self.last_report = now;
+
+        let request = ehttp::Request::post(endpoint, self.report(records).into_bytes());
+        ehttp::fetch(request, |response| match response {
+            Ok(response) if response.ok => log::debug!("usage statistics sent"),
+            Ok(response) => log::warn!(
+                "failed to send usage statistics: {} {}",
+                response.status,
+                response.status_text
+            ),
+            Err(err) => log::warn!("failed to send usage statistics: {err}"),
+        });
+        true
+    }
+}
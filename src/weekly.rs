@@ -0,0 +1,84 @@
+//! Weekly challenge: five boards of increasing size, the same for everyone during an ISO 8601
+//! week, played for a cumulative score. Results of past weeks are kept as an archive.
+
+use crate::State;
+
+/// Colors and height of the puzzles of a week, from easiest to hardest.
+pub const PUZZLES: [(usize, usize); 5] = [(4, 4), (5, 5), (6, 6), (7, 7), (8, 8)];
+
+/// Weeks since the one of 1970-01-01, which starts on Monday 1969-12-29.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+pub struct Week(u64);
+
+impl Week {
+    /// Week of a Unix timestamp.
+    pub fn of(timestamp: u64) -> Self {
+        Self((timestamp / 86_400 + 3) / 7)
+    }
+
+    /// ISO 8601 week, e.g. `2024-W20`.
+    pub fn label(self) -> String {
+        // the week belongs to the year of its Thursday, the day of the epoch for the first week
+        let thursday = self.0 * 7;
+        let (year, month, day) = crate::export::civil_date(thursday * 86_400);
+        format!("{year}-W{:02}", day_of_year(year, month, day) / 7 + 1)
+    }
+
+    /// Seed of puzzle `index`, the same on every platform.
+    pub fn seed(self, index: usize) -> u64 {
+        crate::mix_seed(self.0 * PUZZLES.len() as u64 + index as u64)
+    }
+
+    /// Puzzle `index` of the week, ready to be played.
+    pub fn game(self, index: usize) -> State {
+        let (colors, height) = PUZZLES[index];
+        let mut state = State::from_seed(colors + 1, height, self.seed(index));
+        state.weekly = Some((self, index));
+        state
+    }
+}
+
+/// Days since January 1st, which is day 0.
+fn day_of_year(year: u64, month: u64, day: u64) -> u64 {
+    const DAYS_BEFORE_MONTH: [u64; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    DAYS_BEFORE_MONTH[(month - 1) as usize] + day - 1 + u64::from(leap && month > 2)
+}
+
+/// Points for solving puzzle `index` in `moves`: four per ball, less one per move, so that both
+/// larger boards and shorter solutions count for more.
+pub fn points(index: usize, moves: usize) -> usize {
+    let (colors, height) = PUZZLES[index];
+    (4 * colors * height).saturating_sub(moves)
+}
+
+/// Fewest moves each puzzle of a week was solved in.
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct WeekResults {
+    moves: [Option<usize>; PUZZLES.len()],
+}
+
+impl WeekResults {
+    pub fn moves(&self, index: usize) -> Option<usize> {
+        self.moves.get(index).copied().flatten()
+    }
+
+    /// Keep `moves` for puzzle `index` if it's the best so far.
+    pub fn record(&mut self, index: usize, moves: usize) {
+        if let Some(best) = self.moves.get_mut(index) {
+            *best = Some(best.map_or(moves, |best| best.min(moves)));
+        }
+    }
+
+    pub fn completed(&self) -> usize {
+        self.moves.iter().flatten().count()
+    }
+
+    pub fn score(&self) -> usize {
+        (0..PUZZLES.len())
+            .filter_map(|index| self.moves(index).map(|moves| points(index, moves)))
+            .sum()
+    }
+}
@@ -0,0 +1,90 @@
+//! Playable board as an egui widget, so that other egui applications can embed a game:
+//!
+//! ```no_run
+//! # use boule::{BouleBoard, State};
+//! # fn board_ui(ui: &mut egui::Ui) {
+//! let mut game = State::from_seed(7, 4, 42);
+//! ui.add(
+//!     BouleBoard::new(&mut game)
+//!         .on_move(|_, mv| log::info!("played {mv}"))
+//!         .on_win(|_, moves| log::info!("won in {moves} moves")),
+//! );
+//! # }
+//! ```
+
+use crate::{BoardView, Move, State};
+
+type MoveCallback<'a> = Box<dyn FnMut(&State, Move) + 'a>;
+type WinCallback<'a> = Box<dyn FnMut(&State, usize) + 'a>;
+
+/// Board of a game, played by clicking or dragging balls and drawn paused when the game is. The
+/// response is marked changed whenever the game changes.
+pub struct BouleBoard<'a> {
+    state: &'a mut State,
+    view: BoardView,
+    on_move: Option<MoveCallback<'a>>,
+    on_win: Option<WinCallback<'a>>,
+}
+
+impl<'a> BouleBoard<'a> {
+    pub fn new(state: &'a mut State) -> Self {
+        Self {
+            state,
+            view: BoardView::default(),
+            on_move: None,
+            on_win: None,
+        }
+    }
+
+    /// Draw the board as `view` says rather than columns left to right.
+    pub fn view(mut self, view: BoardView) -> Self {
+        self.view = view;
+        self
+    }
+
+    /// Call `callback` with the game and the move after each move played on the board.
+    pub fn on_move(mut self, callback: impl FnMut(&State, Move) + 'a) -> Self {
+        self.on_move = Some(Box::new(callback));
+        self
+    }
+
+    /// Call `callback` with the game and its move count once a move played on the board wins it.
+    pub fn on_win(mut self, callback: impl FnMut(&State, usize) + 'a) -> Self {
+        self.on_win = Some(Box::new(callback));
+        self
+    }
+}
+
+impl egui::Widget for BouleBoard<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let Self {
+            state,
+            view,
+            mut on_move,
+            mut on_win,
+        } = self;
+
+        if state.paused {
+            let mut inner = ui.scope(|ui| state.paused_ui(ui, view));
+            if inner.inner {
+                inner.response.mark_changed();
+            }
+            return inner.response;
+        }
+
+        let won = state.is_winning().is_some();
+        let mut inner = ui.scope(|ui| state.ui(ui, view));
+        if inner.inner {
+            inner.response.mark_changed();
+            if let (Some(callback), Some(&mv)) = (&mut on_move, state.moves.last()) {
+                callback(state, mv);
+            }
+            if let (Some(callback), Some(play_count)) = (&mut on_win, state.is_winning()) {
+                if !won {
+                    callback(state, play_count);
+                }
+            }
+        }
+        inner.response
+    }
+}